@@ -0,0 +1,77 @@
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use primes::is_prime_naive;
+use primes::wheel::{Wheel, WheelSize};
+
+const SIZES: [WheelSize; 4] = [
+    WheelSize::Mod6,
+    WheelSize::Mod30,
+    WheelSize::Mod210,
+    WheelSize::Mod2310,
+];
+
+fn bench_is_prime(c: &mut Criterion) {
+    let mut group = c.benchmark_group("is_prime");
+    let numbers: Vec<u32> = (4_000_000_000..4_000_001_000).collect();
+
+    group.bench_function("naive", |b| {
+        b.iter(|| {
+            numbers
+                .iter()
+                .filter(|&&n| is_prime_naive(black_box(n)))
+                .count()
+        })
+    });
+
+    for size in SIZES {
+        let wheel = Wheel::new(size);
+        group.bench_with_input(
+            BenchmarkId::new("wheel", wheel.modulus()),
+            &wheel,
+            |b, wheel| {
+                b.iter(|| {
+                    numbers
+                        .iter()
+                        .filter(|&&n| wheel.is_prime(black_box(n)))
+                        .count()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_n_primes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("n_primes_10000");
+
+    group.bench_function("naive", |b| {
+        b.iter(|| {
+            (2u32..)
+                .filter(|&n| is_prime_naive(n))
+                .take(black_box(10_000))
+                .count()
+        })
+    });
+
+    for size in SIZES {
+        let wheel = Wheel::new(size);
+        group.bench_with_input(
+            BenchmarkId::new("wheel", wheel.modulus()),
+            &wheel,
+            |b, wheel| {
+                b.iter(|| {
+                    wheel
+                        .candidates()
+                        .filter(|&n| wheel.is_prime(n))
+                        .take(black_box(10_000))
+                        .count()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_is_prime, bench_n_primes);
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+use std::net::TcpListener;
+use std::sync::Arc;
+
+use primes::server::{Limits, serve};
+use primes::table::{MAX_TABLE_LIMIT, PrimeTable};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+const DEFAULT_TABLE_LIMIT: u32 = 10_000_000;
+
+fn main() -> std::io::Result<()> {
+    // usage: primes-server [address] [table-limit]
+    let mut args = std::env::args().skip(1);
+    let addr = args.next().unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    let table_limit = match args.next() {
+        Some(limit) => match limit.parse::<u32>() {
+            Ok(limit) if limit <= MAX_TABLE_LIMIT => limit,
+            Ok(_) => {
+                eprintln!(
+                    "Table limit {} exceeds the maximum of {}",
+                    limit, MAX_TABLE_LIMIT
+                );
+                std::process::exit(2);
+            }
+            Err(_) => {
+                eprintln!("Invalid table limit: {}", limit);
+                std::process::exit(2);
+            }
+        },
+        None => DEFAULT_TABLE_LIMIT,
+    };
+
+    println!("Precomputing primes up to {}...", table_limit);
+    let table = Arc::new(PrimeTable::new(table_limit));
+    println!("{} primes in table", table.primes().len());
+
+    let listener = TcpListener::bind(&addr)?;
+    println!("Listening on http://{}", listener.local_addr()?);
+
+    serve(listener, table, Limits::default())
+}
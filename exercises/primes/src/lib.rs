@@ -0,0 +1,118 @@
+pub mod miller_rabin;
+pub mod server;
+pub mod table;
+pub mod verify;
+pub mod wheel;
+
+use wheel::DEFAULT_WHEEL;
+
+pub fn is_prime(n: u32) -> bool {
+    DEFAULT_WHEEL.is_prime(n)
+}
+
+// Plain trial division by every integer - kept as a reference for benchmarks
+pub fn is_prime_naive(n: u32) -> bool {
+    if n == 0 || n == 1 {
+        return false;
+    }
+
+    let mut is_prime = true;
+    for d in 2..=((n as f64).sqrt() as u32) {
+        if n.is_multiple_of(d) {
+            is_prime = false;
+            break;
+        }
+    }
+    is_prime
+}
+
+pub fn n_primes(n: u32) -> Vec<u32> {
+    DEFAULT_WHEEL
+        .candidates()
+        .filter(|&c| is_prime(c))
+        .take(n as usize)
+        .collect()
+}
+
+pub fn primes_in_range(range: std::ops::Range<u32>) -> Vec<u32> {
+    DEFAULT_WHEEL
+        .candidates_from(range.start)
+        .take_while(|&c| c < range.end)
+        .filter(|&c| is_prime(c))
+        .collect()
+}
+
+/////////////////////////////////////////////////////////
+#[cfg(test)]
+mod primes_tests {
+    use super::*;
+
+    #[test]
+    fn test_is_prime() {
+        assert!(!is_prime(0));
+        assert!(!is_prime(1));
+        assert!(is_prime(2));
+        assert!(is_prime(3));
+        assert!(is_prime(5));
+        assert!(is_prime(7));
+        assert!(is_prime(11));
+        assert!(is_prime(13));
+        assert!(is_prime(17));
+        assert!(is_prime(19));
+        assert!(!is_prime(4));
+        assert!(!is_prime(6));
+        assert!(!is_prime(8));
+        assert!(!is_prime(9));
+        assert!(!is_prime(10));
+        assert!(is_prime(113));
+    }
+
+    #[test]
+    fn test_n_primes() {
+        let primes = n_primes(0);
+        assert_eq!(primes, Vec::<u32>::new());
+
+        let primes = n_primes(10);
+        assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+
+        let primes = n_primes(20);
+        assert_eq!(
+            primes,
+            vec![
+                2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_prime_matches_naive() {
+        for n in 0..10_000 {
+            assert_eq!(is_prime(n), is_prime_naive(n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn test_primes_in_range() {
+        let primes = primes_in_range(2..100);
+
+        assert_eq!(
+            primes,
+            vec![
+                2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
+                83, 89, 97
+            ]
+        );
+
+        assert_eq!(primes_in_range(0..2), Vec::<u32>::new());
+        assert_eq!(primes_in_range(90..110), vec![97, 101, 103, 107, 109]);
+        assert_eq!(primes_in_range(10..10), Vec::<u32>::new());
+    }
+
+    // Bonus tests for the iterator implementation
+
+    // #[test]
+    // fn test_all_primes() {
+    //     // let primes = all_primes().take(10).collect::<Vec<u32>>();
+    //     // assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    // }
+}
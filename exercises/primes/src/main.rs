@@ -1,111 +1,114 @@
-fn main() {
-    println!("Primes!!!");
-
-    let primes = n_primes(20);
-
-    for p in primes {
-        println!("Prime: {}", p);
-    }
+use primes::n_primes;
+use primes::table::{MAX_TABLE_LIMIT, PrimeTable};
+use primes::verify::{algorithms, verify_pi, verify_random, verify_range};
+
+struct VerifyOptions {
+    exhaustive: u32,
+    random: usize,
+    seed: u64,
+    pi_max: u32,
 }
 
-pub fn is_prime(n: u32) -> bool {
-    if n == 0 || n == 1 {
-        return false;
-    }
-
-    let mut is_prime = true;
-    for d in 2..=((n as f64).sqrt() as u32) {
-        if n % d == 0 {
-            is_prime = false;
-            break;
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        VerifyOptions {
+            exhaustive: 100_000,
+            random: 1_000,
+            seed: 2025,
+            pi_max: 1_000_000,
         }
     }
-    is_prime
 }
 
-pub fn n_primes(n: u32) -> Vec<u32> {
-    let mut primes: Vec<u32> = Vec::with_capacity(n as usize);
-    let mut i: u32 = 2;
-
-    while primes.len() < n as usize {
-        if is_prime(i) {
-            primes.push(i);
+fn parse_verify_options(args: &[String]) -> Result<VerifyOptions, String> {
+    let mut options = VerifyOptions::default();
+    let mut args = args.iter();
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        let invalid = |_| format!("invalid value for {}: {}", flag, value);
+
+        match flag.as_str() {
+            "--exhaustive" => options.exhaustive = value.parse().map_err(invalid)?,
+            "--random" => options.random = value.parse().map_err(invalid)?,
+            "--seed" => options.seed = value.parse().map_err(invalid)?,
+            "--pi-max" => options.pi_max = value.parse().map_err(invalid)?,
+            _ => return Err(format!("unknown option: {}", flag)),
         }
-        i += 1;
     }
 
-    primes
+    Ok(options)
 }
 
-pub fn primes_in_range(range: std::ops::Range<u32>) -> Vec<u32> {
-    let mut primes = Vec::new();
-    for i in range {
-        if is_prime(i) {
-            primes.push(i);
-        }
+fn verify(options: VerifyOptions) -> bool {
+    // beyond the table the sieve falls back to trial division, so capping
+    // it trades speed for memory without changing any verdict
+    let table_limit = options.exhaustive.max(options.pi_max).max(65_536);
+    let table = PrimeTable::new(table_limit.min(MAX_TABLE_LIMIT));
+    let algorithms = algorithms(&table);
+    let names: Vec<&str> = algorithms.iter().map(|a| a.name.as_str()).collect();
+    println!("Algorithms: {}", names.join(", "));
+
+    let mut ok = true;
+
+    println!("Exhaustive check of 0..{}", options.exhaustive);
+    if let Some(disagreement) = verify_range(&algorithms, 0..options.exhaustive) {
+        println!("{}", disagreement);
+        ok = false;
     }
-    primes
-}
 
-
-/////////////////////////////////////////////////////////
-#[cfg(test)]
-mod primes_tests {
-    use super::*;
-
-    #[test]
-    fn test_is_prime() {
-        assert!(!is_prime(0));
-        assert!(!is_prime(1));
-        assert!(is_prime(2));
-        assert!(is_prime(3));
-        assert!(is_prime(5));
-        assert!(is_prime(7));
-        assert!(is_prime(11));
-        assert!(is_prime(13));
-        assert!(is_prime(17));
-        assert!(is_prime(19));
-        assert!(!is_prime(4));
-        assert!(!is_prime(6));
-        assert!(!is_prime(8));
-        assert!(!is_prime(9));
-        assert!(!is_prime(10));
-        assert!(is_prime(113));
+    println!(
+        "Random check of {} values (seed {})",
+        options.random, options.seed
+    );
+    if let Some(disagreement) = verify_random(&algorithms, options.random, options.seed) {
+        println!("{}", disagreement);
+        ok = false;
     }
 
-    #[test]
-    fn test_n_primes() {
-        let primes = n_primes(0);
-        assert_eq!(primes, Vec::new());
+    println!("pi(x) reference check up to {}", options.pi_max);
+    for mismatch in verify_pi(&algorithms, options.pi_max) {
+        println!("{}", mismatch);
+        ok = false;
+    }
 
-        let primes = n_primes(10);
-        assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    println!(
+        "{}",
+        if ok {
+            "All algorithms agree"
+        } else {
+            "Verification FAILED"
+        }
+    );
+    ok
+}
 
-        let primes = n_primes(20);
-        assert_eq!(
-            primes,
-            vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71]
-        );
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // usage: primes verify [--exhaustive N] [--random N] [--seed S] [--pi-max X]
+    if args.first().map(String::as_str) == Some("verify") {
+        match parse_verify_options(&args[1..]) {
+            Ok(options) => {
+                if !verify(options) {
+                    std::process::exit(1);
+                }
+            }
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(2);
+            }
+        }
+        return;
     }
 
-    #[test]
-    fn test_primes_in_range() {
-        let primes = primes_in_range(2..100);
-
-        assert_eq!(
-            primes,
-            vec![
-                2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
-                83, 89, 97
-            ]
-        );
-    }
+    println!("Primes!!!");
 
-    // Bonus tests for the iterator implementation
+    let primes = n_primes(20);
 
-    // #[test]
-    // fn test_all_primes() {
-    //     // let primes = all_primes().take(10).collect::<Vec<u32>>();
-    //     // assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
-    // }
+    for p in primes {
+        println!("Prime: {}", p);
+    }
 }
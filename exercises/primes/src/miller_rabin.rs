@@ -0,0 +1,79 @@
+// Deterministic Miller-Rabin: for n < 4_759_123_141 the witnesses 2, 7 and 61
+// are sufficient, which covers every u32.
+
+const WITNESSES: [u64; 3] = [2, 7, 61];
+
+fn pow_mod(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1;
+    base %= modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result
+}
+
+pub fn is_prime(n: u32) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in [2, 3, 5, 7, 61] {
+        if n == p {
+            return true;
+        }
+        if n.is_multiple_of(p) {
+            return false;
+        }
+    }
+
+    let n = n as u64;
+    let d_bits = (n - 1).trailing_zeros();
+    let d = (n - 1) >> d_bits;
+
+    'witness: for a in WITNESSES {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..d_bits {
+            x = x * x % n;
+            if x == n - 1 {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod miller_rabin_tests {
+    use super::*;
+    use crate::is_prime_naive;
+
+    #[test]
+    fn small_numbers_match_trial_division() {
+        for n in 0..20_000 {
+            assert_eq!(is_prime(n), is_prime_naive(n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn strong_pseudoprimes_are_rejected() {
+        // strong pseudoprimes to some of the witnesses
+        assert!(!is_prime(2_047));
+        assert!(!is_prime(1_373_653));
+        assert!(!is_prime(25_326_001));
+        assert!(!is_prime(3_215_031_751));
+    }
+
+    #[test]
+    fn large_values() {
+        assert!(is_prime(4_294_967_291));
+        assert!(!is_prime(u32::MAX));
+        assert!(!is_prime(65_521 * 65_519));
+    }
+}
@@ -0,0 +1,463 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+
+use crate::table::PrimeTable;
+
+// Minimal HTTP/1.1 JSON API over std::net:
+//
+//   GET /is_prime?n=17           -> {"is_prime":true,"n":17}
+//   GET /next_prime?n=17         -> {"n":17,"next_prime":19}
+//   GET /factor?n=360            -> {"factors":[2,2,2,3,3,5],"n":360}
+//   GET /primes?from=10&to=30    -> {"count":6,"from":10,"primes":[...],"to":30}
+//
+// Errors are reported as {"error":"..."} with a 4xx/5xx status.
+//
+// A connection gets `read_timeout` per read but only `request_timeout` in
+// total to deliver its request head, so a client trickling bytes cannot hold
+// a worker (and with enough of them, every connection slot) indefinitely.
+// Numbers above the table need trial division, so `/primes` accepts a much
+// smaller range there.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Limits {
+    pub max_request_bytes: usize,
+    pub max_range: u32,
+    /// Largest part of a `/primes` range that may lie above the table.
+    pub max_range_above_table: u32,
+    pub max_connections: usize,
+    pub read_timeout: Duration,
+    pub request_timeout: Duration,
+    pub write_timeout: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_request_bytes: 8 * 1024,
+            max_range: 1_000_000,
+            max_range_above_table: 10_000,
+            max_connections: 64,
+            read_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(10),
+            write_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    pub fn write_to(&self, stream: &mut impl Write) -> io::Result<()> {
+        let body = self.body.to_string();
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+fn query_param(query: &str, name: &str) -> Result<u32, Response> {
+    let value = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .ok_or_else(|| Response::error(400, format!("missing query parameter '{}'", name)))?;
+
+    value.parse::<u32>().map_err(|_| {
+        Response::error(
+            400,
+            format!("parameter '{}' must be an integer in 0..=4294967295", name),
+        )
+    })
+}
+
+/// Routes a single request. `target` is the request path including the query string.
+pub fn handle_request(table: &PrimeTable, limits: &Limits, method: &str, target: &str) -> Response {
+    if method != "GET" {
+        return Response::error(405, format!("method {} not allowed", method));
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let result = match path {
+        "/is_prime" => query_param(query, "n")
+            .map(|n| Response::ok(json!({ "n": n, "is_prime": table.is_prime(n) }))),
+        "/next_prime" => query_param(query, "n")
+            .map(|n| Response::ok(json!({ "n": n, "next_prime": table.next_prime(n) }))),
+        "/factor" => query_param(query, "n")
+            .map(|n| Response::ok(json!({ "n": n, "factors": table.factor(n) }))),
+        "/primes" => primes_in_range(table, limits, query),
+        _ => Err(Response::error(404, format!("unknown endpoint '{}'", path))),
+    };
+
+    result.unwrap_or_else(|error| error)
+}
+
+fn primes_in_range(table: &PrimeTable, limits: &Limits, query: &str) -> Result<Response, Response> {
+    let from = query_param(query, "from")?;
+    let to = query_param(query, "to")?;
+
+    if from > to {
+        return Err(Response::error(422, "'from' must not be greater than 'to'"));
+    }
+    if to - from > limits.max_range {
+        return Err(Response::error(
+            413,
+            format!(
+                "range too large: at most {} numbers per request",
+                limits.max_range
+            ),
+        ));
+    }
+
+    let above_table = to.saturating_sub(from.max(table.limit().saturating_add(1)));
+    if above_table > limits.max_range_above_table {
+        return Err(Response::error(
+            413,
+            format!(
+                "range too large: at most {} numbers above {} per request",
+                limits.max_range_above_table,
+                table.limit()
+            ),
+        ));
+    }
+
+    let primes = table.primes_in_range(from..to);
+    Ok(Response::ok(json!({
+        "from": from,
+        "to": to,
+        "count": primes.len(),
+        "primes": primes,
+    })))
+}
+
+// Reads from the stream until `deadline`, each read waiting at most the
+// time left or `read_timeout`, whichever is shorter.
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+    read_timeout: Duration,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream
+            .set_read_timeout(Some(left.min(self.read_timeout)))?;
+        self.stream.read(buf)
+    }
+}
+
+// Reads the request head (request line + headers) without ever buffering
+// more than `limits.max_request_bytes` or taking longer than
+// `limits.request_timeout`; returns the method and target of the request line.
+fn read_request_line(stream: &TcpStream, limits: &Limits) -> Result<(String, String), Response> {
+    let max_bytes = limits.max_request_bytes;
+    let reader = DeadlineReader {
+        stream,
+        deadline: Instant::now() + limits.request_timeout,
+        read_timeout: limits.read_timeout,
+    };
+    let mut reader = BufReader::new(reader.take(max_bytes as u64 + 1));
+    let mut consumed = 0;
+    let mut request_line = String::new();
+
+    loop {
+        let mut line = String::new();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|error| match error.kind() {
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+                    Response::error(408, "request not received in time")
+                }
+                _ => Response::error(400, "malformed request"),
+            })?;
+        consumed += read;
+
+        if consumed > max_bytes {
+            return Err(Response::error(413, "request header too large"));
+        }
+        if read == 0 || line == "\r\n" || line == "\n" {
+            break;
+        }
+        if request_line.is_empty() {
+            request_line = line;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+            Ok((method.to_string(), target.to_string()))
+        }
+        _ => Err(Response::error(400, "malformed request line")),
+    }
+}
+
+pub fn handle_connection(
+    mut stream: TcpStream,
+    table: &PrimeTable,
+    limits: &Limits,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(limits.read_timeout))?;
+    stream.set_write_timeout(Some(limits.write_timeout))?;
+
+    let response = match read_request_line(&stream, limits) {
+        Ok((method, target)) => handle_request(table, limits, &method, &target),
+        Err(error) => error,
+    };
+
+    response.write_to(&mut stream)?;
+
+    // Discard whatever the client still sends (e.g. the rest of an oversized
+    // request) so closing the socket does not reset the connection before the
+    // response is read. This too ends at a deadline.
+    stream.shutdown(Shutdown::Write)?;
+    let mut rest = DeadlineReader {
+        stream: &stream,
+        deadline: Instant::now() + limits.read_timeout,
+        read_timeout: limits.read_timeout,
+    };
+    let _ = io::copy(&mut (&mut rest).take(64 * 1024), &mut io::sink());
+    Ok(())
+}
+
+/// Accepts connections forever, handling each on its own thread. Connections
+/// above `limits.max_connections` are answered with 503 straight away.
+pub fn serve(listener: TcpListener, table: Arc<PrimeTable>, limits: Limits) -> io::Result<()> {
+    let limits = Arc::new(limits);
+    let active = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("accept failed: {}", err);
+                continue;
+            }
+        };
+
+        if active.fetch_add(1, Ordering::SeqCst) >= limits.max_connections {
+            active.fetch_sub(1, Ordering::SeqCst);
+            // this runs on the accept thread, so a client that does not read
+            // may only hold it up for the write timeout
+            let _ = stream.set_write_timeout(Some(limits.write_timeout));
+            let _ = Response::error(503, "too many concurrent requests").write_to(&mut stream);
+            continue;
+        }
+
+        let table = Arc::clone(&table);
+        let limits = Arc::clone(&limits);
+        let active = Arc::clone(&active);
+        thread::spawn(move || {
+            if let Err(err) = handle_connection(stream, &table, &limits) {
+                eprintln!("connection error: {}", err);
+            }
+            active.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod server_tests {
+    use super::*;
+
+    fn get(target: &str) -> Response {
+        let table = PrimeTable::new(1_000);
+        let limits = Limits {
+            max_range: 100,
+            ..Limits::default()
+        };
+        handle_request(&table, &limits, "GET", target)
+    }
+
+    #[test]
+    fn is_prime_endpoint() {
+        assert_eq!(
+            get("/is_prime?n=17"),
+            Response::ok(json!({"n": 17, "is_prime": true}))
+        );
+        assert_eq!(get("/is_prime?n=4294967295").body["is_prime"], json!(false));
+    }
+
+    #[test]
+    fn next_prime_endpoint() {
+        assert_eq!(
+            get("/next_prime?n=17").body,
+            json!({"n": 17, "next_prime": 19})
+        );
+        assert_eq!(
+            get("/next_prime?n=4294967295").body["next_prime"],
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn factor_endpoint() {
+        assert_eq!(
+            get("/factor?n=360").body["factors"],
+            json!([2, 2, 2, 3, 3, 5])
+        );
+    }
+
+    #[test]
+    fn primes_endpoint() {
+        let response = get("/primes?from=10&to=30");
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body["count"], json!(6));
+        assert_eq!(response.body["primes"], json!([11, 13, 17, 19, 23, 29]));
+    }
+
+    #[test]
+    fn primes_range_limit() {
+        assert_eq!(get("/primes?from=0&to=100").status, 200);
+        assert_eq!(get("/primes?from=0&to=101").status, 413);
+        assert_eq!(get("/primes?from=0&to=4294967295").status, 413);
+        assert_eq!(get("/primes?from=30&to=10").status, 422);
+
+        // numbers above the table need trial division
+        let table = PrimeTable::new(1_000);
+        let limits = Limits {
+            max_range_above_table: 50,
+            ..Limits::default()
+        };
+        let status = |target| handle_request(&table, &limits, "GET", target).status;
+        assert_eq!(status("/primes?from=900&to=1051"), 200);
+        assert_eq!(status("/primes?from=900&to=1052"), 413);
+        assert_eq!(status("/primes?from=4000000000&to=4000000050"), 200);
+        assert_eq!(status("/primes?from=4000000000&to=4000000051"), 413);
+    }
+
+    #[test]
+    fn bad_requests() {
+        assert_eq!(get("/is_prime").status, 400);
+        assert_eq!(get("/is_prime?n=-1").status, 400);
+        assert_eq!(get("/is_prime?n=4294967296").status, 400);
+        assert_eq!(get("/primes?from=1").status, 400);
+        assert_eq!(get("/unknown").status, 404);
+
+        let table = PrimeTable::new(10);
+        let response = handle_request(&table, &Limits::default(), "POST", "/is_prime?n=2");
+        assert_eq!(response.status, 405);
+    }
+
+    #[test]
+    fn serves_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let limits = Limits {
+            max_request_bytes: 256,
+            ..Limits::default()
+        };
+        thread::spawn(move || serve(listener, Arc::new(PrimeTable::new(1_000)), limits));
+
+        let request = |raw: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(raw.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = request("GET /factor?n=12 HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(r#"{"factors":[2,2,3],"n":12}"#));
+
+        let huge = format!(
+            "GET /is_prime?n=2 HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+            "a".repeat(1_000)
+        );
+        assert!(request(&huge).starts_with("HTTP/1.1 413 "));
+
+        assert!(request("garbage\r\n\r\n").starts_with("HTTP/1.1 400 "));
+    }
+
+    #[test]
+    fn rejects_connections_over_the_limit() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let limits = Limits {
+            max_connections: 0,
+            write_timeout: Duration::from_millis(100),
+            ..Limits::default()
+        };
+        thread::spawn(move || serve(listener, Arc::new(PrimeTable::new(10)), limits));
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 503 "));
+    }
+
+    #[test]
+    fn slow_clients_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let limits = Limits {
+            read_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_millis(300),
+            ..Limits::default()
+        };
+        thread::spawn(move || serve(listener, Arc::new(PrimeTable::new(10)), limits));
+
+        // every byte arrives well within the read timeout
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let started = Instant::now();
+        for byte in b"GET /is_prime?n=2 HTTP/1.1\r\n" {
+            if stream.write_all(&[*byte]).is_err() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 408 "), "{response}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
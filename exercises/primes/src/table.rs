@@ -0,0 +1,222 @@
+use crate::wheel::DEFAULT_WHEEL;
+
+/// Largest limit the binaries accept for a table. The sieve takes a byte per
+/// number, so this keeps it around 100 MB; larger queries still work through
+/// the trial-division fallback.
+pub const MAX_TABLE_LIMIT: u32 = 100_000_000;
+
+// Sieve of Eratosthenes computed once up to `limit`. Queries below the limit
+// are lookups, queries above it fall back to trial division by the table's
+// primes (which covers every u32 once limit >= 65_536).
+
+#[derive(Debug, Clone)]
+pub struct PrimeTable {
+    limit: u32,
+    sieve: Vec<bool>,
+    primes: Vec<u32>,
+}
+
+impl PrimeTable {
+    pub fn new(limit: u32) -> Self {
+        let mut sieve = vec![true; limit as usize + 1];
+        sieve[0] = false;
+        if limit >= 1 {
+            sieve[1] = false;
+        }
+
+        let mut i = 2usize;
+        while i * i <= limit as usize {
+            if sieve[i] {
+                for multiple in (i * i..=limit as usize).step_by(i) {
+                    sieve[multiple] = false;
+                }
+            }
+            i += 1;
+        }
+
+        let primes = sieve
+            .iter()
+            .enumerate()
+            .filter(|(_, is_prime)| **is_prime)
+            .map(|(n, _)| n as u32)
+            .collect();
+
+        PrimeTable {
+            limit,
+            sieve,
+            primes,
+        }
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Primes up to and including the table limit.
+    pub fn primes(&self) -> &[u32] {
+        &self.primes
+    }
+
+    pub fn is_prime(&self, n: u32) -> bool {
+        if n <= self.limit {
+            return self.sieve[n as usize];
+        }
+
+        let n64 = n as u64;
+        for &p in &self.primes {
+            if (p as u64) * (p as u64) > n64 {
+                return true;
+            }
+            if n.is_multiple_of(p) {
+                return false;
+            }
+        }
+
+        DEFAULT_WHEEL.is_prime(n)
+    }
+
+    /// Smallest prime strictly greater than `n`, or `None` if it does not fit in u32.
+    pub fn next_prime(&self, n: u32) -> Option<u32> {
+        if n < self.limit {
+            let start = n as usize + 1;
+            if let Some(offset) = self.sieve[start..].iter().position(|&is_prime| is_prime) {
+                return Some((start + offset) as u32);
+            }
+        }
+
+        DEFAULT_WHEEL
+            .candidates_from(n.checked_add(1)?.max(self.limit.saturating_add(1)))
+            .find(|&c| self.is_prime(c))
+    }
+
+    /// Prime factors of `n` in ascending order, with multiplicity.
+    /// 0 and 1 have no prime factorization and yield an empty vector.
+    pub fn factor(&self, n: u32) -> Vec<u32> {
+        let mut factors = Vec::new();
+        if n < 2 {
+            return factors;
+        }
+
+        let mut rest = n;
+        let divisors = self
+            .primes
+            .iter()
+            .copied()
+            .chain(DEFAULT_WHEEL.candidates_from(self.limit.saturating_add(1)));
+
+        for d in divisors {
+            if (d as u64) * (d as u64) > rest as u64 {
+                break;
+            }
+            while rest.is_multiple_of(d) {
+                factors.push(d);
+                rest /= d;
+            }
+        }
+
+        if rest > 1 {
+            factors.push(rest);
+        }
+        factors
+    }
+
+    pub fn primes_in_range(&self, range: std::ops::Range<u32>) -> Vec<u32> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+
+        let mut primes: Vec<u32> = if range.start <= self.limit {
+            let from = self.primes.partition_point(|&p| p < range.start);
+            let to = self.primes.partition_point(|&p| p < range.end);
+            self.primes[from..to].to_vec()
+        } else {
+            Vec::new()
+        };
+
+        if range.end > self.limit.saturating_add(1) {
+            let start = range.start.max(self.limit.saturating_add(1));
+            primes.extend(
+                DEFAULT_WHEEL
+                    .candidates_from(start)
+                    .take_while(|&c| c < range.end)
+                    .filter(|&c| self.is_prime(c)),
+            );
+        }
+
+        primes
+    }
+}
+
+#[cfg(test)]
+mod table_tests {
+    use super::*;
+    use crate::is_prime_naive;
+
+    #[test]
+    fn sieve_matches_trial_division() {
+        let table = PrimeTable::new(10_000);
+        for n in 0..=10_000 {
+            assert_eq!(table.is_prime(n), is_prime_naive(n), "n = {}", n);
+        }
+    }
+
+    #[test]
+    fn tiny_tables() {
+        assert!(PrimeTable::new(0).primes().is_empty());
+        assert!(PrimeTable::new(1).primes().is_empty());
+        assert_eq!(PrimeTable::new(2).primes(), &[2]);
+        assert_eq!(PrimeTable::new(10).primes(), &[2, 3, 5, 7]);
+    }
+
+    #[test]
+    fn is_prime_above_limit() {
+        let table = PrimeTable::new(100);
+        for n in 100..20_000 {
+            assert_eq!(table.is_prime(n), is_prime_naive(n), "n = {}", n);
+        }
+        assert!(table.is_prime(4_294_967_291));
+        assert!(!table.is_prime(u32::MAX));
+    }
+
+    #[test]
+    fn next_prime() {
+        let table = PrimeTable::new(100);
+        assert_eq!(table.next_prime(0), Some(2));
+        assert_eq!(table.next_prime(2), Some(3));
+        assert_eq!(table.next_prime(13), Some(17));
+        assert_eq!(table.next_prime(97), Some(101));
+        assert_eq!(table.next_prime(100), Some(101));
+        assert_eq!(table.next_prime(1_000), Some(1_009));
+        assert_eq!(table.next_prime(4_294_967_290), Some(4_294_967_291));
+        assert_eq!(table.next_prime(4_294_967_291), None);
+        assert_eq!(table.next_prime(u32::MAX), None);
+    }
+
+    #[test]
+    fn factor() {
+        let table = PrimeTable::new(100);
+        assert_eq!(table.factor(0), Vec::<u32>::new());
+        assert_eq!(table.factor(1), Vec::<u32>::new());
+        assert_eq!(table.factor(2), vec![2]);
+        assert_eq!(table.factor(360), vec![2, 2, 2, 3, 3, 5]);
+        assert_eq!(table.factor(97 * 101), vec![97, 101]);
+        assert_eq!(table.factor(65_521 * 65_519), vec![65_519, 65_521]);
+        assert_eq!(table.factor(u32::MAX), vec![3, 5, 17, 257, 65_537]);
+        assert_eq!(table.factor(4_294_967_291), vec![4_294_967_291]);
+    }
+
+    #[test]
+    fn primes_in_range_across_limit() {
+        let table = PrimeTable::new(50);
+        assert_eq!(
+            table.primes_in_range(40..80),
+            vec![41, 43, 47, 53, 59, 61, 67, 71, 73, 79]
+        );
+        assert_eq!(table.primes_in_range(0..10), vec![2, 3, 5, 7]);
+        assert_eq!(table.primes_in_range(60..70), vec![61, 67]);
+        assert_eq!(table.primes_in_range(20..20), Vec::<u32>::new());
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = table.primes_in_range(30..10);
+        assert_eq!(reversed, Vec::<u32>::new());
+    }
+}
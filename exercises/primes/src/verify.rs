@@ -0,0 +1,348 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::table::PrimeTable;
+use crate::wheel::{Wheel, WheelSize};
+use crate::{is_prime_naive, miller_rabin};
+
+// Differential testing: every primality algorithm in the crate must give the
+// same verdict for the same input, and prime counts must match published
+// values of pi(x).
+
+/// Published values of pi(x), the number of primes <= x.
+pub const PI_REFERENCE: [(u32, u32); 10] = [
+    (10, 4),
+    (100, 25),
+    (1_000, 168),
+    (10_000, 1_229),
+    (100_000, 9_592),
+    (1_000_000, 78_498),
+    (10_000_000, 664_579),
+    (100_000_000, 5_761_455),
+    (1_000_000_000, 50_847_534),
+    (u32::MAX, 203_280_221),
+];
+
+pub struct Algorithm<'a> {
+    pub name: String,
+    pub is_prime: Box<dyn Fn(u32) -> bool + 'a>,
+}
+
+impl<'a> Algorithm<'a> {
+    pub fn new(name: &str, is_prime: impl Fn(u32) -> bool + 'a) -> Self {
+        Algorithm {
+            name: name.to_string(),
+            is_prime: Box::new(is_prime),
+        }
+    }
+}
+
+/// All primality tests available in the crate, the sieve backed by `table`.
+pub fn algorithms(table: &PrimeTable) -> Vec<Algorithm<'_>> {
+    let mut algorithms = vec![Algorithm::new("trial division", is_prime_naive)];
+
+    for size in [
+        WheelSize::Mod6,
+        WheelSize::Mod30,
+        WheelSize::Mod210,
+        WheelSize::Mod2310,
+    ] {
+        let wheel = Wheel::new(size);
+        let name = format!("wheel mod {}", wheel.modulus());
+        algorithms.push(Algorithm::new(&name, move |n| wheel.is_prime(n)));
+    }
+
+    algorithms.push(Algorithm::new("sieve", |n| table.is_prime(n)));
+    algorithms.push(Algorithm::new("miller-rabin", miller_rabin::is_prime));
+    algorithms
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub n: u32,
+    pub verdicts: Vec<(String, bool)>,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "algorithms disagree for n = {}:", self.n)?;
+        for (name, verdict) in &self.verdicts {
+            write!(
+                f,
+                "\n  {:<16} {}",
+                name,
+                if *verdict { "prime" } else { "composite" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn check(algorithms: &[Algorithm], n: u32) -> Option<Disagreement> {
+    let verdicts: Vec<(String, bool)> = algorithms
+        .iter()
+        .map(|algorithm| (algorithm.name.clone(), (algorithm.is_prime)(n)))
+        .collect();
+
+    if verdicts
+        .iter()
+        .all(|(_, verdict)| *verdict == verdicts[0].1)
+    {
+        None
+    } else {
+        Some(Disagreement { n, verdicts })
+    }
+}
+
+/// Checks every number in `range`; the first disagreement is the minimal one.
+pub fn verify_range(algorithms: &[Algorithm], range: Range<u32>) -> Option<Disagreement> {
+    range.into_iter().find_map(|n| check(algorithms, n))
+}
+
+/// Checks `samples` pseudo-random u32 values and reports the smallest
+/// disagreeing one, shrunk with a bounded search: the result is the smallest
+/// disagreement among every number below `SHRINK_EXHAUSTIVE` and the lowest
+/// `SHRINK_CHECKS` numbers sharing the sample's residue modulo 30, or the
+/// sample itself if none of those disagree.
+pub fn verify_random(algorithms: &[Algorithm], samples: usize, seed: u64) -> Option<Disagreement> {
+    let mut rng = SplitMix64::new(seed);
+
+    let smallest = (0..samples)
+        .map(|_| rng.next_u32())
+        .filter_map(|n| check(algorithms, n))
+        .min_by_key(|disagreement| disagreement.n)?;
+
+    Some(shrink(algorithms, smallest))
+}
+
+/// Numbers below this are all checked when shrinking.
+pub const SHRINK_EXHAUSTIVE: u32 = 10_000;
+/// Numbers of the sample's residue class checked when shrinking.
+pub const SHRINK_CHECKS: u32 = 100_000;
+
+// Small inputs are cheap to check, even by trial division, so they are tried
+// first. After that come inputs of the same parity and residue mod 3 and 5,
+// which tend to hit the same code path of a broken implementation; starting
+// from the smallest keeps each check cheap and the total work bounded.
+fn shrink(algorithms: &[Algorithm], found: Disagreement) -> Disagreement {
+    const STEP: u32 = 30;
+    if let Some(smaller) = verify_range(algorithms, 0..found.n.min(SHRINK_EXHAUSTIVE)) {
+        return smaller;
+    }
+    let first = found.n % STEP;
+    (0..SHRINK_CHECKS)
+        .map(|i| first + i * STEP)
+        .take_while(|&n| n < found.n)
+        .find_map(|n| check(algorithms, n))
+        .unwrap_or(found)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiMismatch {
+    pub algorithm: String,
+    pub x: u32,
+    pub expected: u32,
+    pub actual: u32,
+}
+
+impl fmt::Display for PiMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: pi({}) = {}, expected {}",
+            self.algorithm, self.x, self.actual, self.expected
+        )
+    }
+}
+
+/// Counts primes with every algorithm and compares against `PI_REFERENCE`
+/// for all reference points not exceeding `max_x`.
+pub fn verify_pi(algorithms: &[Algorithm], max_x: u32) -> Vec<PiMismatch> {
+    let references: Vec<(u32, u32)> = PI_REFERENCE
+        .iter()
+        .copied()
+        .take_while(|(x, _)| *x <= max_x)
+        .collect();
+
+    let mut mismatches = Vec::new();
+    for algorithm in algorithms {
+        let mut count = 0;
+        let mut counted_up_to = 0;
+        for &(x, expected) in &references {
+            count += (counted_up_to..=x)
+                .filter(|&n| (algorithm.is_prime)(n))
+                .count() as u32;
+            // u32::MAX is the last reference point, nothing is counted after it
+            counted_up_to = x.saturating_add(1);
+
+            if count != expected {
+                mismatches.push(PiMismatch {
+                    algorithm: algorithm.name.clone(),
+                    x,
+                    expected,
+                    actual: count,
+                });
+            }
+        }
+    }
+    mismatches
+}
+
+// Small deterministic PRNG so runs are reproducible from the seed alone.
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+}
+
+#[cfg(test)]
+mod verify_tests {
+    use super::*;
+
+    fn with_broken(table: &PrimeTable) -> Vec<Algorithm<'_>> {
+        let mut algorithms = algorithms(table);
+        // claims every odd square of a prime is prime
+        algorithms.push(Algorithm::new("broken", |n| {
+            let root = (n as f64).sqrt() as u32;
+            crate::is_prime(n) || (n % 2 == 1 && root * root == n && crate::is_prime(root))
+        }));
+        algorithms
+    }
+
+    #[test]
+    fn all_algorithms_agree_exhaustively() {
+        let table = PrimeTable::new(1_000);
+        assert_eq!(verify_range(&algorithms(&table), 0..50_000), None);
+    }
+
+    #[test]
+    fn all_algorithms_agree_on_random_inputs() {
+        let table = PrimeTable::new(65_536);
+        assert_eq!(verify_random(&algorithms(&table), 200, 42), None);
+    }
+
+    #[test]
+    fn exhaustive_reports_minimal_input() {
+        let table = PrimeTable::new(100);
+        let disagreement = verify_range(&with_broken(&table), 0..1_000).unwrap();
+
+        assert_eq!(disagreement.n, 9);
+        assert_eq!(
+            disagreement.verdicts.last(),
+            Some(&("broken".to_string(), true))
+        );
+        assert_eq!(
+            disagreement.verdicts[0],
+            ("trial division".to_string(), false)
+        );
+    }
+
+    #[test]
+    fn random_disagreement_is_shrunk() {
+        let table = PrimeTable::new(100);
+        let algorithms = vec![
+            Algorithm::new("sieve", |n| table.is_prime(n)),
+            Algorithm::new("always composite above 1000", |n| {
+                n < 1_000 && table.is_prime(n)
+            }),
+        ];
+        let disagreement = verify_random(&algorithms, 1_000, 7).unwrap();
+
+        // 1009, the smallest prime above 1000, is within the exhaustive part
+        assert_eq!(disagreement.n, 1_009);
+    }
+
+    #[test]
+    fn shrinking_large_disagreements_is_bounded() {
+        let table = PrimeTable::new(65_536);
+        let algorithms = vec![
+            Algorithm::new("sieve", |n| table.is_prime(n)),
+            Algorithm::new("always composite above 4e9", |n| {
+                n < 4_000_000_000 && table.is_prime(n)
+            }),
+        ];
+        let found = verify_random(&algorithms, 100_000, 3).unwrap();
+        // nothing below it disagrees, so the sample comes back unchanged
+        assert!(found.n > 4_000_000_000);
+        assert!(table.is_prime(found.n));
+    }
+
+    #[test]
+    fn display_lists_verdicts() {
+        let disagreement = Disagreement {
+            n: 9,
+            verdicts: vec![("a".to_string(), false), ("b".to_string(), true)],
+        };
+        assert_eq!(
+            disagreement.to_string(),
+            "algorithms disagree for n = 9:\n  a                composite\n  b                prime"
+        );
+    }
+
+    #[test]
+    fn pi_reference_values() {
+        let table = PrimeTable::new(100_000);
+        assert_eq!(verify_pi(&algorithms(&table), 100_000), Vec::new());
+    }
+
+    #[test]
+    fn pi_mismatch_is_reported() {
+        let table = PrimeTable::new(100);
+        let mismatches = verify_pi(&with_broken(&table), 1_000);
+
+        // 9, 25, 49 are below 100 and 121..961 below 1000
+        assert_eq!(
+            mismatches,
+            vec![
+                PiMismatch {
+                    algorithm: "broken".to_string(),
+                    x: 10,
+                    expected: 4,
+                    actual: 5
+                },
+                PiMismatch {
+                    algorithm: "broken".to_string(),
+                    x: 100,
+                    expected: 25,
+                    actual: 28
+                },
+                PiMismatch {
+                    algorithm: "broken".to_string(),
+                    x: 1_000,
+                    expected: 168,
+                    actual: 178
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn splitmix_is_deterministic() {
+        let a: Vec<u64> = {
+            let mut rng = SplitMix64::new(1);
+            (0..5).map(|_| rng.next_u64()).collect()
+        };
+        let b: Vec<u64> = {
+            let mut rng = SplitMix64::new(1);
+            (0..5).map(|_| rng.next_u64()).collect()
+        };
+        assert_eq!(a, b);
+        assert_ne!(SplitMix64::new(2).next_u64(), a[0]);
+    }
+}
@@ -0,0 +1,264 @@
+use std::sync::LazyLock;
+
+// Wheel factorization: numbers that share a factor with the wheel modulus
+// (the product of the first few primes) can never be prime, so only the
+// residues coprime to the modulus have to be generated and tested.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WheelSize {
+    Mod6,
+    Mod30,
+    Mod210,
+    Mod2310,
+}
+
+impl WheelSize {
+    pub fn basis(&self) -> &'static [u32] {
+        match self {
+            WheelSize::Mod6 => &[2, 3],
+            WheelSize::Mod30 => &[2, 3, 5],
+            WheelSize::Mod210 => &[2, 3, 5, 7],
+            WheelSize::Mod2310 => &[2, 3, 5, 7, 11],
+        }
+    }
+
+    pub fn modulus(&self) -> u32 {
+        self.basis().iter().product()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wheel {
+    size: WheelSize,
+    residues: Vec<u32>, // residues in [1, modulus) coprime to modulus
+    gaps: Vec<u32>,     // gaps[i] = distance from residues[i] to the next coprime number
+}
+
+pub static DEFAULT_WHEEL: LazyLock<Wheel> = LazyLock::new(|| Wheel::new(WheelSize::Mod30));
+
+impl Wheel {
+    pub fn new(size: WheelSize) -> Self {
+        let modulus = size.modulus();
+        let basis = size.basis();
+
+        let residues: Vec<u32> = (1..modulus)
+            .filter(|r| basis.iter().all(|p| r % p != 0))
+            .collect();
+
+        let gaps = residues
+            .iter()
+            .zip(
+                residues
+                    .iter()
+                    .skip(1)
+                    .chain(std::iter::once(&(modulus + 1))),
+            )
+            .map(|(current, next)| next - current)
+            .collect();
+
+        Wheel {
+            size,
+            residues,
+            gaps,
+        }
+    }
+
+    pub fn size(&self) -> WheelSize {
+        self.size
+    }
+
+    pub fn modulus(&self) -> u32 {
+        self.size.modulus()
+    }
+
+    pub fn basis(&self) -> &'static [u32] {
+        self.size.basis()
+    }
+
+    pub fn spokes(&self) -> usize {
+        self.residues.len()
+    }
+
+    /// All prime candidates in ascending order: the basis primes followed by
+    /// every number coprime to the modulus.
+    pub fn candidates(&self) -> WheelCandidates<'_> {
+        self.candidates_from(0)
+    }
+
+    /// Prime candidates greater than or equal to `start`.
+    pub fn candidates_from(&self, start: u32) -> WheelCandidates<'_> {
+        let basis = self.basis();
+        let basis_index = basis.partition_point(|&p| p < start);
+
+        let largest_basis = *basis.last().unwrap();
+        let start = start.max(largest_basis + 1);
+
+        let modulus = self.modulus();
+        let residue = start % modulus;
+        let base = (start - residue) as u64;
+        // modulus - 1 is always coprime, so a spoke at or after `residue` exists
+        let spoke = self.residues.partition_point(|&r| r < residue);
+        let current = base + self.residues[spoke] as u64;
+
+        WheelCandidates {
+            wheel: self,
+            basis_index,
+            current,
+            spoke,
+        }
+    }
+
+    /// Trial division testing only the divisors produced by the wheel.
+    pub fn is_prime(&self, n: u32) -> bool {
+        if n < 2 {
+            return false;
+        }
+
+        for &p in self.basis() {
+            if n == p {
+                return true;
+            }
+            if n.is_multiple_of(p) {
+                return false;
+            }
+        }
+
+        let n = n as u64;
+        let largest_basis = *self.basis().last().unwrap();
+        self.candidates_from(largest_basis + 1)
+            .map(|d| d as u64)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+    }
+}
+
+impl Default for Wheel {
+    fn default() -> Self {
+        Wheel::new(WheelSize::Mod30)
+    }
+}
+
+pub struct WheelCandidates<'a> {
+    wheel: &'a Wheel,
+    basis_index: usize,
+    current: u64,
+    spoke: usize,
+}
+
+impl Iterator for WheelCandidates<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let basis = self.wheel.basis();
+        if self.basis_index < basis.len() {
+            self.basis_index += 1;
+            return Some(basis[self.basis_index - 1]);
+        }
+
+        if self.current > u32::MAX as u64 {
+            return None;
+        }
+
+        let candidate = self.current as u32;
+        self.current += self.wheel.gaps[self.spoke] as u64;
+        self.spoke = (self.spoke + 1) % self.wheel.gaps.len();
+        Some(candidate)
+    }
+}
+
+#[cfg(test)]
+mod wheel_tests {
+    use super::*;
+
+    const ALL_SIZES: [WheelSize; 4] = [
+        WheelSize::Mod6,
+        WheelSize::Mod30,
+        WheelSize::Mod210,
+        WheelSize::Mod2310,
+    ];
+
+    fn is_coprime_candidate(n: u32, basis: &[u32]) -> bool {
+        n > 1 && (basis.contains(&n) || basis.iter().all(|&p| !n.is_multiple_of(p)))
+    }
+
+    #[test]
+    fn modulus_and_spokes() {
+        let expected = [(6, 2), (30, 8), (210, 48), (2310, 480)];
+
+        for (size, (modulus, spokes)) in ALL_SIZES.iter().zip(expected) {
+            let wheel = Wheel::new(*size);
+            assert_eq!(wheel.modulus(), modulus);
+            assert_eq!(wheel.spokes(), spokes);
+        }
+    }
+
+    #[test]
+    fn mod6_candidates() {
+        let candidates: Vec<u32> = Wheel::new(WheelSize::Mod6).candidates().take(10).collect();
+        assert_eq!(candidates, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 25]);
+    }
+
+    #[test]
+    fn mod30_candidates() {
+        let candidates: Vec<u32> = Wheel::new(WheelSize::Mod30).candidates().take(12).collect();
+        assert_eq!(candidates, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37]);
+    }
+
+    #[test]
+    fn candidates_are_exactly_numbers_coprime_to_basis() {
+        for size in ALL_SIZES {
+            let wheel = Wheel::new(size);
+            let expected: Vec<u32> = (0..5000)
+                .filter(|&n| is_coprime_candidate(n, size.basis()))
+                .collect();
+            let candidates: Vec<u32> = wheel.candidates().take_while(|&n| n < 5000).collect();
+            assert_eq!(candidates, expected, "wheel {:?}", size);
+        }
+    }
+
+    #[test]
+    fn candidates_from_start_inside_and_between_spokes() {
+        let wheel = Wheel::new(WheelSize::Mod30);
+
+        for start in 0..200 {
+            let first = wheel.candidates_from(start).next().unwrap();
+            let expected = (start..)
+                .find(|&n| is_coprime_candidate(n, wheel.basis()))
+                .unwrap();
+            assert_eq!(first, expected, "start {}", start);
+        }
+    }
+
+    #[test]
+    fn candidates_stop_at_u32_max() {
+        let wheel = Wheel::new(WheelSize::Mod6);
+        let tail: Vec<u32> = wheel.candidates_from(u32::MAX - 10).collect();
+        assert_eq!(
+            tail,
+            vec![u32::MAX - 10, u32::MAX - 8, u32::MAX - 4, u32::MAX - 2]
+        );
+    }
+
+    #[test]
+    fn is_prime_with_every_wheel() {
+        for size in ALL_SIZES {
+            let wheel = Wheel::new(size);
+            let primes: Vec<u32> = (0..60).filter(|&n| wheel.is_prime(n)).collect();
+            assert_eq!(
+                primes,
+                vec![
+                    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn is_prime_large_values() {
+        let wheel = Wheel::new(WheelSize::Mod2310);
+        assert!(wheel.is_prime(4_294_967_291)); // largest prime below 2^32
+        assert!(!wheel.is_prime(u32::MAX));
+        assert!(!wheel.is_prime(13 * 13));
+        assert!(!wheel.is_prime(65_521 * 65_519));
+    }
+}
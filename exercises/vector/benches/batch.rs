@@ -0,0 +1,97 @@
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use vector::batch::Vector2DBatch;
+use vector::vector2d::Vector2D;
+
+const SIZES: [usize; 2] = [1_000, 1_000_000];
+
+// Reproducible pseudo-random vectors in [-50, 50)
+fn vectors(count: usize, seed: u32) -> Vec<Vector2D> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        (state >> 8) as f32 / (1 << 24) as f32 * 100.0 - 50.0
+    };
+    (0..count).map(|_| Vector2D::new(next(), next())).collect()
+}
+
+fn bench_add_scaled(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_scaled");
+
+    for size in SIZES {
+        let (positions, velocities) = (vectors(size, 1), vectors(size, 2));
+        group.bench_with_input(BenchmarkId::new("aos", size), &size, |b, _| {
+            let mut positions = positions.clone();
+            b.iter(|| {
+                for (p, v) in positions.iter_mut().zip(&velocities) {
+                    *p += *v * black_box(0.01);
+                }
+            })
+        });
+
+        let velocities = Vector2DBatch::from(velocities.as_slice());
+        group.bench_with_input(BenchmarkId::new("soa", size), &size, |b, _| {
+            let mut positions = Vector2DBatch::from(positions.as_slice());
+            b.iter(|| positions.add_scaled(&velocities, black_box(0.01)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_normalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("normalize");
+
+    for size in SIZES {
+        let aos = vectors(size, 3);
+        group.bench_with_input(BenchmarkId::new("aos", size), &aos, |b, aos| {
+            b.iter(|| {
+                let mut vectors = aos.clone();
+                for v in vectors.iter_mut() {
+                    *v = v.normalize().unwrap_or(Vector2D::ZERO);
+                }
+                vectors
+            })
+        });
+
+        let soa = Vector2DBatch::from(aos.as_slice());
+        group.bench_with_input(BenchmarkId::new("soa", size), &soa, |b, soa| {
+            b.iter(|| {
+                let mut batch = soa.clone();
+                batch.normalize();
+                batch
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_lengths_and_dots(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lengths_and_dots");
+
+    for size in SIZES {
+        let (a, b) = (vectors(size, 4), vectors(size, 5));
+        group.bench_with_input(BenchmarkId::new("aos", size), &size, |bench, _| {
+            bench.iter(|| {
+                let lengths: Vec<f32> = a.iter().map(|v| v.length()).collect();
+                let dots: Vec<f32> = a.iter().zip(&b).map(|(u, v)| u.dot(v)).collect();
+                (lengths, dots)
+            })
+        });
+
+        let (a, b) = (Vector2DBatch::from(a), Vector2DBatch::from(b));
+        group.bench_with_input(BenchmarkId::new("soa", size), &size, |bench, _| {
+            bench.iter(|| (a.lengths(), a.dot(&b)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_add_scaled,
+    bench_normalize,
+    bench_lengths_and_dots
+);
+criterion_main!(benches);
@@ -0,0 +1,521 @@
+use crate::geometry::shapes::{
+    Aabb, Circle, ClosestPoints, Hit, Line, LinearSpan, Polygon, Ray, Segment,
+};
+use crate::matrix::{Affine2, Mat2, Mat3};
+use crate::vector2d::Vector2D;
+use crate::vectorn::Vector;
+
+// Approximate equality for floating-point based types.
+//
+// * absolute:  |a - b| <= epsilon
+// * relative:  |a - b| <= max(|a|, |b|) * max_relative (or within epsilon near zero)
+// * ULPs:      a and b are at most max_ulps representable floats apart
+//
+// NaN is never approximately equal to anything, infinities only to themselves.
+// Composite types compare component-wise and are equal when all components are.
+pub trait ApproxEq {
+    type Epsilon: Copy;
+
+    fn default_epsilon() -> Self::Epsilon;
+    fn default_max_relative() -> Self::Epsilon;
+    fn default_max_ulps() -> u32 {
+        4
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool;
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool;
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool;
+
+    /// Relative comparison with the default tolerances.
+    fn approx_eq(&self, other: &Self) -> bool {
+        self.relative_eq(other, Self::default_epsilon(), Self::default_max_relative())
+    }
+}
+
+macro_rules! impl_approx_eq_float {
+    ($t:ty, $bits:ty) => {
+        impl ApproxEq for $t {
+            type Epsilon = $t;
+
+            fn default_epsilon() -> Self::Epsilon {
+                <$t>::EPSILON
+            }
+
+            fn default_max_relative() -> Self::Epsilon {
+                <$t>::EPSILON
+            }
+
+            fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+                self == other || (self - other).abs() <= epsilon
+            }
+
+            fn relative_eq(
+                &self,
+                other: &Self,
+                epsilon: Self::Epsilon,
+                max_relative: Self::Epsilon,
+            ) -> bool {
+                if self == other {
+                    return true;
+                }
+                if self.is_infinite() || other.is_infinite() {
+                    return false;
+                }
+
+                let diff = (self - other).abs();
+                if diff <= epsilon {
+                    return true;
+                }
+                diff <= self.abs().max(other.abs()) * max_relative
+            }
+
+            fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+                if self.abs_diff_eq(other, epsilon) {
+                    return true;
+                }
+                if self.is_nan() || other.is_nan() || self.signum() != other.signum() {
+                    return false;
+                }
+                // infinity is next to MAX in bit order but not close to it
+                if self.is_infinite() || other.is_infinite() {
+                    return false;
+                }
+
+                let a = self.to_bits() as $bits;
+                let b = other.to_bits() as $bits;
+                a.abs_diff(b) <= max_ulps as _
+            }
+        }
+    };
+}
+
+impl_approx_eq_float!(f32, i32);
+impl_approx_eq_float!(f64, i64);
+
+// Lets the assertion macros name the epsilon type of an expression
+#[doc(hidden)]
+pub fn default_epsilon_of<T: ApproxEq>(_: &T) -> T::Epsilon {
+    T::default_epsilon()
+}
+
+// Component-wise comparison of anything that can be viewed as a slice of scalars
+fn all_components<T: ApproxEq>(a: &[T], b: &[T], eq: impl Fn(&T, &T) -> bool) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| eq(x, y))
+}
+
+macro_rules! impl_approx_eq_components {
+    ($t:ty, $epsilon:ty, $components:expr) => {
+        impl ApproxEq for $t {
+            type Epsilon = $epsilon;
+
+            fn default_epsilon() -> Self::Epsilon {
+                <$epsilon as ApproxEq>::default_epsilon()
+            }
+
+            fn default_max_relative() -> Self::Epsilon {
+                <$epsilon as ApproxEq>::default_max_relative()
+            }
+
+            fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+                all_components(&$components(self), &$components(other), |a, b| {
+                    a.abs_diff_eq(b, epsilon)
+                })
+            }
+
+            fn relative_eq(
+                &self,
+                other: &Self,
+                epsilon: Self::Epsilon,
+                max_relative: Self::Epsilon,
+            ) -> bool {
+                all_components(&$components(self), &$components(other), |a, b| {
+                    a.relative_eq(b, epsilon, max_relative)
+                })
+            }
+
+            fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+                all_components(&$components(self), &$components(other), |a, b| {
+                    a.ulps_eq(b, epsilon, max_ulps)
+                })
+            }
+        }
+    };
+}
+
+impl_approx_eq_components!(Mat2, f32, |m: &Mat2| m.m.concat());
+impl_approx_eq_components!(Mat3, f32, |m: &Mat3| m.m.concat());
+impl_approx_eq_components!(Affine2, f32, |a: &Affine2| {
+    let mut components = a.linear.m.concat();
+    components.extend([a.translation.x, a.translation.y]);
+    components
+});
+
+// Shapes compare point by point; polygons need the same vertex count and
+// starting vertex
+impl_approx_eq_components!(Segment, f32, |s: &Segment| [
+    s.start.x, s.start.y, s.end.x, s.end.y
+]);
+impl_approx_eq_components!(Ray, f32, |r: &Ray| [
+    r.origin.x,
+    r.origin.y,
+    r.direction.x,
+    r.direction.y
+]);
+impl_approx_eq_components!(Line, f32, |l: &Line| [
+    l.point.x,
+    l.point.y,
+    l.direction.x,
+    l.direction.y
+]);
+impl_approx_eq_components!(Circle, f32, |c: &Circle| [c.center.x, c.center.y, c.radius]);
+impl_approx_eq_components!(Aabb, f32, |b: &Aabb| [b.min.x, b.min.y, b.max.x, b.max.y]);
+impl_approx_eq_components!(Polygon, f32, |p: &Polygon| {
+    p.vertices()
+        .iter()
+        .flat_map(|v| [v.x, v.y])
+        .collect::<Vec<_>>()
+});
+impl_approx_eq_components!(LinearSpan, f32, |s: &LinearSpan| [
+    s.origin.x,
+    s.origin.y,
+    s.direction.x,
+    s.direction.y,
+    s.t_min,
+    s.t_max
+]);
+impl_approx_eq_components!(Hit, f32, |h: &Hit| [
+    h.point.x, h.point.y, h.normal.x, h.normal.y, h.t
+]);
+impl_approx_eq_components!(ClosestPoints, f32, |c: &ClosestPoints| [
+    c.on_self.x,
+    c.on_self.y,
+    c.on_other.x,
+    c.on_other.y,
+    c.distance
+]);
+
+impl<T: ApproxEq<Epsilon = T> + Copy> ApproxEq for Vector2D<T> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.x.abs_diff_eq(&other.x, epsilon) && self.y.abs_diff_eq(&other.y, epsilon)
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.x.relative_eq(&other.x, epsilon, max_relative)
+            && self.y.relative_eq(&other.y, epsilon, max_relative)
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        self.x.ulps_eq(&other.x, epsilon, max_ulps) && self.y.ulps_eq(&other.y, epsilon, max_ulps)
+    }
+}
+
+impl<T: ApproxEq<Epsilon = T> + Copy, const N: usize> ApproxEq for Vector<T, N> {
+    type Epsilon = T;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        all_components(&self.0, &other.0, |a, b| a.abs_diff_eq(b, epsilon))
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        all_components(&self.0, &other.0, |a, b| {
+            a.relative_eq(b, epsilon, max_relative)
+        })
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        all_components(&self.0, &other.0, |a, b| a.ulps_eq(b, epsilon, max_ulps))
+    }
+}
+
+impl<T: ApproxEq> ApproxEq for Option<T> {
+    type Epsilon = T::Epsilon;
+
+    fn default_epsilon() -> Self::Epsilon {
+        T::default_epsilon()
+    }
+
+    fn default_max_relative() -> Self::Epsilon {
+        T::default_max_relative()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.abs_diff_eq(b, epsilon),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.relative_eq(b, epsilon, max_relative),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.ulps_eq(b, epsilon, max_ulps),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// `assert_approx_eq!(a, b)` - relative comparison with default tolerances,
+/// `assert_approx_eq!(a, b, epsilon = e)` - absolute comparison.
+#[macro_export]
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !$crate::approx::ApproxEq::approx_eq(left, right) {
+                    panic!(
+                        "assertion `left ≈ right` failed\n  left: {:?}\n right: {:?}",
+                        left, right
+                    );
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, epsilon = $epsilon:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if !$crate::approx::ApproxEq::abs_diff_eq(left, right, $epsilon) {
+                    panic!(
+                        "assertion `left ≈ right` failed (epsilon = {:?})\n  left: {:?}\n right: {:?}",
+                        $epsilon, left, right
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// `assert_approx_ne!(a, b)` - the negation of `assert_approx_eq!(a, b)`.
+#[macro_export]
+macro_rules! assert_approx_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                if $crate::approx::ApproxEq::approx_eq(left, right) {
+                    panic!(
+                        "assertion `left !≈ right` failed\n  left: {:?}\n right: {:?}",
+                        left, right
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// `assert_relative_eq!(a, b, max_relative = r)`
+#[macro_export]
+macro_rules! assert_relative_eq {
+    ($left:expr, $right:expr, max_relative = $max_relative:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let epsilon = $crate::approx::default_epsilon_of(left);
+                if !$crate::approx::ApproxEq::relative_eq(left, right, epsilon, $max_relative) {
+                    panic!(
+                        "assertion `left ≈ right` failed (max_relative = {:?})\n  left: {:?}\n right: {:?}",
+                        $max_relative, left, right
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// `assert_ulps_eq!(a, b, max_ulps = n)`
+#[macro_export]
+macro_rules! assert_ulps_eq {
+    ($left:expr, $right:expr, max_ulps = $max_ulps:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let epsilon = $crate::approx::default_epsilon_of(left);
+                if !$crate::approx::ApproxEq::ulps_eq(left, right, epsilon, $max_ulps) {
+                    panic!(
+                        "assertion `left ≈ right` failed (max_ulps = {})\n  left: {:?}\n right: {:?}",
+                        $max_ulps, left, right
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests_approx {
+    use super::*;
+
+    #[test]
+    fn scale_round_trip_is_approximately_equal() {
+        let v = Vector2D::new(1.1f32, -3.7);
+        let round_trip = v.scale(0.1).scale(10.0);
+
+        assert_approx_eq!(round_trip, v);
+        assert!(round_trip.approx_eq(&v));
+    }
+
+    #[test]
+    fn absolute_comparison() {
+        assert!(1.0f32.abs_diff_eq(&1.05, 0.1));
+        assert!(!1.0f32.abs_diff_eq(&1.2, 0.1));
+        assert!(1e-9f64.abs_diff_eq(&0.0, 1e-8));
+        assert_approx_eq!(0.1f32 + 0.2, 0.3, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn relative_comparison() {
+        assert!(1_000_000.0f32.relative_eq(&1_000_001.0, 0.0, 1e-5));
+        assert!(!1_000_000.0f32.relative_eq(&1_000_100.0, 0.0, 1e-5));
+        assert!(0.0f32.relative_eq(&1e-8, 1e-7, 0.0));
+        assert_relative_eq!(100.0f64, 100.0001, max_relative = 1e-5);
+    }
+
+    #[test]
+    fn ulps_comparison() {
+        let a = 1.0f32;
+        let b = f32::from_bits(a.to_bits() + 3);
+
+        assert!(a.ulps_eq(&b, 0.0, 3));
+        assert!(!a.ulps_eq(&b, 0.0, 2));
+        assert!(!1.0f32.ulps_eq(&-1.0, 0.0, u32::MAX));
+        assert!(0.0f32.ulps_eq(&-0.0, 0.0, 0));
+        assert_ulps_eq!(0.1f64 + 0.2, 0.3, max_ulps = 1);
+    }
+
+    #[test]
+    fn nan_and_infinity() {
+        assert!(!f32::NAN.approx_eq(&f32::NAN));
+        assert!(!f32::NAN.ulps_eq(&f32::NAN, 1.0, u32::MAX));
+        assert!(!f64::NAN.abs_diff_eq(&0.0, f64::INFINITY));
+        assert!(f32::INFINITY.approx_eq(&f32::INFINITY));
+        assert!(!f32::INFINITY.approx_eq(&f32::MAX));
+        assert!(!f32::INFINITY.approx_eq(&f32::NEG_INFINITY));
+        assert!(f32::INFINITY.ulps_eq(&f32::INFINITY, 0.0, 0));
+        assert!(!f32::INFINITY.ulps_eq(&f32::MAX, 0.0, 4));
+        assert!(!f64::MAX.ulps_eq(&f64::INFINITY, 0.0, u32::MAX));
+        assert!(!f32::NEG_INFINITY.ulps_eq(&f32::MIN, 0.0, 4));
+        assert!(!Vector2D::new(f32::NAN, 0.0).approx_eq(&Vector2D::new(f32::NAN, 0.0)));
+    }
+
+    #[test]
+    fn vectors_compare_componentwise() {
+        let a = Vector2D::new(1.0f64, 2.0);
+        assert!(a.abs_diff_eq(&Vector2D::new(1.05, 1.95), 0.1));
+        assert!(!a.abs_diff_eq(&Vector2D::new(1.05, 2.2), 0.1));
+
+        let v = crate::vectorn::Vector3D::new([1.0, 2.0, 3.0]);
+        assert_approx_eq!(v.scale(0.1).scale(10.0), v);
+        assert_approx_ne!(v, crate::vectorn::Vector3D::new([1.0, 2.0, 3.001]));
+    }
+
+    #[test]
+    fn matrices_compare_componentwise() {
+        let r = Mat2::rotation(0.3);
+        assert_approx_eq!(r * r.inverse().unwrap(), Mat2::IDENTITY, epsilon = 1e-6);
+        assert_approx_eq!(
+            Affine2::rotation(std::f32::consts::TAU),
+            Affine2::IDENTITY,
+            epsilon = 1e-6
+        );
+        assert_approx_eq!(Mat3::IDENTITY, Affine2::IDENTITY.to_mat3());
+        assert_approx_ne!(Mat2::IDENTITY, Mat2::ZERO);
+    }
+
+    #[test]
+    fn shapes_compare_componentwise() {
+        use crate::geometry::shapes::Shape;
+
+        let v = Vector2D::new;
+        let circle = Circle::new(v(0.0, 0.0), 1.0);
+        let segment = Segment::new(v(2.0, -1.0), v(2.0, 1.0));
+        assert_approx_eq!(
+            circle.closest_points(&segment),
+            ClosestPoints {
+                on_self: v(1.0, 0.0),
+                on_other: v(2.0, 0.0),
+                distance: 1.0,
+            }
+        );
+        assert_approx_eq!(
+            Segment::new(v(0.1, 0.2), v(0.3, 0.4)),
+            Segment::new(v(0.1, 0.2), v(0.1 + 0.2, 0.4))
+        );
+        assert_approx_ne!(circle, Circle::new(v(0.0, 0.0), 1.001));
+        assert_approx_eq!(
+            Aabb::new(v(0.0, 0.0), v(1.0, 1.0)),
+            Aabb::new(v(0.0, 0.0), v(1.0, 1.0 + 1e-7)),
+            epsilon = 1e-6
+        );
+
+        let square = Polygon::new(vec![v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0)]);
+        assert_approx_eq!(square, square.clone());
+        assert_approx_ne!(
+            square,
+            Polygon::new(vec![v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0)])
+        );
+    }
+
+    #[test]
+    fn options() {
+        assert_approx_eq!(Some(1.0f32), Some(1.0 + f32::EPSILON / 2.0));
+        assert_approx_eq!(None::<f32>, None);
+        assert_approx_ne!(Some(1.0f32), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "left ≈ right")]
+    fn failing_assertion_panics() {
+        assert_approx_eq!(Vector2D::new(1.0f32, 2.0), Vector2D::new(1.0, 2.1));
+    }
+
+    #[test]
+    #[should_panic(expected = "epsilon")]
+    fn failing_absolute_assertion_reports_epsilon() {
+        assert_approx_eq!(1.0f32, 1.5, epsilon = 0.1);
+    }
+}
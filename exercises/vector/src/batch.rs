@@ -0,0 +1,325 @@
+use crate::vector2d::Vector2D;
+
+// Structure-of-arrays storage for many f32 vectors.
+//
+// `Vec<Vector2D>` interleaves x and y, so a loop over it works on one vector
+// at a time. Here all x components sit in one array and all y components in
+// another, and every bulk operation is a plain loop over zipped slices with
+// no branches or early exits, which the compiler turns into SIMD code. The
+// operations that combine two batches require equal lengths and panic
+// otherwise, like the slice methods they are built on.
+//
+// Float addition is not associative, so the compiler has to keep a single
+// running total in order and cannot vectorize a plain sum. `sum` therefore
+// adds into `LANES` independent totals and combines them at the end; the
+// result can differ from a sequential sum in the last bits.
+
+const LANES: usize = 8;
+
+fn lane_sum(values: &[f32]) -> f32 {
+    let mut lanes = [0.0f32; LANES];
+    let chunks = values.chunks_exact(LANES);
+    let rest: f32 = chunks.remainder().iter().sum();
+    for chunk in chunks {
+        for (lane, value) in lanes.iter_mut().zip(chunk) {
+            *lane += value;
+        }
+    }
+    lanes.iter().sum::<f32>() + rest
+}
+
+#[derive(Default, Clone, PartialEq, Debug)]
+pub struct Vector2DBatch {
+    xs: Vec<f32>,
+    ys: Vec<f32>,
+}
+
+impl Vector2DBatch {
+    pub fn new() -> Self {
+        Vector2DBatch::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Vector2DBatch {
+            xs: Vec::with_capacity(capacity),
+            ys: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Panics if the component arrays differ in length.
+    pub fn from_components(xs: Vec<f32>, ys: Vec<f32>) -> Self {
+        assert_eq!(xs.len(), ys.len(), "component arrays differ in length");
+        Vector2DBatch { xs, ys }
+    }
+
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    pub fn xs(&self) -> &[f32] {
+        &self.xs
+    }
+
+    pub fn ys(&self) -> &[f32] {
+        &self.ys
+    }
+
+    /// Both component arrays, for operations not covered here.
+    pub fn components_mut(&mut self) -> (&mut [f32], &mut [f32]) {
+        (&mut self.xs, &mut self.ys)
+    }
+
+    pub fn push(&mut self, v: Vector2D) {
+        self.xs.push(v.x);
+        self.ys.push(v.y);
+    }
+
+    pub fn get(&self, index: usize) -> Option<Vector2D> {
+        Some(Vector2D::new(*self.xs.get(index)?, self.ys[index]))
+    }
+
+    /// Panics if `index` is out of range.
+    pub fn set(&mut self, index: usize, v: Vector2D) {
+        self.xs[index] = v.x;
+        self.ys[index] = v.y;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Vector2D> + '_ {
+        self.xs
+            .iter()
+            .zip(&self.ys)
+            .map(|(x, y)| Vector2D::new(*x, *y))
+    }
+
+    pub fn to_vec(&self) -> Vec<Vector2D> {
+        self.iter().collect()
+    }
+
+    /// `self += other * factor`, e.g. positions advanced by velocities
+    /// over a time step.
+    pub fn add_scaled(&mut self, other: &Self, factor: f32) {
+        self.check_len(other);
+        for (x, ox) in self.xs.iter_mut().zip(&other.xs) {
+            *x += ox * factor;
+        }
+        for (y, oy) in self.ys.iter_mut().zip(&other.ys) {
+            *y += oy * factor;
+        }
+    }
+
+    pub fn lengths_squared(&self) -> Vec<f32> {
+        self.xs
+            .iter()
+            .zip(&self.ys)
+            .map(|(x, y)| x * x + y * y)
+            .collect()
+    }
+
+    pub fn lengths(&self) -> Vec<f32> {
+        self.xs
+            .iter()
+            .zip(&self.ys)
+            .map(|(x, y)| (x * x + y * y).sqrt())
+            .collect()
+    }
+
+    /// Scales every vector to unit length. Zero vectors, and vectors so
+    /// short that their squared length underflows, become zero; unlike
+    /// `Vector2D::normalize` there is no per-vector failure case.
+    pub fn normalize(&mut self) {
+        for (x, y) in self.xs.iter_mut().zip(self.ys.iter_mut()) {
+            let length = (*x * *x + *y * *y).sqrt();
+            let inverse = if length > 0.0 { 1.0 / length } else { 0.0 };
+            *x *= inverse;
+            *y *= inverse;
+        }
+    }
+
+    /// Pairwise dot products.
+    pub fn dot(&self, other: &Self) -> Vec<f32> {
+        self.check_len(other);
+        self.xs
+            .iter()
+            .zip(&self.ys)
+            .zip(other.xs.iter().zip(&other.ys))
+            .map(|((x, y), (ox, oy))| x * ox + y * oy)
+            .collect()
+    }
+
+    pub fn sum(&self) -> Vector2D {
+        Vector2D::new(lane_sum(&self.xs), lane_sum(&self.ys))
+    }
+
+    fn check_len(&self, other: &Self) {
+        assert_eq!(self.len(), other.len(), "batches differ in length");
+    }
+}
+
+impl std::ops::AddAssign<&Vector2DBatch> for Vector2DBatch {
+    fn add_assign(&mut self, other: &Vector2DBatch) {
+        self.add_scaled(other, 1.0);
+    }
+}
+
+impl std::ops::SubAssign<&Vector2DBatch> for Vector2DBatch {
+    fn sub_assign(&mut self, other: &Vector2DBatch) {
+        self.check_len(other);
+        for (x, ox) in self.xs.iter_mut().zip(&other.xs) {
+            *x -= ox;
+        }
+        for (y, oy) in self.ys.iter_mut().zip(&other.ys) {
+            *y -= oy;
+        }
+    }
+}
+
+// Adding a single vector translates the whole batch
+impl std::ops::AddAssign<Vector2D> for Vector2DBatch {
+    fn add_assign(&mut self, v: Vector2D) {
+        self.xs.iter_mut().for_each(|x| *x += v.x);
+        self.ys.iter_mut().for_each(|y| *y += v.y);
+    }
+}
+
+impl std::ops::MulAssign<f32> for Vector2DBatch {
+    fn mul_assign(&mut self, factor: f32) {
+        self.xs.iter_mut().for_each(|x| *x *= factor);
+        self.ys.iter_mut().for_each(|y| *y *= factor);
+    }
+}
+
+impl From<&[Vector2D]> for Vector2DBatch {
+    fn from(vectors: &[Vector2D]) -> Self {
+        Vector2DBatch {
+            xs: vectors.iter().map(|v| v.x).collect(),
+            ys: vectors.iter().map(|v| v.y).collect(),
+        }
+    }
+}
+
+impl From<Vec<Vector2D>> for Vector2DBatch {
+    fn from(vectors: Vec<Vector2D>) -> Self {
+        Vector2DBatch::from(vectors.as_slice())
+    }
+}
+
+impl From<Vector2DBatch> for Vec<Vector2D> {
+    fn from(batch: Vector2DBatch) -> Self {
+        batch.to_vec()
+    }
+}
+
+impl FromIterator<Vector2D> for Vector2DBatch {
+    fn from_iter<I: IntoIterator<Item = Vector2D>>(iter: I) -> Self {
+        let mut batch = Vector2DBatch::new();
+        batch.extend(iter);
+        batch
+    }
+}
+
+impl Extend<Vector2D> for Vector2DBatch {
+    fn extend<I: IntoIterator<Item = Vector2D>>(&mut self, iter: I) {
+        for v in iter {
+            self.push(v);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_batch {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::testing::random_points;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        let vectors = random_points(100, 7);
+        let batch = Vector2DBatch::from(vectors.as_slice());
+        assert_eq!(batch.len(), 100);
+        assert_eq!(batch.xs()[3], vectors[3].x);
+        assert_eq!(batch.ys()[3], vectors[3].y);
+        assert_eq!(batch.to_vec(), vectors);
+        assert_eq!(Vec::<Vector2D>::from(batch.clone()), vectors);
+        assert_eq!(vectors.iter().copied().collect::<Vector2DBatch>(), batch);
+        assert_eq!(Vector2DBatch::from(vectors.clone()), batch);
+
+        let mut small = Vector2DBatch::with_capacity(2);
+        assert!(small.is_empty());
+        small.push(v(1.0, 2.0));
+        small.extend([v(3.0, 4.0)]);
+        small.set(0, v(5.0, 6.0));
+        assert_eq!(small.get(0), Some(v(5.0, 6.0)));
+        assert_eq!(small.get(2), None);
+        assert_eq!(
+            small,
+            Vector2DBatch::from_components(vec![5.0, 3.0], vec![6.0, 4.0])
+        );
+    }
+
+    #[test]
+    fn bulk_operations_match_vector_operations() {
+        let (a, b) = (random_points(257, 1), random_points(257, 2));
+        let (mut batch, other) = (
+            Vector2DBatch::from(a.as_slice()),
+            Vector2DBatch::from(b.as_slice()),
+        );
+
+        let lengths = batch.lengths();
+        let squared = batch.lengths_squared();
+        let dots = batch.dot(&other);
+        for i in 0..a.len() {
+            assert_eq!(lengths[i], a[i].length());
+            assert_eq!(squared[i], a[i].length_squared());
+            assert_eq!(dots[i], a[i].dot(&b[i]));
+        }
+        assert_approx_eq!(batch.sum().x, a.iter().sum::<Vector2D>().x, epsilon = 1e-2);
+        assert_approx_eq!(batch.sum().y, a.iter().sum::<Vector2D>().y, epsilon = 1e-2);
+        // shorter than one set of lanes, and exact in any order
+        let small = Vector2DBatch::from(vec![v(1.0, 2.0), v(3.0, 4.0), v(5.0, 6.0)]);
+        assert_eq!(small.sum(), v(9.0, 12.0));
+        assert_eq!(Vector2DBatch::new().sum(), Vector2D::ZERO);
+
+        batch += &other;
+        batch -= &other;
+        batch.add_scaled(&other, 0.5);
+        batch *= 2.0;
+        batch += v(1.0, -1.0);
+        for (i, result) in batch.iter().enumerate() {
+            assert_eq!(
+                result,
+                (a[i] + b[i] - b[i] + b[i] * 0.5) * 2.0 + v(1.0, -1.0)
+            );
+        }
+
+        batch.normalize();
+        for (i, unit) in batch.iter().enumerate() {
+            let expected = ((a[i] + b[i] * 0.5) * 2.0 + v(1.0, -1.0))
+                .normalize()
+                .unwrap();
+            assert_approx_eq!(unit.x, expected.x, epsilon = 1e-6);
+            assert_approx_eq!(unit.y, expected.y, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn normalizing_zero_vectors_gives_zero() {
+        let mut batch = Vector2DBatch::from(vec![v(0.0, 0.0), v(3.0, 4.0), v(-0.0, 0.0)]);
+        batch.normalize();
+        assert_eq!(batch.to_vec(), [v(0.0, 0.0), v(0.6, 0.8), v(0.0, 0.0)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "batches differ in length")]
+    fn mismatched_lengths_panic() {
+        let mut a = Vector2DBatch::from(vec![v(1.0, 2.0)]);
+        a += &Vector2DBatch::new();
+    }
+}
@@ -0,0 +1,556 @@
+use crate::geometry::predicates;
+use crate::geometry::shapes::TOLERANCE;
+use crate::vector2d::Vector2D;
+use std::cmp::Ordering;
+
+// Algorithms over point sets and polygons given as vertex slices.
+//
+// Orientation tests use the exact predicates, so collinear inputs are
+// recognised as such instead of flipping sign on rounding noise.
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    Collinear,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Containment {
+    Inside,
+    OnBoundary,
+    Outside,
+}
+
+/// Twice the signed area of the triangle (a, b, c), positive when counter-clockwise.
+/// The sign is exact, see `predicates::orient2d`.
+pub fn cross(a: Vector2D, b: Vector2D, c: Vector2D) -> f64 {
+    predicates::orient2d(a, b, c)
+}
+
+pub fn orientation(a: Vector2D, b: Vector2D, c: Vector2D) -> Orientation {
+    let cross = cross(a, b, c);
+    if cross > 0.0 {
+        Orientation::CounterClockwise
+    } else if cross < 0.0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+fn lexicographic(a: &Vector2D, b: &Vector2D) -> Ordering {
+    a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+}
+
+/// Convex hull in counter-clockwise order starting at the lowest-leftmost point
+/// (Andrew's monotone chain). Duplicates and points on hull edges are dropped;
+/// fewer than three distinct or only collinear points yield the extreme points.
+pub fn convex_hull(points: &[Vector2D]) -> Vec<Vector2D> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(lexicographic);
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<Vector2D> = Vec::with_capacity(sorted.len() + 1);
+    let turns_left =
+        |hull: &[Vector2D], p: Vector2D| cross(hull[hull.len() - 2], hull[hull.len() - 1], p) > 0.0;
+
+    for &p in &sorted {
+        while hull.len() >= 2 && !turns_left(&hull, p) {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    // the upper chain may not pop into the lower one
+    let lower_len = hull.len() + 1;
+    for &p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower_len && !turns_left(&hull, p) {
+            hull.pop();
+        }
+        hull.push(p);
+    }
+
+    // the upper chain ends at the starting point
+    hull.pop();
+    hull
+}
+
+/// Shoelace formula, positive for counter-clockwise polygons.
+pub fn polygon_signed_area(polygon: &[Vector2D]) -> f32 {
+    let n = polygon.len();
+    let twice: f64 = (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            a.x as f64 * b.y as f64 - b.x as f64 * a.y as f64
+        })
+        .sum();
+    (twice / 2.0) as f32
+}
+
+pub fn polygon_area(polygon: &[Vector2D]) -> f32 {
+    polygon_signed_area(polygon).abs()
+}
+
+/// Winding order; degenerate polygons (zero area) are `Collinear`.
+pub fn polygon_orientation(polygon: &[Vector2D]) -> Orientation {
+    let area = polygon_signed_area(polygon);
+    if area > 0.0 {
+        Orientation::CounterClockwise
+    } else if area < 0.0 {
+        Orientation::Clockwise
+    } else {
+        Orientation::Collinear
+    }
+}
+
+/// Centroid of the enclosed area, `None` for degenerate polygons.
+pub fn polygon_centroid(polygon: &[Vector2D]) -> Option<Vector2D> {
+    let n = polygon.len();
+    if n < 3 {
+        return None;
+    }
+
+    // relative to the first vertex to keep the products small
+    let origin = polygon[0];
+    let (mut area, mut cx, mut cy) = (0.0f64, 0.0f64, 0.0f64);
+    for i in 0..n {
+        let a = polygon[i] - origin;
+        let b = polygon[(i + 1) % n] - origin;
+        let w = a.x as f64 * b.y as f64 - b.x as f64 * a.y as f64;
+        area += w;
+        cx += (a.x as f64 + b.x as f64) * w;
+        cy += (a.y as f64 + b.y as f64) * w;
+    }
+    if area == 0.0 {
+        return None;
+    }
+    Some(origin + Vector2D::new((cx / (3.0 * area)) as f32, (cy / (3.0 * area)) as f32))
+}
+
+/// Point-in-polygon by crossing number; points within `TOLERANCE` of an edge are on the boundary.
+pub fn point_in_polygon(p: Vector2D, polygon: &[Vector2D]) -> Containment {
+    let n = polygon.len();
+    let mut inside = false;
+
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        if distance_to_segment(p, a, b) <= TOLERANCE {
+            return Containment::OnBoundary;
+        }
+        // count edges crossing the horizontal ray to the right of p
+        if (a.y > p.y) != (b.y > p.y) {
+            let x = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+            if x > p.x {
+                inside = !inside;
+            }
+        }
+    }
+
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+pub(crate) fn distance_to_segment(p: Vector2D, a: Vector2D, b: Vector2D) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        ((p - a).dot(&ab) / length_squared).clamp(0.0, 1.0)
+    };
+    (a + ab * t).distance(&p)
+}
+
+/// Ear-clipping triangulation of a simple polygon in either winding order.
+///
+/// Returns triangles as counter-clockwise index triples into `polygon`.
+/// Repeated and collinear vertices are skipped, so they produce no
+/// zero-area triangles.
+pub fn triangulate(polygon: &[Vector2D]) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..polygon.len()).collect();
+    remaining.dedup_by(|a, b| polygon[*a] == polygon[*b]);
+    while remaining.len() > 1 && polygon[remaining[0]] == polygon[*remaining.last().unwrap()] {
+        remaining.pop();
+    }
+    if polygon_orientation(polygon) == Orientation::Clockwise {
+        remaining.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(remaining.len().saturating_sub(2));
+    while remaining.len() >= 3 {
+        let n = remaining.len();
+        let corner = |i: usize| {
+            (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            )
+        };
+
+        // collinear vertices contribute nothing and would block every ear
+        if let Some(i) = (0..n).find(|&i| {
+            let (a, b, c) = corner(i);
+            orientation(polygon[a], polygon[b], polygon[c]) == Orientation::Collinear
+        }) {
+            remaining.remove(i);
+            continue;
+        }
+
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = corner(i);
+            orientation(polygon[a], polygon[b], polygon[c]) == Orientation::CounterClockwise
+                && remaining.iter().all(|&j| {
+                    let p = polygon[j];
+                    p == polygon[a]
+                        || p == polygon[b]
+                        || p == polygon[c]
+                        || !in_triangle(p, polygon[a], polygon[b], polygon[c])
+                })
+        });
+
+        match ear {
+            Some(i) => {
+                let (a, b, c) = corner(i);
+                triangles.push([a, b, c]);
+                remaining.remove(i);
+            }
+            // not a simple polygon: stop rather than emit overlapping triangles
+            None => break,
+        }
+    }
+    triangles
+}
+
+// Closed test for a counter-clockwise triangle
+fn in_triangle(p: Vector2D, a: Vector2D, b: Vector2D, c: Vector2D) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+/// Indices `(i, j)` with `i < j` of the two closest points, `None` for fewer
+/// than two points. Duplicates are at distance zero. Divide and conquer, O(n log n).
+pub fn closest_pair(points: &[Vector2D]) -> Option<(usize, usize)> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mut by_x: Vec<usize> = (0..points.len()).collect();
+    by_x.sort_by(|&a, &b| lexicographic(&points[a], &points[b]));
+    let (i, j, _) = closest_pair_recursive(points, &mut by_x);
+    Some((i.min(j), i.max(j)))
+}
+
+// Returns with `indices` sorted by y, merging the halves like merge sort
+fn closest_pair_recursive(points: &[Vector2D], indices: &mut [usize]) -> (usize, usize, f32) {
+    let n = indices.len();
+    if n <= 3 {
+        let mut best = (indices[0], indices[1], f32::INFINITY);
+        for a in 0..n {
+            for b in a + 1..n {
+                let d = points[indices[a]].distance_squared(&points[indices[b]]);
+                if d < best.2 {
+                    best = (indices[a], indices[b], d);
+                }
+            }
+        }
+        indices.sort_by(|&a, &b| points[a].y.total_cmp(&points[b].y));
+        return best;
+    }
+
+    let mid = n / 2;
+    let split_x = points[indices[mid]].x;
+    let (left, right) = indices.split_at_mut(mid);
+    let l = closest_pair_recursive(points, left);
+    let r = closest_pair_recursive(points, right);
+    let mut best = if l.2 <= r.2 { l } else { r };
+
+    let mut merged = Vec::with_capacity(n);
+    let (mut a, mut b) = (0, mid);
+    while a < mid || b < n {
+        if b == n || (a < mid && points[indices[a]].y <= points[indices[b]].y) {
+            merged.push(indices[a]);
+            a += 1;
+        } else {
+            merged.push(indices[b]);
+            b += 1;
+        }
+    }
+    indices.copy_from_slice(&merged);
+
+    let strip: Vec<usize> = indices
+        .iter()
+        .copied()
+        .filter(|&i| {
+            let dx = points[i].x - split_x;
+            dx * dx < best.2
+        })
+        .collect();
+    for (k, &a) in strip.iter().enumerate() {
+        for &b in &strip[k + 1..] {
+            let dy = points[b].y - points[a].y;
+            if dy * dy >= best.2 {
+                break;
+            }
+            let d = points[a].distance_squared(&points[b]);
+            if d < best.2 {
+                best = (a, b, d);
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests_algorithms {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::testing::random_points;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    fn triangulated_area(polygon: &[Vector2D], triangles: &[[usize; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|t| polygon_signed_area(&[polygon[t[0]], polygon[t[1]], polygon[t[2]]]))
+            .sum()
+    }
+
+    #[test]
+    fn orientation_of_triangles() {
+        assert_eq!(
+            orientation(v(0.0, 0.0), v(1.0, 0.0), v(0.0, 1.0)),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(
+            orientation(v(0.0, 0.0), v(0.0, 1.0), v(1.0, 0.0)),
+            Orientation::Clockwise
+        );
+        assert_eq!(
+            orientation(v(0.0, 0.0), v(1.0, 1.0), v(3.0, 3.0)),
+            Orientation::Collinear
+        );
+        // points on the diagonal are exactly collinear even with inexact coordinates
+        assert_eq!(
+            orientation(v(0.1, 0.1), v(0.2, 0.2), v(0.3, 0.3)),
+            Orientation::Collinear
+        );
+    }
+
+    #[test]
+    fn convex_hull_of_square_with_interior_and_edge_points() {
+        let points = [
+            v(1.0, 1.0),
+            v(0.0, 0.0),
+            v(2.0, 0.0),
+            v(1.0, 0.0), // on an edge
+            v(2.0, 2.0),
+            v(0.0, 2.0),
+            v(0.0, 2.0), // duplicate
+            v(0.5, 1.5),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0), v(0.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn convex_hull_degenerate_inputs() {
+        assert!(convex_hull(&[]).is_empty());
+        assert_eq!(convex_hull(&[v(1.0, 1.0)]), vec![v(1.0, 1.0)]);
+        assert_eq!(
+            convex_hull(&[v(1.0, 1.0), v(1.0, 1.0), v(1.0, 1.0)]),
+            vec![v(1.0, 1.0)]
+        );
+        assert_eq!(
+            convex_hull(&[v(2.0, 2.0), v(0.0, 0.0), v(1.0, 1.0), v(3.0, 3.0)]),
+            vec![v(0.0, 0.0), v(3.0, 3.0)]
+        );
+    }
+
+    #[test]
+    fn convex_hull_contains_all_points() {
+        let points = random_points(500, 42);
+        let hull = convex_hull(&points);
+        assert!(hull.len() >= 3);
+        assert_eq!(polygon_orientation(&hull), Orientation::CounterClockwise);
+        for i in 0..hull.len() {
+            let (a, b, c) = (
+                hull[i],
+                hull[(i + 1) % hull.len()],
+                hull[(i + 2) % hull.len()],
+            );
+            assert_eq!(orientation(a, b, c), Orientation::CounterClockwise);
+        }
+        for p in points {
+            assert_ne!(point_in_polygon(p, &hull), Containment::Outside);
+        }
+    }
+
+    #[test]
+    fn area_centroid_and_orientation() {
+        let square = [v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0), v(0.0, 2.0)];
+        assert_eq!(polygon_signed_area(&square), 4.0);
+        assert_eq!(polygon_orientation(&square), Orientation::CounterClockwise);
+        assert_eq!(polygon_centroid(&square), Some(v(1.0, 1.0)));
+
+        let clockwise: Vec<Vector2D> = square.iter().rev().copied().collect();
+        assert_eq!(polygon_signed_area(&clockwise), -4.0);
+        assert_eq!(polygon_area(&clockwise), 4.0);
+        assert_eq!(polygon_orientation(&clockwise), Orientation::Clockwise);
+        assert_eq!(polygon_centroid(&clockwise), Some(v(1.0, 1.0)));
+
+        // L shape: 2x1 bar plus 1x1 block on its left end
+        let l_shape = [
+            v(0.0, 0.0),
+            v(2.0, 0.0),
+            v(2.0, 1.0),
+            v(1.0, 1.0),
+            v(1.0, 2.0),
+            v(0.0, 2.0),
+        ];
+        assert_eq!(polygon_area(&l_shape), 3.0);
+        assert_approx_eq!(polygon_centroid(&l_shape).unwrap(), v(5.0 / 6.0, 5.0 / 6.0));
+
+        let flat = [v(0.0, 0.0), v(1.0, 1.0), v(2.0, 2.0)];
+        assert_eq!(polygon_orientation(&flat), Orientation::Collinear);
+        assert_eq!(polygon_centroid(&flat), None);
+        assert_eq!(polygon_centroid(&[v(0.0, 0.0), v(1.0, 1.0)]), None);
+    }
+
+    #[test]
+    fn centroid_far_from_origin() {
+        let offset = v(10000.0, -20000.0);
+        let square: Vec<Vector2D> = [v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0), v(0.0, 2.0)]
+            .iter()
+            .map(|&p| p + offset)
+            .collect();
+        assert_eq!(polygon_centroid(&square), Some(v(1.0, 1.0) + offset));
+    }
+
+    #[test]
+    fn point_in_polygon_cases() {
+        let u = [
+            v(0.0, 0.0),
+            v(3.0, 0.0),
+            v(3.0, 3.0),
+            v(2.0, 3.0),
+            v(2.0, 1.0),
+            v(1.0, 1.0),
+            v(1.0, 3.0),
+            v(0.0, 3.0),
+        ];
+        assert_eq!(point_in_polygon(v(0.5, 2.0), &u), Containment::Inside);
+        assert_eq!(point_in_polygon(v(1.5, 2.0), &u), Containment::Outside);
+        assert_eq!(point_in_polygon(v(1.5, 1.0), &u), Containment::OnBoundary);
+        assert_eq!(point_in_polygon(v(3.0, 3.0), &u), Containment::OnBoundary);
+        // the ray to the right passes exactly through vertices
+        assert_eq!(point_in_polygon(v(0.5, 3.0), &u), Containment::OnBoundary);
+        assert_eq!(point_in_polygon(v(-1.0, 1.0), &u), Containment::Outside);
+        assert_eq!(point_in_polygon(v(0.5, 1.0), &u), Containment::Inside);
+    }
+
+    #[test]
+    fn triangulate_convex_and_clockwise() {
+        let square = [v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0)];
+        let triangles = triangulate(&square);
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangulated_area(&square, &triangles), 1.0);
+
+        let clockwise: Vec<Vector2D> = square.iter().rev().copied().collect();
+        let triangles = triangulate(&clockwise);
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangulated_area(&clockwise, &triangles), 1.0);
+    }
+
+    #[test]
+    fn triangulate_concave() {
+        let u = [
+            v(0.0, 0.0),
+            v(3.0, 0.0),
+            v(3.0, 3.0),
+            v(2.0, 3.0),
+            v(2.0, 1.0),
+            v(1.0, 1.0),
+            v(1.0, 3.0),
+            v(0.0, 3.0),
+        ];
+        let triangles = triangulate(&u);
+        assert_eq!(triangles.len(), u.len() - 2);
+        assert_eq!(triangulated_area(&u, &triangles), 7.0);
+        for t in &triangles {
+            let centroid = (u[t[0]] + u[t[1]] + u[t[2]]) / 3.0;
+            assert_eq!(point_in_polygon(centroid, &u), Containment::Inside);
+        }
+    }
+
+    #[test]
+    fn triangulate_with_collinear_and_duplicate_vertices() {
+        let polygon = [
+            v(0.0, 0.0),
+            v(1.0, 0.0),
+            v(2.0, 0.0), // collinear
+            v(2.0, 0.0), // duplicate
+            v(2.0, 2.0),
+            v(0.0, 2.0),
+            v(0.0, 0.0), // closing duplicate
+        ];
+        let triangles = triangulate(&polygon);
+        assert_eq!(triangulated_area(&polygon, &triangles), 4.0);
+        for t in &triangles {
+            assert_eq!(
+                orientation(polygon[t[0]], polygon[t[1]], polygon[t[2]]),
+                Orientation::CounterClockwise
+            );
+        }
+        assert!(triangulate(&[v(0.0, 0.0), v(1.0, 1.0), v(2.0, 2.0)]).is_empty());
+    }
+
+    #[test]
+    fn closest_pair_small_inputs() {
+        assert_eq!(closest_pair(&[]), None);
+        assert_eq!(closest_pair(&[v(1.0, 1.0)]), None);
+        assert_eq!(closest_pair(&[v(0.0, 0.0), v(5.0, 5.0)]), Some((0, 1)));
+        assert_eq!(
+            closest_pair(&[v(0.0, 0.0), v(10.0, 0.0), v(4.0, 0.0), v(5.0, 0.0)]),
+            Some((2, 3))
+        );
+    }
+
+    #[test]
+    fn closest_pair_with_duplicates() {
+        let mut points = random_points(200, 9);
+        points.push(points[17]);
+        let (i, j) = closest_pair(&points).unwrap();
+        assert_eq!(points[i].distance(&points[j]), 0.0);
+    }
+
+    #[test]
+    fn closest_pair_matches_brute_force() {
+        for seed in 1..20 {
+            let points = random_points(300, seed);
+            let (i, j) = closest_pair(&points).unwrap();
+
+            let mut best = f32::INFINITY;
+            for a in 0..points.len() {
+                for b in a + 1..points.len() {
+                    best = best.min(points[a].distance_squared(&points[b]));
+                }
+            }
+            assert_eq!(
+                points[i].distance_squared(&points[j]),
+                best,
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
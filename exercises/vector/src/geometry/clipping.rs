@@ -0,0 +1,809 @@
+use crate::geometry::algorithms::{
+    Containment, distance_to_segment, point_in_polygon, polygon_signed_area,
+};
+use crate::geometry::predicates::orient2d;
+use crate::geometry::shapes::{Aabb, TOLERANCE};
+use crate::vector2d::Vector2D;
+use std::collections::HashMap;
+
+// Polygon clipping and boolean operations.
+//
+// Sutherland-Hodgman clips against one half-plane at a time, which is all a
+// convex window needs. General polygons go through an overlay in the spirit
+// of Martinez-Rueda: every edge of either operand is split where it meets
+// the other operand's boundary, so each piece lies entirely inside, outside
+// or on that boundary. The operation then decides per piece whether it
+// bounds the result, and the kept pieces are linked back into rings.
+//
+// Rings are oriented so that the area is on the left of every edge (outer
+// rings counter-clockwise, holes clockwise). Pieces on the other boundary
+// are shared edges; their direction tells whether the two areas lie on the
+// same side. Where several kept pieces leave one vertex, the ring continues
+// along the sharpest left turn, which separates polygons and holes that
+// merely touch at a point.
+//
+// Vertices within `TOLERANCE` of a vertex or edge of the other operand are
+// snapped onto it, so nearly coincident boundaries are treated as shared
+// instead of producing slivers.
+
+/// Polygon with holes. `new` orients the outer ring counter-clockwise and
+/// the holes clockwise, as the boolean operations expect.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PolygonWithHoles {
+    pub outer: Vec<Vector2D>,
+    pub holes: Vec<Vec<Vector2D>>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Operation {
+    Union,
+    Intersection,
+    /// The first operand minus the second.
+    Difference,
+    Xor,
+}
+
+impl PolygonWithHoles {
+    pub fn new(outer: Vec<Vector2D>, holes: Vec<Vec<Vector2D>>) -> Self {
+        PolygonWithHoles {
+            outer: oriented(outer, true),
+            holes: holes
+                .into_iter()
+                .map(|hole| oriented(hole, false))
+                .collect(),
+        }
+    }
+
+    /// Area of the outer ring minus the areas of the holes.
+    pub fn area(&self) -> f32 {
+        polygon_signed_area(&self.outer).abs()
+            - self
+                .holes
+                .iter()
+                .map(|hole| polygon_signed_area(hole).abs())
+                .sum::<f32>()
+    }
+
+    pub fn contains(&self, p: Vector2D) -> Containment {
+        match point_in_polygon(p, &self.outer) {
+            Containment::Inside => {}
+            other => return other,
+        }
+        for hole in &self.holes {
+            match point_in_polygon(p, hole) {
+                Containment::Inside => return Containment::Outside,
+                Containment::OnBoundary => return Containment::OnBoundary,
+                Containment::Outside => {}
+            }
+        }
+        Containment::Inside
+    }
+}
+
+impl From<Vec<Vector2D>> for PolygonWithHoles {
+    fn from(outer: Vec<Vector2D>) -> Self {
+        PolygonWithHoles::new(outer, Vec::new())
+    }
+}
+
+// Drops repeated vertices and reverses the ring if needed
+fn oriented(mut ring: Vec<Vector2D>, counter_clockwise: bool) -> Vec<Vector2D> {
+    ring.dedup();
+    while ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    if (polygon_signed_area(&ring) > 0.0) != counter_clockwise {
+        ring.reverse();
+    }
+    ring
+}
+
+/////////////////////////////////////////////////////////
+// Sutherland-Hodgman
+
+/// Part of `polygon` on the side of the line through `point` that `normal`
+/// points away from.
+pub fn clip_to_half_plane(
+    polygon: &[Vector2D],
+    point: Vector2D,
+    normal: Vector2D,
+) -> Vec<Vector2D> {
+    let side = |p: Vector2D| (p - point).dot(&normal);
+
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        let (sa, sb) = (side(a), side(b));
+        if sa <= 0.0 {
+            clipped.push(a);
+        }
+        if (sa < 0.0 && sb > 0.0) || (sa > 0.0 && sb < 0.0) {
+            clipped.push(a.lerp(&b, sa / (sa - sb)));
+        }
+    }
+    clipped
+}
+
+/// Sutherland-Hodgman: the part of `subject` inside the convex `window`, in
+/// either winding. Where the result of a concave subject falls apart, the
+/// pieces stay joined by zero-width edges along the window's boundary; use
+/// `boolean` to get them as separate polygons.
+pub fn clip_convex(subject: &[Vector2D], window: &[Vector2D]) -> Vec<Vector2D> {
+    let sign = if polygon_signed_area(window) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    let mut clipped = subject.to_vec();
+    for i in 0..window.len() {
+        if clipped.is_empty() {
+            break;
+        }
+        let (a, b) = (window[i], window[(i + 1) % window.len()]);
+        let outward = -(b - a).perpendicular() * sign;
+        clipped = clip_to_half_plane(&clipped, a, outward);
+    }
+    clipped
+}
+
+/////////////////////////////////////////////////////////
+// Boolean operations
+
+pub fn union(a: &[PolygonWithHoles], b: &[PolygonWithHoles]) -> Vec<PolygonWithHoles> {
+    boolean(a, b, Operation::Union)
+}
+
+pub fn intersection(a: &[PolygonWithHoles], b: &[PolygonWithHoles]) -> Vec<PolygonWithHoles> {
+    boolean(a, b, Operation::Intersection)
+}
+
+pub fn difference(a: &[PolygonWithHoles], b: &[PolygonWithHoles]) -> Vec<PolygonWithHoles> {
+    boolean(a, b, Operation::Difference)
+}
+
+pub fn xor(a: &[PolygonWithHoles], b: &[PolygonWithHoles]) -> Vec<PolygonWithHoles> {
+    boolean(a, b, Operation::Xor)
+}
+
+/// Applies `operation` to the areas covered by `a` and `b`. Each operand is
+/// a set of polygons whose rings do not cross each other (such as the
+/// result of an earlier operation). The result uses the same form, with
+/// collinear vertices removed and zero-area parts dropped.
+pub fn boolean(
+    a: &[PolygonWithHoles],
+    b: &[PolygonWithHoles],
+    operation: Operation,
+) -> Vec<PolygonWithHoles> {
+    let rings_a = rings(a);
+    let mut rings_b = rings(b);
+    snap(&rings_a, &mut rings_b);
+    let (edges_a, edges_b) = split(&ring_edges(&rings_a), &ring_edges(&rings_b));
+
+    // `None` drops a piece, `Some(reversed)` keeps it
+    let mut kept = Vec::new();
+    for (start, end) in edges_a {
+        let reversed = match (classify(start, end, &rings_b), operation) {
+            (Class::Outside, Operation::Union | Operation::Difference | Operation::Xor) => {
+                Some(false)
+            }
+            (Class::Inside, Operation::Intersection) => Some(false),
+            (Class::Inside, Operation::Xor) => Some(true),
+            (Class::Same, Operation::Union | Operation::Intersection) => Some(false),
+            (Class::Opposite, Operation::Difference) => Some(false),
+            _ => None,
+        };
+        match reversed {
+            Some(false) => kept.push((start, end)),
+            Some(true) => kept.push((end, start)),
+            None => {}
+        }
+    }
+    // shared pieces were decided from the side of `a`
+    for (start, end) in edges_b {
+        let reversed = match (classify(start, end, &rings_a), operation) {
+            (Class::Outside, Operation::Union | Operation::Xor) => Some(false),
+            (Class::Inside, Operation::Intersection) => Some(false),
+            (Class::Inside, Operation::Difference | Operation::Xor) => Some(true),
+            _ => None,
+        };
+        match reversed {
+            Some(false) => kept.push((start, end)),
+            Some(true) => kept.push((end, start)),
+            None => {}
+        }
+    }
+
+    assemble(link(&kept))
+}
+
+// Rings of all polygons, outer rings counter-clockwise and holes clockwise
+fn rings(polygons: &[PolygonWithHoles]) -> Vec<Vec<Vector2D>> {
+    polygons
+        .iter()
+        .flat_map(|polygon| {
+            std::iter::once(oriented(polygon.outer.clone(), true)).chain(
+                polygon
+                    .holes
+                    .iter()
+                    .map(|hole| oriented(hole.clone(), false)),
+            )
+        })
+        .filter(|ring| polygon_signed_area(ring) != 0.0)
+        .collect()
+}
+
+fn ring_edges(rings: &[Vec<Vector2D>]) -> Vec<(Vector2D, Vector2D)> {
+    rings
+        .iter()
+        .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+        .collect()
+}
+
+// Moves vertices of `b` onto nearby vertices of `a`
+fn snap(a: &[Vec<Vector2D>], b: &mut [Vec<Vector2D>]) {
+    for p in b.iter_mut().flatten() {
+        if let Some(q) = a.iter().flatten().find(|q| q.distance(p) <= TOLERANCE) {
+            *p = *q;
+        }
+    }
+}
+
+// `p` is close to the inside of the edge (s, e) but not to its end points
+fn lies_on(p: Vector2D, s: Vector2D, e: Vector2D) -> bool {
+    p.distance(&s) > TOLERANCE
+        && p.distance(&e) > TOLERANCE
+        && distance_to_segment(p, s, e) <= TOLERANCE
+}
+
+// Point where the edges cross at a single point inside both
+fn crossing(a0: Vector2D, a1: Vector2D, b0: Vector2D, b1: Vector2D) -> Option<Vector2D> {
+    let (o1, o2) = (orient2d(a0, a1, b0), orient2d(a0, a1, b1));
+    let (o3, o4) = (orient2d(b0, b1, a0), orient2d(b0, b1, a1));
+    let opposite = |x: f64, y: f64| (x < 0.0 && y > 0.0) || (x > 0.0 && y < 0.0);
+    if !opposite(o1, o2) || !opposite(o3, o4) {
+        return None;
+    }
+    let t = o3 / (o3 - o4);
+    Some(Vector2D::new(
+        (a0.x as f64 + (a1.x as f64 - a0.x as f64) * t) as f32,
+        (a0.y as f64 + (a1.y as f64 - a0.y as f64) * t) as f32,
+    ))
+}
+
+type Edges = Vec<(Vector2D, Vector2D)>;
+
+// Splits the edges of each side wherever the other side's boundary meets them
+fn split(a: &[(Vector2D, Vector2D)], b: &[(Vector2D, Vector2D)]) -> (Edges, Edges) {
+    let mut cuts_a = vec![Vec::new(); a.len()];
+    let mut cuts_b = vec![Vec::new(); b.len()];
+    for (i, &(a0, a1)) in a.iter().enumerate() {
+        let bounds_a = Aabb::new(a0, a1).expand(TOLERANCE);
+        for (j, &(b0, b1)) in b.iter().enumerate() {
+            let bounds_b = Aabb::new(b0, b1);
+            if bounds_a.min.x > bounds_b.max.x
+                || bounds_b.min.x > bounds_a.max.x
+                || bounds_a.min.y > bounds_b.max.y
+                || bounds_b.min.y > bounds_a.max.y
+            {
+                continue;
+            }
+
+            let mut touching = false;
+            for p in [b0, b1] {
+                if lies_on(p, a0, a1) {
+                    cuts_a[i].push(p);
+                    touching = true;
+                }
+            }
+            for p in [a0, a1] {
+                if lies_on(p, b0, b1) {
+                    cuts_b[j].push(p);
+                    touching = true;
+                }
+            }
+            if !touching && let Some(p) = crossing(a0, a1, b0, b1) {
+                cuts_a[i].push(p);
+                cuts_b[j].push(p);
+            }
+        }
+    }
+    (subdivide(a, cuts_a), subdivide(b, cuts_b))
+}
+
+fn subdivide(edges: &[(Vector2D, Vector2D)], cuts: Vec<Vec<Vector2D>>) -> Edges {
+    let mut pieces = Vec::with_capacity(edges.len());
+    for (&(start, end), mut cuts) in edges.iter().zip(cuts) {
+        let direction = end - start;
+        cuts.sort_by(|p, q| {
+            (*p - start)
+                .dot(&direction)
+                .total_cmp(&(*q - start).dot(&direction))
+        });
+        let mut previous = start;
+        for p in cuts.into_iter().chain([end]) {
+            if p != previous {
+                pieces.push((previous, p));
+                previous = p;
+            }
+        }
+    }
+    pieces
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Class {
+    Inside,
+    Outside,
+    /// On the other boundary, with the other area on the same side.
+    Same,
+    /// On the other boundary, with the other area on the opposite side.
+    Opposite,
+}
+
+fn classify(start: Vector2D, end: Vector2D, rings: &[Vec<Vector2D>]) -> Class {
+    let midpoint = start.lerp(&end, 0.5);
+    for (p, q) in ring_edges(rings) {
+        if distance_to_segment(midpoint, p, q) <= TOLERANCE {
+            return if (end - start).dot(&(q - p)) > 0.0 {
+                Class::Same
+            } else {
+                Class::Opposite
+            };
+        }
+    }
+    let containing = rings
+        .iter()
+        .filter(|ring| point_in_polygon(midpoint, ring) == Containment::Inside)
+        .count();
+    if containing % 2 == 1 {
+        Class::Inside
+    } else {
+        Class::Outside
+    }
+}
+
+// Joins directed edges into closed rings
+fn link(edges: &[(Vector2D, Vector2D)]) -> Vec<Vec<Vector2D>> {
+    // +0.0 turns -0.0 into 0.0 so that both hash alike
+    let key = |p: Vector2D| ((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits());
+    let mut outgoing: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+    for (i, &(start, _)) in edges.iter().enumerate() {
+        outgoing.entry(key(start)).or_default().push(i);
+    }
+    let turn = |from: usize, to: usize| {
+        let d = edges[from].1 - edges[from].0;
+        let e = edges[to].1 - edges[to].0;
+        d.perp_dot(&e).atan2(d.dot(&e))
+    };
+
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let mut ring = vec![edges[first].0];
+        let mut current = first;
+        loop {
+            let next = outgoing
+                .get(&key(edges[current].1))
+                .into_iter()
+                .flatten()
+                .copied()
+                .filter(|&k| k == first || !used[k])
+                .max_by(|&i, &j| turn(current, i).total_cmp(&turn(current, j)));
+            match next {
+                Some(k) if k == first => {
+                    rings.push(ring);
+                    break;
+                }
+                Some(k) => {
+                    used[k] = true;
+                    ring.push(edges[k].0);
+                    current = k;
+                }
+                // a dead end comes from rounding or from operands that break
+                // the documented form; the chain is dropped
+                None => break,
+            }
+        }
+    }
+    rings
+}
+
+// Removes vertices lying on the segment between their neighbours
+fn without_collinear(mut ring: Vec<Vector2D>) -> Vec<Vector2D> {
+    loop {
+        let n = ring.len();
+        if n < 3 {
+            return Vec::new();
+        }
+        let redundant = (0..n).find(|&i| {
+            distance_to_segment(ring[i], ring[(i + n - 1) % n], ring[(i + 1) % n]) <= TOLERANCE
+        });
+        match redundant {
+            Some(i) => {
+                ring.remove(i);
+            }
+            None => return ring,
+        }
+    }
+}
+
+// Whether `ring` lies in `outer`, judged by its first vertex or edge midpoint
+// that is not on the outer boundary
+fn lies_inside(ring: &[Vector2D], outer: &[Vector2D]) -> bool {
+    let midpoints = (0..ring.len()).map(|i| ring[i].lerp(&ring[(i + 1) % ring.len()], 0.5));
+    ring.iter()
+        .copied()
+        .chain(midpoints)
+        .map(|p| point_in_polygon(p, outer))
+        .find(|c| *c != Containment::OnBoundary)
+        == Some(Containment::Inside)
+}
+
+// Sorts rings into outer rings and holes and gives each hole to the
+// smallest outer ring around it
+fn assemble(rings: Vec<Vec<Vector2D>>) -> Vec<PolygonWithHoles> {
+    let mut polygons = Vec::new();
+    let mut holes = Vec::new();
+    for ring in rings.into_iter().map(without_collinear) {
+        let area = polygon_signed_area(&ring);
+        if area > 0.0 {
+            polygons.push(PolygonWithHoles {
+                outer: ring,
+                holes: Vec::new(),
+            });
+        } else if area < 0.0 {
+            holes.push(ring);
+        }
+    }
+
+    for hole in holes {
+        let container = polygons
+            .iter()
+            .enumerate()
+            .filter(|(_, polygon)| lies_inside(&hole, &polygon.outer))
+            .min_by(|(_, p), (_, q)| {
+                polygon_signed_area(&p.outer).total_cmp(&polygon_signed_area(&q.outer))
+            })
+            .map(|(i, _)| i);
+        if let Some(i) = container {
+            polygons[i].holes.push(hole);
+        }
+    }
+    polygons
+}
+
+#[cfg(test)]
+mod tests_clipping {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::geometry::algorithms::polygon_area;
+    use crate::testing::random_points;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    fn rectangle(x0: f32, y0: f32, x1: f32, y1: f32) -> Vec<Vector2D> {
+        vec![v(x0, y0), v(x1, y0), v(x1, y1), v(x0, y1)]
+    }
+
+    fn square(x: f32, y: f32, size: f32) -> PolygonWithHoles {
+        rectangle(x, y, x + size, y + size).into()
+    }
+
+    fn area(polygons: &[PolygonWithHoles]) -> f32 {
+        polygons.iter().map(PolygonWithHoles::area).sum()
+    }
+
+    // Same ring up to the starting vertex
+    fn assert_ring(ring: &[Vector2D], expected: &[Vector2D]) {
+        assert_eq!(ring.len(), expected.len(), "{ring:?}");
+        let offset = ring
+            .iter()
+            .position(|p| *p == expected[0])
+            .expect("missing vertex");
+        for (i, p) in expected.iter().enumerate() {
+            assert_approx_eq!(ring[(offset + i) % ring.len()], *p);
+        }
+    }
+
+    // Star-shaped, usually concave polygon around `center`
+    fn star(center: Vector2D, seed: u64) -> PolygonWithHoles {
+        let radii = random_points(12, seed);
+        let vertices: Vec<Vector2D> = radii
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let angle = i as f32 / radii.len() as f32 * std::f32::consts::TAU;
+                center + Vector2D::from_polar(5.0 + r.x / 4.0, angle)
+            })
+            .collect();
+        PolygonWithHoles::from(vertices)
+    }
+
+    #[test]
+    fn sutherland_hodgman_clips_to_convex_windows() {
+        let subject = rectangle(0.0, 0.0, 4.0, 4.0);
+        let window = vec![v(2.0, -1.0), v(6.0, 2.0), v(2.0, 5.0)];
+        let clipped = clip_convex(&subject, &window);
+        assert_approx_eq!(polygon_area(&clipped), 23.0 / 3.0, epsilon = 1e-4);
+
+        let mut clockwise = window.clone();
+        clockwise.reverse();
+        assert_approx_eq!(
+            polygon_area(&clip_convex(&subject, &clockwise)),
+            polygon_area(&clipped),
+            epsilon = 1e-4
+        );
+        assert_eq!(
+            clip_convex(&subject, &rectangle(-1.0, -1.0, 5.0, 5.0)),
+            subject
+        );
+        assert!(clip_convex(&subject, &rectangle(5.0, 5.0, 6.0, 6.0)).is_empty());
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = [square(0.0, 0.0, 2.0)];
+        let b = [square(1.0, 1.0, 2.0)];
+
+        let both = intersection(&a, &b);
+        assert_eq!(both.len(), 1);
+        assert_ring(&both[0].outer, &rectangle(1.0, 1.0, 2.0, 2.0));
+
+        let either = union(&a, &b);
+        assert_eq!(either.len(), 1);
+        assert_ring(
+            &either[0].outer,
+            &[
+                v(0.0, 0.0),
+                v(2.0, 0.0),
+                v(2.0, 1.0),
+                v(3.0, 1.0),
+                v(3.0, 3.0),
+                v(1.0, 3.0),
+                v(1.0, 2.0),
+                v(0.0, 2.0),
+            ],
+        );
+
+        let only_a = difference(&a, &b);
+        assert_ring(
+            &only_a[0].outer,
+            &[
+                v(0.0, 0.0),
+                v(2.0, 0.0),
+                v(2.0, 1.0),
+                v(1.0, 1.0),
+                v(1.0, 2.0),
+                v(0.0, 2.0),
+            ],
+        );
+
+        // the two L shapes touch at (1, 2) and (2, 1)
+        let exclusive = xor(&a, &b);
+        assert_eq!(exclusive.len(), 2);
+        assert_approx_eq!(area(&exclusive), 6.0);
+    }
+
+    #[test]
+    fn shared_edges_merge_or_vanish() {
+        let a = [square(0.0, 0.0, 1.0)];
+        let b = [square(1.0, 0.0, 1.0)];
+        let merged = union(&a, &b);
+        assert_eq!(merged.len(), 1);
+        assert_ring(&merged[0].outer, &rectangle(0.0, 0.0, 2.0, 1.0));
+        assert!(intersection(&a, &b).is_empty());
+        assert_ring(&difference(&a, &b)[0].outer, &a[0].outer);
+        assert_ring(&xor(&a, &b)[0].outer, &rectangle(0.0, 0.0, 2.0, 1.0));
+
+        // partially shared edge, b's corner lies on a's edge
+        let b = [square(1.0, 0.5, 1.0)];
+        let merged = union(&a, &b);
+        assert_ring(
+            &merged[0].outer,
+            &[
+                v(0.0, 0.0),
+                v(1.0, 0.0),
+                v(1.0, 0.5),
+                v(2.0, 0.5),
+                v(2.0, 1.5),
+                v(1.0, 1.5),
+                v(1.0, 1.0),
+                v(0.0, 1.0),
+            ],
+        );
+
+        // a square sharing the bottom edge, so the edge runs the same way
+        let inner = [square(0.0, 0.0, 0.5)];
+        assert_ring(&intersection(&a, &inner)[0].outer, &inner[0].outer);
+        assert_ring(&union(&a, &inner)[0].outer, &a[0].outer);
+        assert_ring(
+            &difference(&a, &inner)[0].outer,
+            &[
+                v(0.5, 0.0),
+                v(1.0, 0.0),
+                v(1.0, 1.0),
+                v(0.0, 1.0),
+                v(0.0, 0.5),
+                v(0.5, 0.5),
+            ],
+        );
+    }
+
+    #[test]
+    fn identical_and_disjoint_operands() {
+        let a = [square(0.0, 0.0, 1.0)];
+        assert_ring(&union(&a, &a)[0].outer, &a[0].outer);
+        assert_ring(&intersection(&a, &a)[0].outer, &a[0].outer);
+        assert!(difference(&a, &a).is_empty());
+        assert!(xor(&a, &a).is_empty());
+
+        let far = [square(5.0, 5.0, 1.0)];
+        assert_eq!(union(&a, &far).len(), 2);
+        assert!(intersection(&a, &far).is_empty());
+        assert_ring(&difference(&a, &far)[0].outer, &a[0].outer);
+        assert_eq!(union(&a, &[]).len(), 1);
+        assert!(intersection(&a, &[]).is_empty());
+
+        // squares touching at a corner stay separate
+        let corner = [square(1.0, 1.0, 1.0)];
+        assert_eq!(union(&a, &corner).len(), 2);
+        assert!(intersection(&a, &corner).is_empty());
+    }
+
+    #[test]
+    fn nearly_coincident_vertices_are_snapped() {
+        let a = [square(0.0, 0.0, 1.0)];
+        // the shared corners are off by less than the tolerance
+        let b = [PolygonWithHoles::from(vec![
+            v(1.0 + 1e-6, -1e-6),
+            v(2.0, 0.0),
+            v(2.0, 1.0),
+            v(1.0 - 1e-6, 1.0 + 1e-6),
+        ])];
+        let merged = union(&a, &b);
+        assert_eq!(merged.len(), 1);
+        assert_ring(&merged[0].outer, &rectangle(0.0, 0.0, 2.0, 1.0));
+        assert!(intersection(&a, &b).is_empty());
+    }
+
+    #[test]
+    fn holes() {
+        let outer = [square(0.0, 0.0, 4.0)];
+        let middle = [square(1.0, 1.0, 2.0)];
+
+        let frame = difference(&outer, &middle);
+        assert_eq!(frame.len(), 1);
+        assert_eq!(frame[0].holes.len(), 1);
+        assert_ring(&frame[0].outer, &outer[0].outer);
+        assert_ring(
+            &frame[0].holes[0],
+            &[v(1.0, 1.0), v(1.0, 3.0), v(3.0, 3.0), v(3.0, 1.0)],
+        );
+        assert_approx_eq!(area(&frame), 12.0);
+        assert_eq!(frame[0].contains(v(2.0, 2.0)), Containment::Outside);
+        assert_eq!(frame[0].contains(v(0.5, 2.0)), Containment::Inside);
+        assert_eq!(frame[0].contains(v(1.0, 2.0)), Containment::OnBoundary);
+
+        // filling the hole gives back the plain square
+        let filled = union(&frame, &middle);
+        assert_eq!(filled.len(), 1);
+        assert!(filled[0].holes.is_empty());
+        assert_ring(&filled[0].outer, &outer[0].outer);
+        assert_eq!(intersection(&frame, &middle), vec![]);
+
+        // a bar across the frame's hole
+        let bar = [PolygonWithHoles::from(rectangle(-1.0, 1.5, 5.0, 2.5))];
+        assert_approx_eq!(area(&intersection(&frame, &bar)), 2.0);
+        assert_approx_eq!(area(&union(&frame, &bar)), 12.0 + 6.0 - 2.0);
+        let cut = difference(&frame, &bar);
+        assert_eq!(cut.len(), 2);
+        assert!(cut.iter().all(|p| p.holes.is_empty()));
+        assert_approx_eq!(area(&cut), 10.0);
+
+        // a hole touching the outer ring at a vertex
+        let notch = [PolygonWithHoles::from(vec![
+            v(0.0, 0.0),
+            v(2.0, 1.0),
+            v(1.0, 2.0),
+        ])];
+        let pinched = difference(&outer, &notch);
+        assert_approx_eq!(area(&pinched), 16.0 - 1.5);
+    }
+
+    #[test]
+    fn random_concave_polygons_agree_with_point_membership() {
+        for seed in 1..6 {
+            let a = [star(v(10.0, 10.0), seed)];
+            let b = [star(v(13.0, 12.0), seed + 100)];
+            let results = [
+                (Operation::Union, boolean(&a, &b, Operation::Union)),
+                (
+                    Operation::Intersection,
+                    boolean(&a, &b, Operation::Intersection),
+                ),
+                (
+                    Operation::Difference,
+                    boolean(&a, &b, Operation::Difference),
+                ),
+                (Operation::Xor, boolean(&a, &b, Operation::Xor)),
+            ];
+
+            let (area_a, area_b) = (area(&a), area(&b));
+            let area_of = |operation: Operation| {
+                area(&results.iter().find(|(o, _)| *o == operation).unwrap().1)
+            };
+            let common = area_of(Operation::Intersection);
+            assert_approx_eq!(
+                area_of(Operation::Union),
+                area_a + area_b - common,
+                epsilon = 1e-2
+            );
+            assert_approx_eq!(
+                area_of(Operation::Difference),
+                area_a - common,
+                epsilon = 1e-2
+            );
+            assert_approx_eq!(
+                area_of(Operation::Xor),
+                area_a + area_b - 2.0 * common,
+                epsilon = 1e-2
+            );
+
+            for p in random_points(400, seed) {
+                let p = p / 4.0;
+                let (in_a, in_b) = (a[0].contains(p), b[0].contains(p));
+                if in_a == Containment::OnBoundary || in_b == Containment::OnBoundary {
+                    continue;
+                }
+                let (in_a, in_b) = (in_a == Containment::Inside, in_b == Containment::Inside);
+                for (operation, polygons) in &results {
+                    let expected = match operation {
+                        Operation::Union => in_a || in_b,
+                        Operation::Intersection => in_a && in_b,
+                        Operation::Difference => in_a && !in_b,
+                        Operation::Xor => in_a != in_b,
+                    };
+                    let inside = polygons
+                        .iter()
+                        .filter(|polygon| polygon.contains(p) == Containment::Inside)
+                        .count();
+                    assert_eq!(
+                        inside, expected as usize,
+                        "{operation:?} at {p:?}, seed {seed}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_operands_do_not_panic() {
+        // the first ring crosses itself, which leaves kept edges ending
+        // where no kept edge starts
+        let a = [PolygonWithHoles::from(vec![
+            v(2.0, 0.0),
+            v(1.0, 1.0),
+            v(2.0, 1.0),
+            v(0.0, 1.0),
+        ])];
+        let b = [PolygonWithHoles::from(vec![
+            v(1.0, 1.0),
+            v(2.0, 1.0),
+            v(3.0, 2.0),
+        ])];
+        for operation in [
+            Operation::Union,
+            Operation::Intersection,
+            Operation::Difference,
+            Operation::Xor,
+        ] {
+            boolean(&a, &b, operation);
+        }
+    }
+}
@@ -0,0 +1,776 @@
+use crate::geometry::shapes::{Aabb, Segment, Shape};
+use crate::vector2d::Vector2D;
+
+// Parametric curves. Both splines are stored as their control points and
+// evaluated one cubic Bezier segment at a time: segment i covers the
+// parameter range [i, i + 1], so integer parameters fall on the joints.
+//
+// Flattening subdivides a Bezier until its control points lie within the
+// tolerance of the chord. A Bezier never leaves the convex hull of its control
+// points, so the whole piece is then within the tolerance of the chord too.
+//
+// Arc length has no closed form for cubics; `ArcLength` integrates the speed
+// with Gauss-Legendre quadrature over short intervals and inverts the table
+// with a few Newton steps.
+
+const MAX_FLATTEN_DEPTH: usize = 16;
+// arc length table intervals per unit of parameter
+const ARC_LENGTH_INTERVALS: usize = 16;
+
+pub trait Curve {
+    /// Parameter range `(start, end)`.
+    fn domain(&self) -> (f32, f32);
+
+    fn point(&self, t: f32) -> Vector2D;
+
+    /// First derivative with respect to the parameter.
+    fn derivative(&self, t: f32) -> Vector2D;
+
+    fn second_derivative(&self, t: f32) -> Vector2D;
+
+    /// Tight bounding box of the curve (not of its control points).
+    fn aabb(&self) -> Aabb;
+
+    /// Polyline from the start to the end of the curve that stays within
+    /// `tolerance` of it.
+    fn flatten(&self, tolerance: f32) -> Vec<Vector2D>;
+
+    /// Unit tangent; `None` where the derivative vanishes.
+    fn tangent(&self, t: f32) -> Option<Vector2D> {
+        self.derivative(t).normalize()
+    }
+
+    /// Signed curvature, positive when turning counter-clockwise; `None`
+    /// where the derivative vanishes.
+    fn curvature(&self, t: f32) -> Option<f32> {
+        let (d, dd) = (self.derivative(t), self.second_derivative(t));
+        let speed = d.length();
+        if speed == 0.0 {
+            return None;
+        }
+        Some(d.perp_dot(&dd) / (speed * speed * speed))
+    }
+
+    fn arc_length(&self) -> ArcLength<'_, Self>
+    where
+        Self: Sized,
+    {
+        ArcLength::new(self)
+    }
+
+    fn length(&self) -> f32 {
+        ArcLength::new(self).length()
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct QuadraticBezier {
+    pub p0: Vector2D,
+    pub p1: Vector2D,
+    pub p2: Vector2D,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct CubicBezier {
+    pub p0: Vector2D,
+    pub p1: Vector2D,
+    pub p2: Vector2D,
+    pub p3: Vector2D,
+}
+
+/// Uniform Catmull-Rom spline, passing through every control point.
+///
+/// The end points are repeated to give the first and last segment a
+/// neighbour, so the domain is [0, n - 1] for n points.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CatmullRom {
+    points: Vec<Vector2D>,
+}
+
+/// Uniform cubic B-spline, twice continuously differentiable.
+///
+/// It does not pass through its control points; the domain is [0, n - 3]
+/// for n points.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BSpline {
+    points: Vec<Vector2D>,
+}
+
+/////////////////////////////////////////////////////////
+// Bezier curves
+
+impl QuadraticBezier {
+    pub fn new(p0: Vector2D, p1: Vector2D, p2: Vector2D) -> Self {
+        QuadraticBezier { p0, p1, p2 }
+    }
+
+    /// The same curve as a cubic (degree elevation).
+    pub fn to_cubic(&self) -> CubicBezier {
+        CubicBezier {
+            p0: self.p0,
+            p1: self.p0 + (self.p1 - self.p0) * (2.0 / 3.0),
+            p2: self.p2 + (self.p1 - self.p2) * (2.0 / 3.0),
+            p3: self.p2,
+        }
+    }
+
+    /// The parts before and after `t` (de Casteljau).
+    pub fn split(&self, t: f32) -> (QuadraticBezier, QuadraticBezier) {
+        let a = self.p0.lerp(&self.p1, t);
+        let b = self.p1.lerp(&self.p2, t);
+        let mid = a.lerp(&b, t);
+        (
+            QuadraticBezier::new(self.p0, a, mid),
+            QuadraticBezier::new(mid, b, self.p2),
+        )
+    }
+}
+
+impl Curve for QuadraticBezier {
+    fn domain(&self) -> (f32, f32) {
+        (0.0, 1.0)
+    }
+
+    fn point(&self, t: f32) -> Vector2D {
+        let s = 1.0 - t;
+        self.p0 * (s * s) + self.p1 * (2.0 * s * t) + self.p2 * (t * t)
+    }
+
+    fn derivative(&self, t: f32) -> Vector2D {
+        ((self.p1 - self.p0) * (1.0 - t) + (self.p2 - self.p1) * t) * 2.0
+    }
+
+    fn second_derivative(&self, _t: f32) -> Vector2D {
+        (self.p2 - self.p1 * 2.0 + self.p0) * 2.0
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.to_cubic().aabb()
+    }
+
+    fn flatten(&self, tolerance: f32) -> Vec<Vector2D> {
+        self.to_cubic().flatten(tolerance)
+    }
+}
+
+impl CubicBezier {
+    pub fn new(p0: Vector2D, p1: Vector2D, p2: Vector2D, p3: Vector2D) -> Self {
+        CubicBezier { p0, p1, p2, p3 }
+    }
+
+    /// The parts before and after `t` (de Casteljau).
+    pub fn split(&self, t: f32) -> (CubicBezier, CubicBezier) {
+        let a = self.p0.lerp(&self.p1, t);
+        let b = self.p1.lerp(&self.p2, t);
+        let c = self.p2.lerp(&self.p3, t);
+        let ab = a.lerp(&b, t);
+        let bc = b.lerp(&c, t);
+        let mid = ab.lerp(&bc, t);
+        (
+            CubicBezier::new(self.p0, a, ab, mid),
+            CubicBezier::new(mid, bc, c, self.p3),
+        )
+    }
+
+    // Largest distance of the inner control points from the chord
+    fn flatness(&self) -> f32 {
+        let chord = Segment::new(self.p0, self.p3);
+        chord
+            .distance_to_point(self.p1)
+            .max(chord.distance_to_point(self.p2))
+    }
+
+    fn flatten_into(&self, tolerance: f32, depth: usize, out: &mut Vec<Vector2D>) {
+        if depth >= MAX_FLATTEN_DEPTH || self.flatness() <= tolerance {
+            out.push(self.p3);
+        } else {
+            let (first, second) = self.split(0.5);
+            first.flatten_into(tolerance, depth + 1, out);
+            second.flatten_into(tolerance, depth + 1, out);
+        }
+    }
+}
+
+impl Curve for CubicBezier {
+    fn domain(&self) -> (f32, f32) {
+        (0.0, 1.0)
+    }
+
+    fn point(&self, t: f32) -> Vector2D {
+        let s = 1.0 - t;
+        self.p0 * (s * s * s)
+            + self.p1 * (3.0 * s * s * t)
+            + self.p2 * (3.0 * s * t * t)
+            + self.p3 * (t * t * t)
+    }
+
+    fn derivative(&self, t: f32) -> Vector2D {
+        let s = 1.0 - t;
+        ((self.p1 - self.p0) * (s * s)
+            + (self.p2 - self.p1) * (2.0 * s * t)
+            + (self.p3 - self.p2) * (t * t))
+            * 3.0
+    }
+
+    fn second_derivative(&self, t: f32) -> Vector2D {
+        ((self.p2 - self.p1 * 2.0 + self.p0) * (1.0 - t) + (self.p3 - self.p2 * 2.0 + self.p1) * t)
+            * 6.0
+    }
+
+    fn aabb(&self) -> Aabb {
+        // end points plus the interior extrema of each coordinate, where the
+        // derivative a (1-t)^2 + 2 b (1-t) t + c t^2 vanishes
+        let mut bounds = Aabb::new(self.p0, self.p3);
+        for axis in 0..2 {
+            let a = self.p1[axis] - self.p0[axis];
+            let b = self.p2[axis] - self.p1[axis];
+            let c = self.p3[axis] - self.p2[axis];
+            for t in quadratic_roots(a - 2.0 * b + c, 2.0 * (b - a), a) {
+                if t > 0.0 && t < 1.0 {
+                    let p = self.point(t);
+                    bounds.min[axis] = bounds.min[axis].min(p[axis]);
+                    bounds.max[axis] = bounds.max[axis].max(p[axis]);
+                }
+            }
+        }
+        bounds
+    }
+
+    fn flatten(&self, tolerance: f32) -> Vec<Vector2D> {
+        let mut points = vec![self.p0];
+        self.flatten_into(tolerance, 0, &mut points);
+        points
+    }
+}
+
+// Real roots of a t^2 + b t + c, falling back to the linear equation when a
+// is negligible
+fn quadratic_roots(a: f32, b: f32, c: f32) -> Vec<f32> {
+    if a.abs() <= 1e-12 * (b.abs() + c.abs()) {
+        return if b == 0.0 { vec![] } else { vec![-c / b] };
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+    // avoids cancellation between -b and the square root
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    if q == 0.0 {
+        return vec![0.0];
+    }
+    vec![q / a, c / q]
+}
+
+/////////////////////////////////////////////////////////
+// Splines
+
+// Segment index and local parameter for a spline parameter
+fn locate(t: f32, segments: usize) -> (usize, f32) {
+    let i = (t.floor().max(0.0) as usize).min(segments - 1);
+    (i, t - i as f32)
+}
+
+fn flatten_segments(segments: Vec<CubicBezier>, tolerance: f32) -> Vec<Vector2D> {
+    let mut points = vec![segments[0].p0];
+    for segment in &segments {
+        segment.flatten_into(tolerance, 0, &mut points);
+    }
+    points
+}
+
+fn union_of_boxes(segments: Vec<CubicBezier>) -> Aabb {
+    segments
+        .iter()
+        .map(Curve::aabb)
+        .reduce(|a, b| a.union(&b))
+        .unwrap()
+}
+
+impl CatmullRom {
+    /// Panics with fewer than 2 points.
+    pub fn new(points: Vec<Vector2D>) -> Self {
+        assert!(
+            points.len() >= 2,
+            "Catmull-Rom spline needs at least 2 points"
+        );
+        CatmullRom { points }
+    }
+
+    pub fn points(&self) -> &[Vector2D] {
+        &self.points
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.points.len() - 1
+    }
+
+    /// Segment `i` as a cubic Bezier from point `i` to point `i + 1`.
+    pub fn segment(&self, i: usize) -> CubicBezier {
+        let last = self.points.len() - 1;
+        let p = |k: isize| self.points[k.clamp(0, last as isize) as usize];
+        let i = i as isize;
+        let (p0, p1, p2, p3) = (p(i - 1), p(i), p(i + 1), p(i + 2));
+        CubicBezier::new(p1, p1 + (p2 - p0) / 6.0, p2 - (p3 - p1) / 6.0, p2)
+    }
+
+    pub fn segments(&self) -> Vec<CubicBezier> {
+        (0..self.segment_count()).map(|i| self.segment(i)).collect()
+    }
+}
+
+impl Curve for CatmullRom {
+    fn domain(&self) -> (f32, f32) {
+        (0.0, self.segment_count() as f32)
+    }
+
+    fn point(&self, t: f32) -> Vector2D {
+        let (i, u) = locate(t, self.segment_count());
+        self.segment(i).point(u)
+    }
+
+    fn derivative(&self, t: f32) -> Vector2D {
+        let (i, u) = locate(t, self.segment_count());
+        self.segment(i).derivative(u)
+    }
+
+    fn second_derivative(&self, t: f32) -> Vector2D {
+        let (i, u) = locate(t, self.segment_count());
+        self.segment(i).second_derivative(u)
+    }
+
+    fn aabb(&self) -> Aabb {
+        union_of_boxes(self.segments())
+    }
+
+    fn flatten(&self, tolerance: f32) -> Vec<Vector2D> {
+        flatten_segments(self.segments(), tolerance)
+    }
+}
+
+impl BSpline {
+    /// Panics with fewer than 4 points.
+    pub fn new(points: Vec<Vector2D>) -> Self {
+        assert!(points.len() >= 4, "B-spline needs at least 4 points");
+        BSpline { points }
+    }
+
+    pub fn points(&self) -> &[Vector2D] {
+        &self.points
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.points.len() - 3
+    }
+
+    /// Segment `i`, shaped by points `i..i + 4`, as a cubic Bezier.
+    pub fn segment(&self, i: usize) -> CubicBezier {
+        let [p0, p1, p2, p3] = [0, 1, 2, 3].map(|k| self.points[i + k]);
+        CubicBezier::new(
+            (p0 + p1 * 4.0 + p2) / 6.0,
+            (p1 * 2.0 + p2) / 3.0,
+            (p1 + p2 * 2.0) / 3.0,
+            (p1 + p2 * 4.0 + p3) / 6.0,
+        )
+    }
+
+    pub fn segments(&self) -> Vec<CubicBezier> {
+        (0..self.segment_count()).map(|i| self.segment(i)).collect()
+    }
+
+    /// The same curve with twice as many segments (Lane-Riesenfeld
+    /// subdivision): parameter `t` here is `2 t` on the result, and the
+    /// control polygon moves closer to the curve.
+    pub fn refine(&self) -> BSpline {
+        let p = &self.points;
+        let mut points = vec![(p[0] + p[1]) / 2.0];
+        for i in 1..p.len() - 1 {
+            points.push((p[i - 1] + p[i] * 6.0 + p[i + 1]) / 8.0);
+            points.push((p[i] + p[i + 1]) / 2.0);
+        }
+        BSpline { points }
+    }
+}
+
+impl Curve for BSpline {
+    fn domain(&self) -> (f32, f32) {
+        (0.0, self.segment_count() as f32)
+    }
+
+    fn point(&self, t: f32) -> Vector2D {
+        let (i, u) = locate(t, self.segment_count());
+        self.segment(i).point(u)
+    }
+
+    fn derivative(&self, t: f32) -> Vector2D {
+        let (i, u) = locate(t, self.segment_count());
+        self.segment(i).derivative(u)
+    }
+
+    fn second_derivative(&self, t: f32) -> Vector2D {
+        let (i, u) = locate(t, self.segment_count());
+        self.segment(i).second_derivative(u)
+    }
+
+    fn aabb(&self) -> Aabb {
+        union_of_boxes(self.segments())
+    }
+
+    fn flatten(&self, tolerance: f32) -> Vec<Vector2D> {
+        flatten_segments(self.segments(), tolerance)
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Arc length
+
+// 5-point Gauss-Legendre rule on [-1, 1]
+const GAUSS_NODES: [f32; 5] = [-0.906_179_8, -0.538_469_3, 0.0, 0.538_469_3, 0.906_179_8];
+const GAUSS_WEIGHTS: [f32; 5] = [
+    0.236_926_88,
+    0.478_628_66,
+    0.568_888_9,
+    0.478_628_66,
+    0.236_926_88,
+];
+
+/// Arc length parameterization of a curve.
+pub struct ArcLength<'a, C: ?Sized> {
+    curve: &'a C,
+    // cumulative length at evenly spaced parameters
+    parameters: Vec<f32>,
+    lengths: Vec<f32>,
+}
+
+impl<'a, C: Curve + ?Sized> ArcLength<'a, C> {
+    pub fn new(curve: &'a C) -> Self {
+        let (start, end) = curve.domain();
+        let intervals = (((end - start) * ARC_LENGTH_INTERVALS as f32).ceil() as usize).max(1);
+        let parameters: Vec<f32> = (0..=intervals)
+            .map(|i| start + (end - start) * i as f32 / intervals as f32)
+            .collect();
+        let mut lengths = vec![0.0];
+        for pair in parameters.windows(2) {
+            let total = lengths.last().unwrap() + integrate_speed(curve, pair[0], pair[1]);
+            lengths.push(total);
+        }
+        ArcLength {
+            curve,
+            parameters,
+            lengths,
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().unwrap()
+    }
+
+    /// Parameter at which the distance travelled along the curve equals
+    /// `distance`, clamped to the curve.
+    pub fn parameter_at(&self, distance: f32) -> f32 {
+        let distance = distance.clamp(0.0, self.length());
+        let k = self
+            .lengths
+            .partition_point(|&l| l <= distance)
+            .clamp(1, self.lengths.len() - 1)
+            - 1;
+        let (t0, t1) = (self.parameters[k], self.parameters[k + 1]);
+        let (l0, l1) = (self.lengths[k], self.lengths[k + 1]);
+        if l1 <= l0 {
+            return t0;
+        }
+
+        // Newton steps on length(t0, t) = distance - l0 from a linear guess
+        let mut t = t0 + (t1 - t0) * (distance - l0) / (l1 - l0);
+        for _ in 0..4 {
+            let speed = self.curve.derivative(t).length();
+            if speed == 0.0 {
+                break;
+            }
+            let error = integrate_speed(self.curve, t0, t) - (distance - l0);
+            t = (t - error / speed).clamp(t0, t1);
+        }
+        t
+    }
+
+    pub fn point_at(&self, distance: f32) -> Vector2D {
+        self.curve.point(self.parameter_at(distance))
+    }
+
+    /// `count` points spaced evenly along the curve, including both ends.
+    pub fn evenly_spaced(&self, count: usize) -> Vec<Vector2D> {
+        match count {
+            0 => vec![],
+            1 => vec![self.point_at(0.0)],
+            _ => (0..count)
+                .map(|i| self.point_at(self.length() * i as f32 / (count - 1) as f32))
+                .collect(),
+        }
+    }
+}
+
+fn integrate_speed<C: Curve + ?Sized>(curve: &C, from: f32, to: f32) -> f32 {
+    let (half, mid) = ((to - from) / 2.0, (to + from) / 2.0);
+    GAUSS_NODES
+        .iter()
+        .zip(GAUSS_WEIGHTS)
+        .map(|(x, w)| w * curve.derivative(mid + half * x).length())
+        .sum::<f32>()
+        * half
+}
+
+#[cfg(test)]
+mod tests_curves {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    fn arch() -> CubicBezier {
+        CubicBezier::new(
+            Vector2D::new(0.0, 0.0),
+            Vector2D::new(0.0, 1.0),
+            Vector2D::new(1.0, 1.0),
+            Vector2D::new(1.0, 0.0),
+        )
+    }
+
+    fn zigzag() -> Vec<Vector2D> {
+        vec![
+            Vector2D::new(0.0, 0.0),
+            Vector2D::new(1.0, 2.0),
+            Vector2D::new(2.0, -1.0),
+            Vector2D::new(4.0, 1.0),
+            Vector2D::new(5.0, 3.0),
+            Vector2D::new(3.0, 4.0),
+        ]
+    }
+
+    fn distance_to_polyline(points: &[Vector2D], p: Vector2D) -> f32 {
+        points
+            .windows(2)
+            .map(|w| Segment::new(w[0], w[1]).distance_to_point(p))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    // central differences against the analytic derivatives
+    fn check_derivatives(curve: &dyn Curve) {
+        let (start, end) = curve.domain();
+        let h = 1e-2;
+        for i in 1..20 {
+            let t = start + (end - start) * (i as f32 + 0.37) / 21.0;
+            let d = (curve.point(t + h) - curve.point(t - h)) / (2.0 * h);
+            assert_approx_eq!(curve.derivative(t), d, epsilon = 1e-2);
+            // splines may have a jump in the second derivative at their joints
+            if (t - t.round()).abs() > 2.0 * h {
+                let dd = (curve.derivative(t + h) - curve.derivative(t - h)) / (2.0 * h);
+                assert_approx_eq!(curve.second_derivative(t), dd, epsilon = 5e-2);
+            }
+        }
+    }
+
+    fn check_flattening(curve: &dyn Curve, tolerance: f32) {
+        let polyline = curve.flatten(tolerance);
+        let (start, end) = curve.domain();
+        assert_eq!(polyline[0], curve.point(start));
+        assert_approx_eq!(*polyline.last().unwrap(), curve.point(end));
+        for i in 0..=500 {
+            let p = curve.point(start + (end - start) * i as f32 / 500.0);
+            assert!(distance_to_polyline(&polyline, p) <= tolerance * 1.001);
+        }
+    }
+
+    fn check_aabb(curve: &dyn Curve) {
+        let aabb = curve.aabb();
+        let (start, end) = curve.domain();
+        let samples: Vec<Vector2D> = (0..=1000)
+            .map(|i| curve.point(start + (end - start) * i as f32 / 1000.0))
+            .collect();
+        let sampled = Aabb::from_points(samples).unwrap();
+        assert_approx_eq!(aabb.min, sampled.min, epsilon = 1e-3);
+        assert_approx_eq!(aabb.max, sampled.max, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn cubic_evaluation_and_split() {
+        let curve = arch();
+        assert_eq!(curve.point(0.0), curve.p0);
+        assert_eq!(curve.point(1.0), curve.p3);
+        assert_eq!(curve.point(0.5), Vector2D::new(0.5, 0.75));
+        assert_eq!(curve.derivative(0.0), Vector2D::new(0.0, 3.0));
+        assert_eq!(curve.tangent(0.5), Some(Vector2D::UNIT_X));
+        check_derivatives(&curve);
+
+        let (left, right) = curve.split(0.3);
+        for i in 0..=10 {
+            let u = i as f32 / 10.0;
+            assert_approx_eq!(left.point(u), curve.point(0.3 * u), epsilon = 1e-4);
+            assert_approx_eq!(right.point(u), curve.point(0.3 + 0.7 * u), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn quadratic_matches_its_cubic() {
+        let curve = QuadraticBezier::new(
+            Vector2D::new(0.0, 0.0),
+            Vector2D::new(2.0, 4.0),
+            Vector2D::new(4.0, 0.0),
+        );
+        let cubic = curve.to_cubic();
+        for i in 0..=10 {
+            let t = i as f32 / 10.0;
+            assert_approx_eq!(curve.point(t), cubic.point(t), epsilon = 1e-4);
+            assert_approx_eq!(curve.derivative(t), cubic.derivative(t), epsilon = 1e-4);
+            assert_approx_eq!(
+                curve.second_derivative(t),
+                cubic.second_derivative(t),
+                epsilon = 1e-4
+            );
+        }
+        check_derivatives(&curve);
+        assert_approx_eq!(curve.aabb().max, Vector2D::new(4.0, 2.0));
+
+        let (left, right) = curve.split(0.5);
+        assert_eq!(left.p2, Vector2D::new(2.0, 2.0));
+        assert_eq!(right.point(1.0), curve.p2);
+    }
+
+    #[test]
+    fn bounding_boxes_include_extrema() {
+        assert_approx_eq!(arch().aabb().max, Vector2D::new(1.0, 0.75));
+        check_aabb(&arch());
+        check_aabb(&CubicBezier::new(
+            Vector2D::new(0.0, 0.0),
+            Vector2D::new(3.0, 2.0),
+            Vector2D::new(-2.0, 2.0),
+            Vector2D::new(1.0, 0.0),
+        ));
+        check_aabb(&CatmullRom::new(zigzag()));
+        check_aabb(&BSpline::new(zigzag()));
+        // straight lines have no interior extrema
+        let line = CubicBezier::new(
+            Vector2D::ZERO,
+            Vector2D::new(1.0, 1.0),
+            Vector2D::new(2.0, 2.0),
+            Vector2D::new(3.0, 3.0),
+        );
+        assert_eq!(
+            line.aabb(),
+            Aabb::new(Vector2D::ZERO, Vector2D::new(3.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn flattening_respects_the_tolerance() {
+        for tolerance in [0.1, 0.01, 0.001] {
+            check_flattening(&arch(), tolerance);
+            check_flattening(&CatmullRom::new(zigzag()), tolerance);
+            check_flattening(&BSpline::new(zigzag()), tolerance);
+        }
+        assert!(arch().flatten(0.001).len() > arch().flatten(0.1).len());
+
+        let line = QuadraticBezier::new(
+            Vector2D::ZERO,
+            Vector2D::new(1.0, 1.0),
+            Vector2D::new(2.0, 2.0),
+        );
+        assert_eq!(
+            line.flatten(1e-6),
+            vec![Vector2D::ZERO, Vector2D::new(2.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn catmull_rom_interpolates_its_points() {
+        let spline = CatmullRom::new(zigzag());
+        assert_eq!(spline.domain(), (0.0, 5.0));
+        for (i, p) in zigzag().iter().enumerate() {
+            assert_approx_eq!(spline.point(i as f32), *p, epsilon = 1e-4);
+        }
+        // tangents at interior points follow the neighbours
+        assert_approx_eq!(
+            spline.derivative(2.0),
+            (zigzag()[3] - zigzag()[1]) / 2.0,
+            epsilon = 1e-4
+        );
+        assert_approx_eq!(
+            spline.derivative(2.0 - 1e-6),
+            spline.derivative(2.0),
+            epsilon = 1e-3
+        );
+        check_derivatives(&spline);
+
+        let two = CatmullRom::new(vec![Vector2D::ZERO, Vector2D::new(3.0, 0.0)]);
+        assert_approx_eq!(two.point(0.5), Vector2D::new(1.5, 0.0), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn b_spline_is_smooth_and_refines_exactly() {
+        let spline = BSpline::new(zigzag());
+        assert_eq!(spline.domain(), (0.0, 3.0));
+        for joint in [1.0, 2.0] {
+            let (before, after) = (
+                spline.segment(joint as usize - 1),
+                spline.segment(joint as usize),
+            );
+            assert_approx_eq!(before.point(1.0), after.point(0.0), epsilon = 1e-4);
+            assert_approx_eq!(
+                before.derivative(1.0),
+                after.derivative(0.0),
+                epsilon = 1e-4
+            );
+            assert_approx_eq!(
+                before.second_derivative(1.0),
+                after.second_derivative(0.0),
+                epsilon = 1e-4
+            );
+        }
+        check_derivatives(&spline);
+
+        let refined = spline.refine();
+        assert_eq!(refined.segment_count(), 6);
+        for i in 0..=30 {
+            let t = i as f32 / 10.0;
+            assert_approx_eq!(refined.point(2.0 * t), spline.point(t), epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn arc_length_of_lines_and_arcs() {
+        // unevenly spaced control points on a line: the speed varies
+        let line = CubicBezier::new(
+            Vector2D::ZERO,
+            Vector2D::new(0.1, 0.0),
+            Vector2D::new(0.2, 0.0),
+            Vector2D::new(10.0, 0.0),
+        );
+        let table = line.arc_length();
+        assert_approx_eq!(table.length(), 10.0, epsilon = 1e-4);
+        for d in [0.0, 1.0, 2.5, 7.0, 10.0] {
+            assert_approx_eq!(table.point_at(d).x, d, epsilon = 1e-3);
+        }
+        assert_eq!(table.point_at(20.0), line.p3);
+
+        // quarter circle, the usual cubic approximation
+        let k = 0.552_284_8;
+        let arc = CubicBezier::new(
+            Vector2D::new(1.0, 0.0),
+            Vector2D::new(1.0, k),
+            Vector2D::new(k, 1.0),
+            Vector2D::new(0.0, 1.0),
+        );
+        assert_approx_eq!(arc.length(), std::f32::consts::FRAC_PI_2, epsilon = 1e-3);
+        assert_approx_eq!(arc.curvature(0.5).unwrap(), 1.0, epsilon = 1e-2);
+
+        // against the length of a fine polyline up to each parameter
+        let spline = CatmullRom::new(zigzag());
+        let table = spline.arc_length();
+        assert_eq!(table.evenly_spaced(50).len(), 50);
+        for i in 0..=10 {
+            let distance = table.length() * i as f32 / 10.0;
+            let t = table.parameter_at(distance);
+            let samples: Vec<Vector2D> = (0..=10_000)
+                .map(|k| spline.point(t * k as f32 / 10_000.0))
+                .collect();
+            let travelled: f32 = samples.windows(2).map(|w| w[0].distance(&w[1])).sum();
+            assert_approx_eq!(travelled, distance, epsilon = 1e-3 * table.length());
+        }
+    }
+}
@@ -0,0 +1,599 @@
+use crate::geometry::clipping::clip_to_half_plane;
+use crate::geometry::predicates::{in_circle, orient2d};
+use crate::geometry::shapes::Aabb;
+use crate::vector2d::Vector2D;
+use std::collections::{HashMap, HashSet};
+
+// Bowyer-Watson Delaunay triangulation.
+//
+// The initial super triangle has its three vertices at infinity in the
+// directions 90, 210 and 330 degrees. Circumcircle tests against triangles
+// touching them are resolved by their limits (half-planes), so the super
+// triangle never has to be "large enough" and hull triangles are not lost
+// on nearly collinear input.
+
+const GHOSTS: usize = 3;
+
+/// Delaunay triangulation of a point set.
+#[derive(Clone, Debug)]
+pub struct Delaunay {
+    points: Vec<Vector2D>,
+    triangles: Vec<[usize; 3]>,
+}
+
+/// Voronoi cell of one input point, clipped to the bounding box.
+#[derive(Clone, PartialEq, Debug)]
+pub struct VoronoiCell {
+    pub site: Vector2D,
+    /// Counter-clockwise polygon, empty when the clipped cell vanishes.
+    pub vertices: Vec<Vector2D>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Voronoi {
+    pub bounds: Aabb,
+    /// One cell per input point; duplicate points share the same cell.
+    pub cells: Vec<VoronoiCell>,
+}
+
+// Vertex ids 0..3 are the ghost vertices, input point i has id i + 3
+struct Builder<'a> {
+    points: &'a [Vector2D],
+    triangles: Vec<Option<[usize; 3]>>,
+    // directed edge (u, v) -> triangle having it in counter-clockwise order
+    edges: HashMap<(usize, usize), usize>,
+    // most recently created triangle, where point location starts
+    newest: usize,
+}
+
+impl Builder<'_> {
+    fn add_triangle(&mut self, t: [usize; 3]) -> usize {
+        let id = self.triangles.len();
+        for k in 0..3 {
+            self.edges.insert((t[k], t[(k + 1) % 3]), id);
+        }
+        self.triangles.push(Some(t));
+        self.newest = id;
+        id
+    }
+
+    fn remove_triangle(&mut self, id: usize) -> [usize; 3] {
+        let t = self.triangles[id].take().unwrap();
+        for k in 0..3 {
+            self.edges.remove(&(t[k], t[(k + 1) % 3]));
+        }
+        t
+    }
+
+    fn ghost_direction(g: usize) -> Vector2D {
+        const SIN_60: f32 = 0.866_025_4;
+        [
+            Vector2D::new(0.0, 1.0),
+            Vector2D::new(-SIN_60, -0.5),
+            Vector2D::new(SIN_60, -0.5),
+        ][g]
+    }
+
+    /// Whether `p` lies strictly inside the (limit) circumcircle of triangle `t`.
+    fn in_circumcircle(&self, t: [usize; 3], p: Vector2D) -> bool {
+        let ghosts = t.iter().filter(|&&v| v < GHOSTS).count();
+        // rotate so real vertices come first, keeping the winding
+        let start = (0..3)
+            .find(|&k| t[k] >= GHOSTS && (ghosts != 1 || t[(k + 1) % 3] >= GHOSTS))
+            .unwrap_or(0);
+        let [a, b, c] = [t[start], t[(start + 1) % 3], t[(start + 2) % 3]];
+        let point = |v: usize| self.points[v - GHOSTS];
+
+        match ghosts {
+            0 => in_circle(point(a), point(b), point(c), p) > 0.0,
+            1 => {
+                // the circle degenerates to the half-plane left of a -> b
+                let (a, b) = (point(a), point(b));
+                let side = orient2d(a, b, p);
+                side > 0.0 || (side == 0.0 && strictly_between(p, a, b))
+            }
+            2 => {
+                // a half-plane through `a` facing away from the third ghost
+                let third = (0..GHOSTS).find(|&g| g != b && g != c).unwrap();
+                let d = Self::ghost_direction(third);
+                let offset = p - point(a);
+                (offset.x as f64) * (d.x as f64) + (offset.y as f64) * (d.y as f64) < 0.0
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether `p` is strictly right of the directed edge u -> v, ghost
+    /// vertices being points at infinity in their directions.
+    fn right_of(&self, u: usize, v: usize, p: Vector2D) -> bool {
+        let point = |v: usize| self.points[v - GHOSTS];
+        let cross = |d: Vector2D, from: Vector2D| {
+            let (x, y) = (p.x as f64 - from.x as f64, p.y as f64 - from.y as f64);
+            (d.x as f64) * y - (d.y as f64) * x
+        };
+        match (u < GHOSTS, v < GHOSTS) {
+            (false, false) => orient2d(point(u), point(v), p) < 0.0,
+            (false, true) => cross(Self::ghost_direction(v), point(u)) < 0.0,
+            (true, false) => cross(-Self::ghost_direction(u), point(v)) < 0.0,
+            (true, true) => false,
+        }
+    }
+
+    /// Visibility walk from the newest triangle to the one containing `p`:
+    /// step across any edge that has `p` on its outer side. Points are
+    /// inserted along a Hilbert curve, so the walk is usually a few steps long.
+    /// `None` if it does not settle within one step per triangle.
+    fn locate(&self, p: Vector2D) -> Option<usize> {
+        let mut t = self.newest;
+        let mut previous = usize::MAX;
+        for step in 0..self.triangles.len() {
+            let vertices = self.triangles[t]?;
+            // rotating the first edge tried keeps the walk from cycling
+            let next = (0..3).map(|k| (k + step) % 3).find_map(|k| {
+                let (u, v) = (vertices[k], vertices[(k + 1) % 3]);
+                let n = *self.edges.get(&(v, u))?;
+                (n != previous && self.right_of(u, v, p)).then_some(n)
+            });
+            match next {
+                Some(n) => (previous, t) = (t, n),
+                None => return Some(t),
+            }
+        }
+        None
+    }
+
+    fn insert(&mut self, id: usize) {
+        let p = self.points[id - GHOSTS];
+
+        // a triangle containing `p` has it inside its circumcircle; should
+        // rounding mislead the walk, scan the live triangles instead
+        let first = self
+            .locate(p)
+            .filter(|&t| self.in_circumcircle(self.triangles[t].unwrap(), p))
+            .or_else(|| {
+                (0..self.triangles.len())
+                    .rev()
+                    .find(|&t| self.triangles[t].is_some_and(|t| self.in_circumcircle(t, p)))
+            })
+            .expect("some triangle contains every point");
+
+        // the cavity of bad triangles is connected, grow it from the first one
+        let mut bad = vec![first];
+        let mut seen: HashSet<usize> = HashSet::from([first]);
+        let mut boundary = Vec::new();
+        while let Some(t) = bad.pop() {
+            let vertices = self.triangles[t].unwrap();
+            for k in 0..3 {
+                let (u, v) = (vertices[k], vertices[(k + 1) % 3]);
+                match self.edges.get(&(v, u)) {
+                    Some(&n) if seen.contains(&n) => {}
+                    Some(&n) if self.in_circumcircle(self.triangles[n].unwrap(), p) => {
+                        seen.insert(n);
+                        bad.push(n);
+                    }
+                    _ => boundary.push((u, v)),
+                }
+            }
+        }
+
+        for t in seen {
+            self.remove_triangle(t);
+        }
+        for (u, v) in boundary {
+            self.add_triangle([u, v, id]);
+        }
+    }
+}
+
+// Position of each point along a Hilbert curve over a 2^16 x 2^16 grid
+// spanning the bounding box of the finite points
+fn hilbert_keys(points: &[Vector2D]) -> Vec<u64> {
+    const SIDE: u32 = 1 << 16;
+    let Some(bounds) = Aabb::from_points(points.iter().copied().filter(|p| is_finite(*p))) else {
+        return Vec::new();
+    };
+    let size = bounds.size();
+    let scale = (SIDE - 1) as f64 / (size.x.max(size.y) as f64).max(f64::MIN_POSITIVE);
+    points
+        .iter()
+        .map(|p| {
+            // non-finite points are not inserted, but still need a cell
+            let cell = |c: f32, min: f32| (((c as f64 - min as f64) * scale) as u32).min(SIDE - 1);
+            let (mut x, mut y) = (cell(p.x, bounds.min.x), cell(p.y, bounds.min.y));
+            let mut key = 0u64;
+            let mut s = SIDE / 2;
+            while s > 0 {
+                let (rx, ry) = ((x & s > 0) as u32, (y & s > 0) as u32);
+                key += s as u64 * s as u64 * ((3 * rx) ^ ry) as u64;
+                // rotate the quadrant so the curve stays continuous
+                if ry == 0 {
+                    if rx == 1 {
+                        x = SIDE - 1 - x;
+                        y = SIDE - 1 - y;
+                    }
+                    (x, y) = (y, x);
+                }
+                s /= 2;
+            }
+            key
+        })
+        .collect()
+}
+
+fn is_finite(p: Vector2D) -> bool {
+    p.x.is_finite() && p.y.is_finite()
+}
+
+// For collinear points comparing one coordinate is exact
+fn strictly_between(p: Vector2D, a: Vector2D, b: Vector2D) -> bool {
+    let (p, a, b) = if a.x != b.x {
+        (p.x, a.x, b.x)
+    } else {
+        (p.y, a.y, b.y)
+    };
+    p > a.min(b) && p < a.max(b)
+}
+
+impl Delaunay {
+    /// Triangulates the points; duplicates and non-finite points are ignored
+    /// and fully collinear input produces no triangles.
+    pub fn new(points: &[Vector2D]) -> Self {
+        let mut builder = Builder {
+            points,
+            triangles: Vec::new(),
+            edges: HashMap::new(),
+            newest: 0,
+        };
+        builder.add_triangle([0, 1, 2]);
+
+        // insert along a Hilbert curve so consecutive points are neighbours
+        // and the walk in `locate` stays short; equal points end up adjacent
+        let keys = hilbert_keys(points);
+        let mut order: Vec<usize> = (0..points.len())
+            .filter(|&i| is_finite(points[i]))
+            .collect();
+        order.sort_by(|&a, &b| {
+            keys[a]
+                .cmp(&keys[b])
+                .then(points[a].x.total_cmp(&points[b].x))
+                .then(points[a].y.total_cmp(&points[b].y))
+        });
+        order.dedup_by(|a, b| points[*a] == points[*b]);
+
+        for i in order {
+            builder.insert(i + GHOSTS);
+        }
+
+        let triangles = builder
+            .triangles
+            .into_iter()
+            .flatten()
+            .filter(|t| t.iter().all(|&v| v >= GHOSTS))
+            .map(|t| t.map(|v| v - GHOSTS))
+            .collect();
+        Delaunay {
+            points: points.to_vec(),
+            triangles,
+        }
+    }
+
+    pub fn points(&self) -> &[Vector2D] {
+        &self.points
+    }
+
+    /// Counter-clockwise index triples into `points()`.
+    pub fn triangles(&self) -> &[[usize; 3]] {
+        &self.triangles
+    }
+
+    /// Undirected edges `(i, j)` with `i < j`, sorted.
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = self
+            .triangles
+            .iter()
+            .flat_map(|t| (0..3).map(move |k| (t[k].min(t[(k + 1) % 3]), t[k].max(t[(k + 1) % 3]))))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Circumcenter of each triangle, i.e. the vertices of the Voronoi diagram.
+    pub fn circumcenters(&self) -> Vec<Vector2D> {
+        self.triangles
+            .iter()
+            .map(|t| circumcenter(self.points[t[0]], self.points[t[1]], self.points[t[2]]))
+            .collect()
+    }
+
+    /// Voronoi diagram dual to the triangulation, clipped to `bounds`.
+    pub fn voronoi(&self, bounds: Aabb) -> Voronoi {
+        let n = self.points.len();
+        let mut first_of: HashMap<(u32, u32), usize> = HashMap::new();
+        let canonical: Vec<usize> = (0..n)
+            .map(|i| {
+                let p = self.points[i];
+                *first_of.entry((p.x.to_bits(), p.y.to_bits())).or_insert(i)
+            })
+            .collect();
+
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); n];
+        if self.triangles.is_empty() {
+            // collinear or tiny input: every other distinct point may be a neighbour
+            for i in 0..n {
+                neighbours[i] = (0..n)
+                    .filter(|&j| canonical[j] == j && canonical[i] != j)
+                    .filter(|&j| is_finite(self.points[j]))
+                    .collect();
+            }
+        } else {
+            for (i, j) in self.edges() {
+                neighbours[i].push(j);
+                neighbours[j].push(i);
+            }
+        }
+
+        let cells = (0..n)
+            .map(|i| {
+                let site = self.points[i];
+                if !is_finite(site) {
+                    return VoronoiCell {
+                        site,
+                        vertices: Vec::new(),
+                    };
+                }
+                let mut vertices = bounds.corners().to_vec();
+                for &j in &neighbours[canonical[i]] {
+                    vertices = clip_to_bisector(&vertices, site, self.points[j]);
+                }
+                VoronoiCell { site, vertices }
+            })
+            .collect();
+
+        Voronoi { bounds, cells }
+    }
+}
+
+fn circumcenter(a: Vector2D, b: Vector2D, c: Vector2D) -> Vector2D {
+    let (bx, by) = (b.x as f64 - a.x as f64, b.y as f64 - a.y as f64);
+    let (cx, cy) = (c.x as f64 - a.x as f64, c.y as f64 - a.y as f64);
+    let d = 2.0 * (bx * cy - by * cx);
+    let b_len = bx * bx + by * by;
+    let c_len = cx * cx + cy * cy;
+    let x = (cy * b_len - by * c_len) / d;
+    let y = (bx * c_len - cx * b_len) / d;
+    a + Vector2D::new(x as f32, y as f32)
+}
+
+// Keeps the part of the convex polygon closer to `site` than to `other`
+fn clip_to_bisector(polygon: &[Vector2D], site: Vector2D, other: Vector2D) -> Vec<Vector2D> {
+    clip_to_half_plane(polygon, site.lerp(&other, 0.5), other - site)
+}
+
+#[cfg(test)]
+mod tests_delaunay {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::geometry::algorithms::{Containment, convex_hull, point_in_polygon, polygon_area};
+    use crate::testing::random_points;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    fn triangle_area(d: &Delaunay, t: &[usize; 3]) -> f32 {
+        polygon_area(&[d.points()[t[0]], d.points()[t[1]], d.points()[t[2]]])
+    }
+
+    fn assert_delaunay(d: &Delaunay) {
+        let points = d.points();
+        for t in d.triangles() {
+            let (a, b, c) = (points[t[0]], points[t[1]], points[t[2]]);
+            assert!(
+                orient2d(a, b, c) > 0.0,
+                "triangle {:?} is not counter-clockwise",
+                t
+            );
+            for (i, &p) in points.iter().enumerate() {
+                if !t.contains(&i) {
+                    assert!(
+                        in_circle(a, b, c, p) <= 0.0,
+                        "point {} inside circumcircle of {:?}",
+                        i,
+                        t
+                    );
+                }
+            }
+        }
+
+        let hull_area = polygon_area(&convex_hull(points));
+        let area: f32 = d.triangles().iter().map(|t| triangle_area(d, t)).sum();
+        assert!(
+            (area - hull_area).abs() <= hull_area * 1e-4,
+            "{} vs {}",
+            area,
+            hull_area
+        );
+    }
+
+    #[test]
+    fn square() {
+        let d = Delaunay::new(&[v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0)]);
+        assert_eq!(d.triangles().len(), 2);
+        assert_eq!(d.edges().len(), 5);
+        assert_delaunay(&d);
+    }
+
+    #[test]
+    fn single_triangle() {
+        let d = Delaunay::new(&[v(0.0, 0.0), v(0.0, 1.0), v(1.0, 0.0)]);
+        assert_eq!(d.triangles().len(), 1);
+        assert_delaunay(&d);
+        assert_approx_eq!(d.circumcenters()[0], v(0.5, 0.5));
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        assert!(Delaunay::new(&[]).triangles().is_empty());
+        assert!(Delaunay::new(&[v(1.0, 1.0)]).triangles().is_empty());
+        assert!(
+            Delaunay::new(&[v(0.0, 0.0), v(1.0, 1.0), v(2.0, 2.0), v(3.0, 3.0)])
+                .triangles()
+                .is_empty()
+        );
+
+        let with_duplicates = [
+            v(0.0, 0.0),
+            v(1.0, 0.0),
+            v(0.0, 0.0),
+            v(0.0, 1.0),
+            v(1.0, 0.0),
+        ];
+        let d = Delaunay::new(&with_duplicates);
+        assert_eq!(d.triangles().len(), 1);
+        assert_delaunay(&d);
+    }
+
+    #[test]
+    fn non_finite_points_are_ignored() {
+        let mut points = random_points(50, 4);
+        let expected = Delaunay::new(&points);
+        points.extend([
+            v(f32::NAN, 1.0),
+            v(f32::INFINITY, 0.0),
+            v(3.0, f32::NEG_INFINITY),
+        ]);
+        let d = Delaunay::new(&points);
+        assert_eq!(d.triangles(), expected.triangles());
+
+        let bounds = Aabb::new(v(0.0, 0.0), v(100.0, 100.0));
+        let voronoi = d.voronoi(bounds);
+        assert!(voronoi.cells[50..].iter().all(|c| c.vertices.is_empty()));
+        assert_eq!(voronoi.cells[..50], expected.voronoi(bounds).cells[..]);
+
+        // without triangles, only the finite sites split the bounds
+        let voronoi = Delaunay::new(&[v(1.0, 0.0), v(f32::NAN, 0.0), v(3.0, 0.0)])
+            .voronoi(Aabb::new(v(0.0, -1.0), v(4.0, 1.0)));
+        assert_approx_eq!(polygon_area(&voronoi.cells[0].vertices), 4.0);
+        assert_approx_eq!(polygon_area(&voronoi.cells[2].vertices), 4.0);
+    }
+
+    #[test]
+    fn collinear_hull_points_are_kept() {
+        // points along the bottom edge must all be triangulated
+        let mut points: Vec<Vector2D> = (0..10).map(|i| v(i as f32, 0.0)).collect();
+        points.push(v(4.5, 3.0));
+        let d = Delaunay::new(&points);
+        assert_eq!(d.triangles().len(), 9);
+        assert_delaunay(&d);
+    }
+
+    #[test]
+    fn cocircular_grid() {
+        let points: Vec<Vector2D> = (0..6)
+            .flat_map(|i| (0..6).map(move |j| v(i as f32, j as f32)))
+            .collect();
+        let d = Delaunay::new(&points);
+        assert_eq!(d.triangles().len(), 2 * 5 * 5);
+        assert_delaunay(&d);
+    }
+
+    #[test]
+    fn random_point_sets() {
+        for seed in 1..6 {
+            let points = random_points(200, seed);
+            let d = Delaunay::new(&points);
+            let hull = convex_hull(&points).len();
+            assert_eq!(d.triangles().len(), 2 * points.len() - 2 - hull);
+            assert_delaunay(&d);
+        }
+    }
+
+    #[test]
+    fn walk_locates_points_without_scanning() {
+        // unsorted input and points outside the hull so far exercise long
+        // walks and walks into the ghost triangles
+        let mut points = random_points(300, 11);
+        points.extend([
+            v(-50.0, 50.0),
+            v(150.0, 50.0),
+            v(50.0, -50.0),
+            v(50.0, 150.0),
+        ]);
+        let mut builder = Builder {
+            points: &points,
+            triangles: Vec::new(),
+            edges: HashMap::new(),
+            newest: 0,
+        };
+        builder.add_triangle([0, 1, 2]);
+        for (i, p) in points.iter().enumerate() {
+            let t = builder.locate(*p).expect("walk settles");
+            assert!(
+                builder.in_circumcircle(builder.triangles[t].unwrap(), *p),
+                "point {i}"
+            );
+            builder.insert(i + GHOSTS);
+        }
+    }
+
+    #[test]
+    fn nearly_collinear_points() {
+        // a thin sliver: hull triangles along it are easily lost with a finite super triangle
+        let mut points: Vec<Vector2D> = (0..20).map(|i| v(i as f32 * 1000.0, 0.0)).collect();
+        points.push(v(9500.0, 0.001));
+        let d = Delaunay::new(&points);
+        assert_eq!(d.triangles().len(), 19);
+        assert_delaunay(&d);
+    }
+
+    #[test]
+    fn voronoi_of_two_points() {
+        let bounds = Aabb::new(v(0.0, 0.0), v(4.0, 2.0));
+        let d = Delaunay::new(&[v(1.0, 1.0), v(3.0, 1.0)]);
+        let voronoi = d.voronoi(bounds);
+        assert_eq!(voronoi.cells.len(), 2);
+        assert_approx_eq!(polygon_area(&voronoi.cells[0].vertices), 4.0);
+        assert_approx_eq!(polygon_area(&voronoi.cells[1].vertices), 4.0);
+        assert!(voronoi.cells[0].vertices.iter().all(|p| p.x <= 2.0));
+        assert!(voronoi.cells[1].vertices.iter().all(|p| p.x >= 2.0));
+    }
+
+    #[test]
+    fn voronoi_cells_partition_the_bounds() {
+        let points = random_points(60, 7);
+        let bounds = Aabb::new(v(-10.0, -10.0), v(110.0, 110.0));
+        let voronoi = Delaunay::new(&points).voronoi(bounds);
+
+        let total: f32 = voronoi
+            .cells
+            .iter()
+            .map(|c| polygon_area(&c.vertices))
+            .sum();
+        assert!((total - bounds.area()).abs() < bounds.area() * 1e-4);
+
+        // every sample lies in the cell of its nearest site
+        for sample in random_points(200, 99) {
+            let nearest = (0..points.len())
+                .min_by(|&a, &b| {
+                    points[a]
+                        .distance_squared(&sample)
+                        .total_cmp(&points[b].distance_squared(&sample))
+                })
+                .unwrap();
+            let cell = &voronoi.cells[nearest].vertices;
+            assert_ne!(point_in_polygon(sample, cell), Containment::Outside);
+        }
+    }
+
+    #[test]
+    fn voronoi_with_collinear_and_duplicate_sites() {
+        let bounds = Aabb::new(v(0.0, -1.0), v(3.0, 1.0));
+        let points = [v(0.5, 0.0), v(1.5, 0.0), v(2.5, 0.0), v(1.5, 0.0)];
+        let voronoi = Delaunay::new(&points).voronoi(bounds);
+        assert_approx_eq!(polygon_area(&voronoi.cells[0].vertices), 2.0);
+        assert_approx_eq!(polygon_area(&voronoi.cells[1].vertices), 2.0);
+        assert_approx_eq!(polygon_area(&voronoi.cells[2].vertices), 2.0);
+        assert_eq!(voronoi.cells[3].vertices, voronoi.cells[1].vertices);
+    }
+}
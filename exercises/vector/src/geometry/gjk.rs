@@ -0,0 +1,642 @@
+use crate::geometry::shapes::{Aabb, Circle, ClosestPoints, Polygon, Segment, TOLERANCE};
+use crate::vector2d::Vector2D;
+
+// Queries on convex shapes that are described only by a support function.
+//
+// Two convex shapes A and B overlap exactly when their Minkowski difference
+// A - B contains the origin. GJK walks a simplex (point, segment or triangle)
+// of A - B towards the origin: either a triangle ends up enclosing it, or the
+// closest point of A - B is found, and its barycentric weights on the simplex
+// give the closest points on A and B.
+//
+// EPA starts from GJK's enclosing triangle and grows a polygon inside A - B
+// until the edge nearest the origin lies on the boundary. That distance is
+// the penetration depth, and the edge normal the direction to separate in.
+//
+// Curved shapes are only approached, never reached exactly, so both loops
+// stop once an iteration gains less than a small relative amount.
+
+const MAX_ITERATIONS: usize = 64;
+const CONVERGENCE: f32 = 1e-5;
+
+/// Convex shape given by its support mapping. Concave polygons behave like
+/// their convex hull.
+pub trait Support {
+    /// Point of the shape furthest in `direction`, which need not be unit
+    /// length; any one of several equally far points will do.
+    fn support(&self, direction: Vector2D) -> Vector2D;
+
+    /// Any point of the shape, used to start the search.
+    fn center(&self) -> Vector2D;
+}
+
+/// Segment swept by a circle: all points within `radius` of it.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Capsule {
+    pub start: Vector2D,
+    pub end: Vector2D,
+    pub radius: f32,
+}
+
+/// All sums `a + b` of a point of each shape, e.g. a polygon with rounded
+/// corners is `MinkowskiSum(polygon, circle)` with the circle at the origin.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MinkowskiSum<A, B>(pub A, pub B);
+
+/// Overlap of two shapes.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Penetration {
+    /// Unit direction from the first shape towards the second; moving the
+    /// second shape by `normal * depth` makes them touch.
+    pub normal: Vector2D,
+    pub depth: f32,
+    /// Point of the first shape deepest inside the second, and vice versa.
+    pub on_a: Vector2D,
+    pub on_b: Vector2D,
+}
+
+impl Capsule {
+    pub fn new(start: Vector2D, end: Vector2D, radius: f32) -> Self {
+        Capsule { start, end, radius }
+    }
+
+    pub fn segment(&self) -> Segment {
+        Segment::new(self.start, self.end)
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Support mappings
+
+impl Support for Vector2D {
+    fn support(&self, _direction: Vector2D) -> Vector2D {
+        *self
+    }
+
+    fn center(&self) -> Vector2D {
+        *self
+    }
+}
+
+impl Support for Segment {
+    fn support(&self, direction: Vector2D) -> Vector2D {
+        if self.direction().dot(&direction) > 0.0 {
+            self.end
+        } else {
+            self.start
+        }
+    }
+
+    fn center(&self) -> Vector2D {
+        self.midpoint()
+    }
+}
+
+impl Support for Circle {
+    fn support(&self, direction: Vector2D) -> Vector2D {
+        self.center + direction.normalize().unwrap_or(Vector2D::ZERO) * self.radius
+    }
+
+    fn center(&self) -> Vector2D {
+        self.center
+    }
+}
+
+impl Support for Aabb {
+    fn support(&self, direction: Vector2D) -> Vector2D {
+        Vector2D::new(
+            if direction.x >= 0.0 {
+                self.max.x
+            } else {
+                self.min.x
+            },
+            if direction.y >= 0.0 {
+                self.max.y
+            } else {
+                self.min.y
+            },
+        )
+    }
+
+    fn center(&self) -> Vector2D {
+        Aabb::center(self)
+    }
+}
+
+impl Support for Polygon {
+    fn support(&self, direction: Vector2D) -> Vector2D {
+        *self
+            .vertices()
+            .iter()
+            .max_by(|a, b| a.dot(&direction).total_cmp(&b.dot(&direction)))
+            .unwrap()
+    }
+
+    fn center(&self) -> Vector2D {
+        self.vertices()[0]
+    }
+}
+
+impl Support for Capsule {
+    fn support(&self, direction: Vector2D) -> Vector2D {
+        Circle::new(self.segment().support(direction), self.radius).support(direction)
+    }
+
+    fn center(&self) -> Vector2D {
+        self.segment().midpoint()
+    }
+}
+
+impl<A: Support, B: Support> Support for MinkowskiSum<A, B> {
+    fn support(&self, direction: Vector2D) -> Vector2D {
+        self.0.support(direction) + self.1.support(direction)
+    }
+
+    fn center(&self) -> Vector2D {
+        self.0.center() + self.1.center()
+    }
+}
+
+impl<T: Support + ?Sized> Support for &T {
+    fn support(&self, direction: Vector2D) -> Vector2D {
+        (**self).support(direction)
+    }
+
+    fn center(&self) -> Vector2D {
+        (**self).center()
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Queries
+
+/// Closest points of two disjoint shapes; `None` when they overlap or touch.
+pub fn distance<A, B>(a: &A, b: &B) -> Option<ClosestPoints>
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    match gjk(a, b) {
+        Gjk::Separated(closest) => Some(closest),
+        Gjk::Overlapping(_) => None,
+    }
+}
+
+/// True when the shapes overlap or touch.
+pub fn intersects<A, B>(a: &A, b: &B) -> bool
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    matches!(gjk(a, b), Gjk::Overlapping(_))
+}
+
+/// Smallest translation separating two overlapping shapes; `None` when they
+/// are disjoint. Touching shapes have depth 0.
+pub fn penetration<A, B>(a: &A, b: &B) -> Option<Penetration>
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    match gjk(a, b) {
+        Gjk::Separated(_) => None,
+        Gjk::Overlapping(simplex) => Some(epa(a, b, simplex)),
+    }
+}
+
+/////////////////////////////////////////////////////////
+// GJK
+
+// Point of the Minkowski difference with the points of A and B it came from
+#[derive(Copy, Clone, Debug)]
+struct Vertex {
+    a: Vector2D,
+    b: Vector2D,
+    p: Vector2D,
+}
+
+fn support_vertex<A, B>(a: &A, b: &B, direction: Vector2D) -> Vertex
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    let (on_a, on_b) = (a.support(direction), b.support(-direction));
+    Vertex {
+        a: on_a,
+        b: on_b,
+        p: on_a - on_b,
+    }
+}
+
+enum Gjk {
+    Separated(ClosestPoints),
+    Overlapping(Vec<Vertex>),
+}
+
+fn gjk<A, B>(a: &A, b: &B) -> Gjk
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    let start = b.center() - a.center();
+    let direction = if start == Vector2D::ZERO {
+        Vector2D::UNIT_X
+    } else {
+        start
+    };
+    let mut simplex = vec![support_vertex(a, b, direction)];
+
+    let mut weights;
+    let mut closest;
+    let mut iterations = 0;
+    loop {
+        (closest, weights) = reduce(&mut simplex);
+        if simplex.len() == 3 || closest.length() <= TOLERANCE {
+            return Gjk::Overlapping(simplex);
+        }
+        iterations += 1;
+        if iterations > MAX_ITERATIONS {
+            break;
+        }
+
+        let w = support_vertex(a, b, -closest);
+        // no support point gets noticeably closer to the origin
+        let progress = closest.length_squared() - closest.dot(&w.p);
+        if progress <= CONVERGENCE * closest.length_squared() || simplex.iter().any(|v| v.p == w.p)
+        {
+            break;
+        }
+        simplex.push(w);
+    }
+
+    let combine = |point: fn(&Vertex) -> Vector2D| {
+        simplex
+            .iter()
+            .zip(&weights)
+            .map(|(v, w)| point(v) * *w)
+            .sum::<Vector2D>()
+    };
+    Gjk::Separated(ClosestPoints {
+        on_self: combine(|v| v.a),
+        on_other: combine(|v| v.b),
+        distance: closest.length(),
+    })
+}
+
+// Reduces the simplex to the smallest subset whose hull holds its point
+// closest to the origin, and returns that point with the subset's weights.
+// A triangle is kept only when it encloses the origin.
+fn reduce(simplex: &mut Vec<Vertex>) -> (Vector2D, Vec<f32>) {
+    match simplex.len() {
+        1 => (simplex[0].p, vec![1.0]),
+        2 => reduce_segment(simplex),
+        _ => {
+            let [p0, p1, p2] = [simplex[0].p, simplex[1].p, simplex[2].p];
+            let sides = [
+                (p1 - p0).perp_dot(&-p0),
+                (p2 - p1).perp_dot(&-p1),
+                (p0 - p2).perp_dot(&-p2),
+            ];
+            let area = (p1 - p0).perp_dot(&(p2 - p0));
+            if area != 0.0 && (sides.iter().all(|&s| s >= 0.0) || sides.iter().all(|&s| s <= 0.0)) {
+                return (Vector2D::ZERO, vec![]);
+            }
+
+            [(0, 1), (1, 2), (2, 0)]
+                .into_iter()
+                .map(|(i, j)| {
+                    let mut edge = vec![simplex[i], simplex[j]];
+                    let (closest, weights) = reduce_segment(&mut edge);
+                    (edge, closest, weights)
+                })
+                .min_by(|x, y| x.1.length_squared().total_cmp(&y.1.length_squared()))
+                .map(|(edge, closest, weights)| {
+                    *simplex = edge;
+                    (closest, weights)
+                })
+                .unwrap()
+        }
+    }
+}
+
+fn reduce_segment(simplex: &mut Vec<Vertex>) -> (Vector2D, Vec<f32>) {
+    let (p, q) = (simplex[0].p, simplex[1].p);
+    let edge = q - p;
+    let length_squared = edge.length_squared();
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (-p.dot(&edge) / length_squared).clamp(0.0, 1.0)
+    };
+    if t == 0.0 {
+        simplex.truncate(1);
+        (p, vec![1.0])
+    } else if t == 1.0 {
+        simplex.remove(0);
+        (q, vec![1.0])
+    } else {
+        (p + edge * t, vec![1.0 - t, t])
+    }
+}
+
+/////////////////////////////////////////////////////////
+// EPA
+
+fn epa<A, B>(a: &A, b: &B, mut polytope: Vec<Vertex>) -> Penetration
+where
+    A: Support + ?Sized,
+    B: Support + ?Sized,
+{
+    // GJK stops early for touching shapes; widen the simplex to a triangle
+    let axes = [
+        Vector2D::UNIT_X,
+        Vector2D::UNIT_Y,
+        -Vector2D::UNIT_X,
+        -Vector2D::UNIT_Y,
+    ];
+    for direction in axes {
+        if polytope.len() > 1 {
+            break;
+        }
+        let w = support_vertex(a, b, direction);
+        if w.p != polytope[0].p {
+            polytope.push(w);
+        }
+    }
+    if polytope.len() == 2 {
+        let normal = (polytope[1].p - polytope[0].p).perpendicular();
+        for direction in [normal, -normal] {
+            let w = support_vertex(a, b, direction);
+            if (w.p - polytope[0].p).dot(&direction) > TOLERANCE * direction.length() {
+                polytope.push(w);
+                break;
+            }
+        }
+    }
+    if polytope.len() < 3 {
+        // A - B has no area: both shapes are points or parallel segments
+        let normal = polytope
+            .get(1)
+            .and_then(|v| (v.p - polytope[0].p).perpendicular().normalize())
+            .unwrap_or(Vector2D::UNIT_X);
+        let on_a = polytope[0].a;
+        return Penetration {
+            normal,
+            depth: 0.0,
+            on_a,
+            on_b: on_a,
+        };
+    }
+    if (polytope[1].p - polytope[0].p).perp_dot(&(polytope[2].p - polytope[0].p)) < 0.0 {
+        polytope.swap(1, 2);
+    }
+
+    let mut iterations = 0;
+    loop {
+        // edge nearest the origin, with its outward normal
+        let (i, normal, distance) = (0..polytope.len())
+            .filter_map(|i| {
+                let (p, q) = (polytope[i].p, polytope[(i + 1) % polytope.len()].p);
+                let normal = Vector2D::new(q.y - p.y, p.x - q.x).normalize()?;
+                Some((i, normal, normal.dot(&p)))
+            })
+            .min_by(|x, y| x.2.total_cmp(&y.2))
+            .unwrap();
+
+        let w = support_vertex(a, b, normal);
+        let gain = normal.dot(&w.p) - distance;
+        iterations += 1;
+        if gain <= CONVERGENCE * distance.max(1.0)
+            || iterations >= MAX_ITERATIONS
+            || polytope.iter().any(|v| v.p == w.p)
+        {
+            let (v, u) = (polytope[i], polytope[(i + 1) % polytope.len()]);
+            let edge = u.p - v.p;
+            let t = (-v.p.dot(&edge) / edge.length_squared()).clamp(0.0, 1.0);
+            return Penetration {
+                normal,
+                depth: distance.max(0.0),
+                on_a: v.a.lerp(&u.a, t),
+                on_b: v.b.lerp(&u.b, t),
+            };
+        }
+        polytope.insert(i + 1, w);
+    }
+}
+
+#[cfg(test)]
+mod tests_gjk {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::geometry::algorithms::convex_hull;
+    use crate::geometry::shapes::Shape;
+    use crate::testing::random_points;
+
+    fn square(center: Vector2D, half: f32) -> Polygon {
+        Aabb::new(
+            center - Vector2D::new(half, half),
+            center + Vector2D::new(half, half),
+        )
+        .to_polygon()
+    }
+
+    // Convex polygons with a few vertices each, spread over [0, 20)^2
+    fn random_hulls(count: usize, seed: u64) -> Vec<Polygon> {
+        let points = random_points(count * 6, seed);
+        points
+            .chunks(6)
+            .map(|chunk| {
+                let offset = chunk[0] * 0.2;
+                let local: Vec<Vector2D> = chunk.iter().map(|p| *p * 0.04 + offset).collect();
+                Polygon::new(convex_hull(&local))
+            })
+            .filter(|p| p.vertices().len() >= 3)
+            .collect()
+    }
+
+    // Separating-axis reference: for convex polygons the minimum overlap
+    // over all edge normals is the penetration depth.
+    fn sat_depth(a: &Polygon, b: &Polygon) -> f32 {
+        let normals = a
+            .edges()
+            .chain(b.edges())
+            .filter_map(|e| e.direction().perpendicular().normalize());
+        normals
+            .map(|n| {
+                let project = |p: &Polygon| {
+                    p.vertices()
+                        .iter()
+                        .map(|v| v.dot(&n))
+                        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), x| {
+                            (lo.min(x), hi.max(x))
+                        })
+                };
+                let ((a_min, a_max), (b_min, b_max)) = (project(a), project(b));
+                (a_max - b_min).min(b_max - a_min)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    #[test]
+    fn distance_between_circles() {
+        let a = Circle::new(Vector2D::ZERO, 1.0);
+        let b = Circle::new(Vector2D::new(3.0, 4.0), 2.0);
+        let closest = distance(&a, &b).unwrap();
+        assert_approx_eq!(closest.distance, 2.0, epsilon = 1e-3);
+        assert_approx_eq!(closest.on_self, Vector2D::new(0.6, 0.8), epsilon = 1e-3);
+        assert_approx_eq!(closest.on_other, Vector2D::new(1.8, 2.4), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn distance_between_polygons_matches_boundaries() {
+        let hulls = random_hulls(40, 3);
+        let mut checked = 0;
+        for (i, a) in hulls.iter().enumerate() {
+            for b in &hulls[i + 1..] {
+                let reference = a.closest_points(b);
+                match distance(a, b) {
+                    Some(closest) => {
+                        assert_approx_eq!(closest.distance, reference.distance, epsilon = 1e-3);
+                        assert_approx_eq!(
+                            closest.on_self.distance(&closest.on_other),
+                            closest.distance,
+                            epsilon = 1e-3
+                        );
+                        checked += 1;
+                    }
+                    None => assert!(reference.distance <= 1e-3, "{:?} {:?}", a, b),
+                }
+            }
+        }
+        assert!(checked > 500);
+    }
+
+    #[test]
+    fn capsules_aabbs_and_points() {
+        let capsule = Capsule::new(Vector2D::new(0.0, 0.0), Vector2D::new(4.0, 0.0), 1.0);
+        let point = Vector2D::new(2.0, 3.0);
+        assert_approx_eq!(distance(&capsule, &point).unwrap().distance, 2.0);
+        assert_approx_eq!(
+            distance(&capsule, &Vector2D::new(7.0, 4.0))
+                .unwrap()
+                .distance,
+            4.0,
+            epsilon = 1e-4
+        );
+        assert!(intersects(&capsule, &Vector2D::new(-0.5, 0.5)));
+
+        let aabb = Aabb::new(Vector2D::new(1.0, 2.0), Vector2D::new(3.0, 5.0));
+        assert_approx_eq!(distance(&capsule, &aabb).unwrap().distance, 1.0);
+        // parallel edges: any pair of facing points is closest
+        let post = Segment::new(Vector2D::new(5.0, 0.0), Vector2D::new(5.0, 9.0));
+        let closest = distance(&aabb, &post).unwrap();
+        assert_approx_eq!(closest.distance, 2.0);
+        assert_approx_eq!(closest.on_self.x, 3.0);
+        assert_approx_eq!(closest.on_other.y, closest.on_self.y);
+    }
+
+    #[test]
+    fn minkowski_sums_round_off_shapes() {
+        let rounded = MinkowskiSum(
+            square(Vector2D::ZERO, 1.0),
+            Circle::new(Vector2D::ZERO, 0.5),
+        );
+        // beyond a face and beyond a corner
+        assert_approx_eq!(
+            distance(&rounded, &Vector2D::new(0.0, 3.0))
+                .unwrap()
+                .distance,
+            1.5,
+            epsilon = 1e-3
+        );
+        assert_approx_eq!(
+            distance(&rounded, &Vector2D::new(4.0, 5.0))
+                .unwrap()
+                .distance,
+            4.5,
+            epsilon = 1e-3
+        );
+        assert!(intersects(&rounded, &Vector2D::new(1.3, 1.3)));
+        assert!(!intersects(&rounded, &Vector2D::new(1.4, 1.4)));
+
+        // a capsule is a segment plus a circle
+        let capsule = Capsule::new(Vector2D::ZERO, Vector2D::new(0.0, 2.0), 0.5);
+        let sum = MinkowskiSum(capsule.segment(), Circle::new(Vector2D::ZERO, 0.5));
+        let probe = Vector2D::new(3.0, 4.0);
+        assert_approx_eq!(
+            distance(&capsule, &probe).unwrap().distance,
+            distance(&sum, &probe).unwrap().distance,
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn penetration_of_boxes_and_circles() {
+        let a = square(Vector2D::ZERO, 1.0);
+        let b = square(Vector2D::new(1.7, 0.5), 1.0);
+        let overlap = penetration(&a, &b).unwrap();
+        assert_approx_eq!(overlap.normal, Vector2D::UNIT_X);
+        assert_approx_eq!(overlap.depth, 0.3);
+        assert_approx_eq!(overlap.on_a.x, 1.0);
+        assert_approx_eq!(overlap.on_b.x, 0.7);
+        assert!(distance(&a, &b).is_none());
+
+        let c = Circle::new(Vector2D::ZERO, 1.0);
+        let d = Circle::new(Vector2D::new(0.0, -1.5), 1.0);
+        let overlap = penetration(&c, &d).unwrap();
+        assert_approx_eq!(overlap.normal, -Vector2D::UNIT_Y, epsilon = 1e-2);
+        assert_approx_eq!(overlap.depth, 0.5, epsilon = 1e-3);
+
+        // concentric: any direction will do, so the polygon approximating the
+        // circles is refined evenly and converges more slowly
+        let overlap = penetration(&c, &Circle::new(Vector2D::ZERO, 0.5)).unwrap();
+        assert_approx_eq!(overlap.depth, 1.5, epsilon = 5e-3);
+
+        assert!(penetration(&c, &Circle::new(Vector2D::new(2.5, 0.0), 1.0)).is_none());
+    }
+
+    #[test]
+    fn penetration_of_polygons_matches_sat() {
+        let hulls = random_hulls(40, 11);
+        let mut checked = 0;
+        for (i, a) in hulls.iter().enumerate() {
+            for b in &hulls[i + 1..] {
+                let Some(overlap) = penetration(a, b) else {
+                    continue;
+                };
+                assert_approx_eq!(overlap.depth, sat_depth(a, b), epsilon = 1e-3);
+                // moving b by the penetration leaves the two just touching
+                let moved = Polygon::new(
+                    b.vertices()
+                        .iter()
+                        .map(|v| *v + overlap.normal * (overlap.depth + 1e-3))
+                        .collect(),
+                );
+                assert!(distance(a, &moved).is_some());
+                checked += 1;
+            }
+        }
+        assert!(checked > 20, "only {} overlapping pairs", checked);
+    }
+
+    #[test]
+    fn touching_and_degenerate_shapes() {
+        let a = square(Vector2D::ZERO, 1.0);
+        let b = square(Vector2D::new(2.0, 0.0), 1.0);
+        assert!(intersects(&a, &b));
+        assert_approx_eq!(penetration(&a, &b).unwrap().depth, 0.0);
+
+        let p = Vector2D::new(1.0, 1.0);
+        assert!(intersects(&p, &p));
+        assert_eq!(penetration(&p, &p).unwrap().depth, 0.0);
+
+        let horizontal = Segment::new(Vector2D::new(-1.0, 0.0), Vector2D::new(1.0, 0.0));
+        let vertical = Segment::new(Vector2D::new(0.0, -1.0), Vector2D::new(0.0, 1.0));
+        assert!(intersects(&horizontal, &vertical));
+        assert!(intersects(&horizontal, &horizontal));
+        assert!(!intersects(
+            &horizontal,
+            &Segment::new(Vector2D::new(2.0, 0.0), Vector2D::new(3.0, 0.0))
+        ));
+    }
+}
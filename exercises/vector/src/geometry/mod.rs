@@ -0,0 +1,8 @@
+pub mod algorithms;
+pub mod clipping;
+pub mod curves;
+pub mod delaunay;
+pub mod gjk;
+pub mod polyline;
+pub mod predicates;
+pub mod shapes;
@@ -0,0 +1,472 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::geometry::algorithms::distance_to_segment;
+use crate::vector2d::Vector2D;
+
+// Operations on open polylines given as point slices. Each returns the new
+// polyline with its deviation from the input, measured as the largest
+// distance from a vertex of either polyline to the other one.
+//
+// Comparing every vertex with every segment would cost O(n·m), so each
+// operation records which input segments every output vertex came from, and
+// vertices are only measured against the corresponding part of the other
+// polyline. That is the distance that matters for an approximation, and it
+// keeps the measurement linear; `max_deviation` gives the exact value.
+//
+// Simplification keeps a subset of the vertices, including both ends:
+// Ramer-Douglas-Peucker keeps the vertex furthest from the current chord
+// until all others are within the tolerance, Visvalingam-Whyatt repeatedly
+// drops the vertex whose triangle with its neighbours has the least area.
+// Chaikin smoothing and resampling create new vertices.
+
+/// A polyline derived from another one.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Approximation {
+    pub points: Vec<Vector2D>,
+    /// Largest distance from a vertex of the input to the corresponding part
+    /// of the output polyline or the other way round. At least the distance
+    /// to the nearest point, and equal to it unless the polyline doubles back
+    /// close to itself.
+    pub max_deviation: f32,
+}
+
+impl Approximation {
+    /// `spans[j]` is the range of input segments, by index of their first
+    /// vertex, that output vertex `j` derives from. Ranges must be ordered
+    /// along the polyline and cover every input segment between them.
+    fn new(input: &[Vector2D], points: Vec<Vector2D>, spans: &[(usize, usize)]) -> Self {
+        let max_deviation = local_deviation(input, &points, spans);
+        Approximation {
+            points,
+            max_deviation,
+        }
+    }
+
+    /// The output keeps a subset of the input vertices, given by index.
+    fn from_kept(input: &[Vector2D], kept: impl Iterator<Item = usize>) -> Self {
+        let (points, spans): (Vec<_>, Vec<_>) = kept
+            .map(|i| (input[i], vertex_span(i, input.len())))
+            .unzip();
+        Approximation::new(input, points, &spans)
+    }
+}
+
+// The segments that meet at input vertex `i`
+fn vertex_span(i: usize, len: usize) -> (usize, usize) {
+    let last = len.saturating_sub(2);
+    (i.saturating_sub(1).min(last), i.min(last))
+}
+
+fn local_deviation(input: &[Vector2D], output: &[Vector2D], spans: &[(usize, usize)]) -> f32 {
+    if input.len() < 2 || output.len() < 2 {
+        // one side is a single point, so the exact measure is linear too
+        return max_deviation(input, output);
+    }
+
+    let mut deviation: f32 = 0.0;
+    for (p, &(first, last)) in output.iter().zip(spans) {
+        let nearest = (first..=last)
+            .map(|k| distance_to_segment(*p, input[k], input[k + 1]))
+            .fold(f32::INFINITY, f32::min);
+        deviation = deviation.max(nearest);
+    }
+
+    let mut nearest = vec![f32::INFINITY; input.len()];
+    for (j, w) in output.windows(2).enumerate() {
+        // the input vertices bounding the segments either end derives from
+        let (first, last) = (spans[j].0, spans[j + 1].1 + 1);
+        for (i, p) in input.iter().enumerate().take(last + 1).skip(first) {
+            nearest[i] = nearest[i].min(distance_to_segment(*p, w[0], w[1]));
+        }
+    }
+    for (p, nearest) in input.iter().zip(nearest) {
+        // not reached with valid spans, but never report infinity
+        let nearest = if nearest.is_finite() {
+            nearest
+        } else {
+            distance_to_polyline(*p, output)
+        };
+        deviation = deviation.max(nearest);
+    }
+    deviation
+}
+
+/// Largest distance from a vertex of one polyline to the other polyline.
+pub fn max_deviation(a: &[Vector2D], b: &[Vector2D]) -> f32 {
+    let one_way = |from: &[Vector2D], to: &[Vector2D]| {
+        from.iter()
+            .map(|p| distance_to_polyline(*p, to))
+            .fold(0.0, f32::max)
+    };
+    one_way(a, b).max(one_way(b, a))
+}
+
+fn distance_to_polyline(p: Vector2D, polyline: &[Vector2D]) -> f32 {
+    match polyline {
+        [] => f32::INFINITY,
+        [q] => p.distance(q),
+        _ => polyline
+            .windows(2)
+            .map(|w| distance_to_segment(p, w[0], w[1]))
+            .fold(f32::INFINITY, f32::min),
+    }
+}
+
+/// Ramer-Douglas-Peucker: the fewest-ish vertices such that every removed
+/// vertex lies within `epsilon` of the output.
+pub fn ramer_douglas_peucker(points: &[Vector2D], epsilon: f32) -> Approximation {
+    if points.len() < 3 {
+        return Approximation::from_kept(points, 0..points.len());
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let furthest = (first + 1..last)
+            .map(|i| {
+                (
+                    i,
+                    distance_to_segment(points[i], points[first], points[last]),
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = furthest
+            && distance > epsilon
+        {
+            keep[i] = true;
+            ranges.push((first, i));
+            ranges.push((i, last));
+        }
+    }
+
+    Approximation::from_kept(points, (0..points.len()).filter(|&i| keep[i]))
+}
+
+/// Visvalingam-Whyatt: drops vertices in order of increasing effective area
+/// until every remaining interior vertex spans a triangle of at least
+/// `min_area` with its neighbours.
+pub fn visvalingam_whyatt(points: &[Vector2D], min_area: f32) -> Approximation {
+    let n = points.len();
+    if n < 3 {
+        return Approximation::from_kept(points, 0..n);
+    }
+
+    let triangle_area = |a: usize, b: usize, c: usize| {
+        (points[b] - points[a])
+            .perp_dot(&(points[c] - points[a]))
+            .abs()
+            / 2.0
+    };
+
+    // doubly linked list over the surviving vertices
+    let mut previous: Vec<usize> = (0..n).map(|i| i.wrapping_sub(1)).collect();
+    let mut next: Vec<usize> = (1..=n).collect();
+    let mut area = vec![f32::INFINITY; n];
+    let mut removed = vec![false; n];
+
+    // Areas are non-negative, so their bit patterns order like the values;
+    // ties go to the lower index, which keeps the result deterministic.
+    let mut heap = BinaryHeap::new();
+    for (i, a) in area.iter_mut().enumerate().take(n - 1).skip(1) {
+        *a = triangle_area(i - 1, i, i + 1);
+        heap.push(Reverse((a.to_bits(), i)));
+    }
+
+    while let Some(Reverse((bits, i))) = heap.pop() {
+        if removed[i] || bits != area[i].to_bits() {
+            continue;
+        }
+        let smallest = area[i];
+        if smallest >= min_area {
+            break;
+        }
+        removed[i] = true;
+        let (before, after) = (previous[i], next[i]);
+        next[before] = after;
+        previous[after] = before;
+
+        // a neighbour never becomes cheaper to remove than the vertex just removed
+        for j in [before, after] {
+            if j != 0 && j != n - 1 {
+                area[j] = triangle_area(previous[j], j, next[j]).max(smallest);
+                heap.push(Reverse((area[j].to_bits(), j)));
+            }
+        }
+    }
+
+    Approximation::from_kept(points, (0..n).filter(|&i| !removed[i]))
+}
+
+/// Chaikin corner cutting: each pass replaces every segment by the points at
+/// a quarter and three quarters of its length, keeping both end points.
+/// The result approaches a quadratic B-spline.
+pub fn chaikin(points: &[Vector2D], iterations: usize) -> Approximation {
+    let mut current = points.to_vec();
+    let mut spans: Vec<_> = (0..points.len())
+        .map(|i| vertex_span(i, points.len()))
+        .collect();
+    if points.len() >= 3 {
+        for _ in 0..iterations {
+            let mut smoothed = Vec::with_capacity(2 * current.len());
+            let mut smoothed_spans = Vec::with_capacity(2 * current.len());
+            smoothed.push(current[0]);
+            smoothed_spans.push(spans[0]);
+            for (w, s) in current.windows(2).zip(spans.windows(2)) {
+                // a cut point lies between the inputs of both ends
+                let span = (s[0].0, s[1].1);
+                smoothed.push(w[0].lerp(&w[1], 0.25));
+                smoothed.push(w[0].lerp(&w[1], 0.75));
+                smoothed_spans.extend([span, span]);
+            }
+            smoothed.push(*current.last().unwrap());
+            smoothed_spans.push(*spans.last().unwrap());
+            (current, spans) = (smoothed, smoothed_spans);
+        }
+    }
+    Approximation::new(points, current, &spans)
+}
+
+/// Points every `spacing` along the polyline, measured along its length,
+/// followed by the last point if the length is not a multiple of `spacing`.
+///
+/// Panics if `spacing` is not positive.
+pub fn resample(points: &[Vector2D], spacing: f32) -> Approximation {
+    assert!(spacing > 0.0, "spacing must be positive");
+    let Some(&first) = points.first() else {
+        return Approximation::new(points, vec![], &[]);
+    };
+
+    // Sample i lies i * spacing along the polyline. Computing that from the
+    // index in f64, rather than adding up spacings, keeps the samples evenly
+    // spaced however many there are.
+    let spacing = spacing as f64;
+    let lengths: Vec<f64> = points
+        .windows(2)
+        .map(|w| w[0].distance(&w[1]) as f64)
+        .collect();
+    let count = (lengths.iter().sum::<f64>() / spacing) as usize;
+    let mut resampled = Vec::with_capacity(count.saturating_add(2));
+    let mut spans = Vec::with_capacity(count.saturating_add(2));
+    resampled.push(first);
+    spans.push((0, 0));
+
+    let mut next = 1_usize;
+    // distance along the polyline to the start of the current segment
+    let mut start = 0.0;
+    for (k, (w, &length)) in points.windows(2).zip(&lengths).enumerate() {
+        let end = start + length;
+        while next as f64 * spacing <= end {
+            let t = (next as f64 * spacing - start) / length;
+            resampled.push(w[0].lerp(&w[1], t as f32));
+            spans.push((k, k));
+            next += 1;
+        }
+        start = end;
+    }
+
+    let last = *points.last().unwrap();
+    if *resampled.last().unwrap() != last && ((next - 1) as f64) * spacing < start {
+        resampled.push(last);
+        let k = points.len().saturating_sub(2);
+        spans.push((k, k));
+    }
+    Approximation::new(points, resampled, &spans)
+}
+
+#[cfg(test)]
+mod tests_polyline {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    // deterministic wandering trace with small jitter
+    fn trace(count: usize) -> Vec<Vector2D> {
+        let mut state = 0x9e37_79b9_u32;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32 - 0.5
+        };
+        let mut heading: f32 = 0.0;
+        let mut p = Vector2D::ZERO;
+        (0..count)
+            .map(|_| {
+                heading += next() * 0.4;
+                p += Vector2D::from_polar(1.0, heading) + Vector2D::new(next(), next()) * 0.1;
+                p
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rdp_keeps_corners_and_bounds_the_error() {
+        let zigzag = vec![
+            v(0.0, 0.0),
+            v(1.0, 0.05),
+            v(2.0, -0.05),
+            v(3.0, 0.0),
+            v(3.05, 1.0),
+            v(3.0, 2.0),
+        ];
+        let simplified = ramer_douglas_peucker(&zigzag, 0.1);
+        assert_eq!(
+            simplified.points,
+            vec![v(0.0, 0.0), v(3.0, 0.0), v(3.0, 2.0)]
+        );
+        assert_approx_eq!(simplified.max_deviation, 0.05);
+        assert_eq!(ramer_douglas_peucker(&zigzag, 0.0).points, zigzag);
+
+        let trace = trace(2000);
+        for epsilon in [0.1, 0.5, 2.0] {
+            let simplified = ramer_douglas_peucker(&trace, epsilon);
+            assert!(simplified.max_deviation <= epsilon);
+            assert_eq!(simplified.points[0], trace[0]);
+            assert_eq!(simplified.points.last(), trace.last());
+            assert!(simplified.points.len() < trace.len() / 2);
+        }
+    }
+
+    #[test]
+    fn rdp_measures_distance_to_segments_not_lines() {
+        // the path doubles back beyond its end point
+        let out_and_back = vec![v(0.0, 0.0), v(5.0, 0.0), v(2.0, 0.0)];
+        assert_eq!(
+            ramer_douglas_peucker(&out_and_back, 1.0).points,
+            out_and_back
+        );
+    }
+
+    #[test]
+    fn visvalingam_drops_the_smallest_triangles_first() {
+        let line = vec![v(0.0, 0.0), v(1.0, 0.0), v(2.0, 0.0), v(2.0, 2.0)];
+        assert_eq!(
+            visvalingam_whyatt(&line, 1e-6).points,
+            vec![v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0)]
+        );
+
+        // a small bump (area 0.1) and a big corner (area 4)
+        let shape = vec![
+            v(0.0, 0.0),
+            v(1.0, 0.2),
+            v(2.0, 0.0),
+            v(4.0, 0.0),
+            v(4.0, 4.0),
+        ];
+        let mild = visvalingam_whyatt(&shape, 1.0);
+        assert_eq!(mild.points, vec![v(0.0, 0.0), v(4.0, 0.0), v(4.0, 4.0)]);
+        assert_approx_eq!(mild.max_deviation, 0.2);
+        let all = visvalingam_whyatt(&shape, 100.0);
+        assert_eq!(all.points, vec![v(0.0, 0.0), v(4.0, 4.0)]);
+
+        let trace = trace(2000);
+        let mut previous = trace.len();
+        for min_area in [0.01, 0.1, 1.0, 10.0] {
+            let simplified = visvalingam_whyatt(&trace, min_area);
+            assert!(simplified.points.len() <= previous);
+            assert_eq!(simplified.points.last(), trace.last());
+            previous = simplified.points.len();
+        }
+        assert!(previous < trace.len() / 10);
+    }
+
+    #[test]
+    fn chaikin_cuts_corners() {
+        let corner = vec![v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0)];
+        let once = chaikin(&corner, 1);
+        assert_eq!(
+            once.points,
+            vec![
+                v(0.0, 0.0),
+                v(0.25, 0.0),
+                v(0.75, 0.0),
+                v(1.0, 0.25),
+                v(1.0, 0.75),
+                v(1.0, 1.0),
+            ]
+        );
+        assert_approx_eq!(once.max_deviation, 0.25 / 2f32.sqrt());
+
+        let thrice = chaikin(&corner, 3);
+        // every pass doubles the vertex count
+        assert_eq!(thrice.points.len(), 3 * 8);
+        assert_eq!(thrice.points.first(), corner.first());
+        assert_eq!(thrice.points.last(), corner.last());
+        // the limit curve touches the middle of the first cut, so the corner
+        // is never cut deeper than by the first pass
+        assert_approx_eq!(thrice.max_deviation, once.max_deviation, epsilon = 1e-5);
+
+        assert_eq!(chaikin(&corner, 0).points, corner);
+        assert_eq!(chaikin(&corner[..2], 5).points, corner[..2].to_vec());
+    }
+
+    #[test]
+    fn resampling_walks_along_the_path() {
+        let line = vec![v(0.0, 0.0), v(10.0, 0.0)];
+        let samples = resample(&line, 3.0);
+        assert_eq!(
+            samples.points,
+            vec![
+                v(0.0, 0.0),
+                v(3.0, 0.0),
+                v(6.0, 0.0),
+                v(9.0, 0.0),
+                v(10.0, 0.0)
+            ]
+        );
+        assert_eq!(samples.max_deviation, 0.0);
+        assert_eq!(resample(&line, 5.0).points.len(), 3);
+
+        // spacing is measured along the corner, so the chord cuts it
+        let corner = vec![v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0)];
+        let samples = resample(&corner, 1.5);
+        assert_eq!(samples.points.len(), 4);
+        assert_approx_eq!(samples.points[2], v(2.0, 1.0));
+        assert_approx_eq!(
+            samples.max_deviation,
+            distance_to_segment(v(2.0, 0.0), v(1.5, 0.0), v(2.0, 1.0))
+        );
+
+        let trace = trace(500);
+        let samples = resample(&trace, 2.5);
+        for w in samples.points[..samples.points.len() - 1].windows(2) {
+            assert!(w[0].distance(&w[1]) <= 2.5 + 1e-3);
+        }
+        assert!(resample(&[], 1.0).points.is_empty());
+        assert_eq!(resample(&[v(1.0, 1.0)], 1.0).points, vec![v(1.0, 1.0)]);
+    }
+
+    #[test]
+    fn resampling_does_not_drift() {
+        // adding up 0.1 in f32 ten thousand times drifts by about 3e-3
+        let line = vec![v(0.0, 0.0), v(1000.0, 0.0)];
+        let samples = resample(&line, 0.1);
+        assert_eq!(samples.points.len(), 10_001);
+        for (i, p) in samples.points.iter().enumerate() {
+            assert_approx_eq!(p.x, i as f32 * 0.1, epsilon = 1e-3);
+        }
+    }
+
+    #[test]
+    fn deviation_matches_the_exact_measure() {
+        let trace = trace(200);
+        for approximation in [
+            ramer_douglas_peucker(&trace, 0.5),
+            visvalingam_whyatt(&trace, 1.0),
+            chaikin(&trace, 2),
+            resample(&trace, 2.5),
+        ] {
+            assert_approx_eq!(
+                approximation.max_deviation,
+                max_deviation(&trace, &approximation.points),
+                epsilon = 1e-4
+            );
+        }
+    }
+}
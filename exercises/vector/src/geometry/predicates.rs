@@ -0,0 +1,245 @@
+use crate::vector2d::Vector2D;
+
+// Exact geometric predicates.
+//
+// Each predicate is first evaluated in f64 together with an error bound
+// (Shewchuk, "Adaptive Precision Floating-Point Arithmetic and Fast Robust
+// Geometric Predicates"). Only when the result is too close to zero to trust
+// is it recomputed exactly with floating-point expansions, so the sign of the
+// returned value is always correct while the magnitude is approximate.
+
+const EPSILON: f64 = f64::EPSILON / 2.0;
+const ORIENT_BOUND: f64 = (3.0 + 16.0 * EPSILON) * EPSILON;
+const IN_CIRCLE_BOUND: f64 = (10.0 + 96.0 * EPSILON) * EPSILON;
+
+/// Positive when a, b, c turn counter-clockwise, negative when clockwise,
+/// zero when collinear. Approximately twice the triangle's signed area.
+pub fn orient2d(a: Vector2D, b: Vector2D, c: Vector2D) -> f64 {
+    let (ax, ay, bx, by, cx, cy) = widen(a, b, c);
+    let left = (ax - cx) * (by - cy);
+    let right = (ay - cy) * (bx - cx);
+    let det = left - right;
+
+    if det.abs() > ORIENT_BOUND * (left.abs() + right.abs()) {
+        return det;
+    }
+
+    let left = Expansion::difference(ax, cx).product(&Expansion::difference(by, cy));
+    let right = Expansion::difference(ay, cy).product(&Expansion::difference(bx, cx));
+    left.sum(&right.negate()).estimate()
+}
+
+/// Positive when `d` lies inside the circle through the counter-clockwise
+/// triangle a, b, c, negative outside, zero on the circle. The sign flips
+/// for clockwise triangles.
+pub fn in_circle(a: Vector2D, b: Vector2D, c: Vector2D, d: Vector2D) -> f64 {
+    let (ax, ay, bx, by, cx, cy) = widen(a, b, c);
+    let (dx, dy) = (d.x as f64, d.y as f64);
+
+    let (adx, ady) = (ax - dx, ay - dy);
+    let (bdx, bdy) = (bx - dx, by - dy);
+    let (cdx, cdy) = (cx - dx, cy - dy);
+
+    let (bc, cb) = (bdx * cdy, cdx * bdy);
+    let (ca, ac) = (cdx * ady, adx * cdy);
+    let (ab, ba) = (adx * bdy, bdx * ady);
+    let a_lift = adx * adx + ady * ady;
+    let b_lift = bdx * bdx + bdy * bdy;
+    let c_lift = cdx * cdx + cdy * cdy;
+
+    let det = a_lift * (bc - cb) + b_lift * (ca - ac) + c_lift * (ab - ba);
+    let permanent = a_lift * (bc.abs() + cb.abs())
+        + b_lift * (ca.abs() + ac.abs())
+        + c_lift * (ab.abs() + ba.abs());
+    if det.abs() > IN_CIRCLE_BOUND * permanent {
+        return det;
+    }
+
+    let adx = Expansion::difference(ax, dx);
+    let ady = Expansion::difference(ay, dy);
+    let bdx = Expansion::difference(bx, dx);
+    let bdy = Expansion::difference(by, dy);
+    let cdx = Expansion::difference(cx, dx);
+    let cdy = Expansion::difference(cy, dy);
+
+    let lift = |x: &Expansion, y: &Expansion| x.product(x).sum(&y.product(y));
+    let cross = |x1: &Expansion, y1: &Expansion, x2: &Expansion, y2: &Expansion| {
+        x1.product(y2).sum(&x2.product(y1).negate())
+    };
+
+    lift(&adx, &ady)
+        .product(&cross(&bdx, &bdy, &cdx, &cdy))
+        .sum(&lift(&bdx, &bdy).product(&cross(&cdx, &cdy, &adx, &ady)))
+        .sum(&lift(&cdx, &cdy).product(&cross(&adx, &ady, &bdx, &bdy)))
+        .estimate()
+}
+
+fn widen(a: Vector2D, b: Vector2D, c: Vector2D) -> (f64, f64, f64, f64, f64, f64) {
+    (
+        a.x as f64, a.y as f64, b.x as f64, b.y as f64, c.x as f64, c.y as f64,
+    )
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_virtual = sum - a;
+    let a_virtual = sum - b_virtual;
+    (sum, (a - a_virtual) + (b - b_virtual))
+}
+
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let product = a * b;
+    (product, a.mul_add(b, -product))
+}
+
+// Exact sum of non-overlapping f64 components, ordered by increasing magnitude
+struct Expansion(Vec<f64>);
+
+impl Expansion {
+    fn difference(a: f64, b: f64) -> Expansion {
+        let (sum, error) = two_sum(a, -b);
+        Expansion(vec![error, sum]).compress()
+    }
+
+    fn compress(self) -> Expansion {
+        Expansion(self.0.into_iter().filter(|&c| c != 0.0).collect())
+    }
+
+    fn grow(&self, b: f64) -> Expansion {
+        let mut q = b;
+        let mut components = Vec::with_capacity(self.0.len() + 1);
+        for &e in &self.0 {
+            let (sum, error) = two_sum(q, e);
+            components.push(error);
+            q = sum;
+        }
+        components.push(q);
+        Expansion(components).compress()
+    }
+
+    fn sum(&self, other: &Expansion) -> Expansion {
+        other
+            .0
+            .iter()
+            .fold(Expansion(self.0.clone()), |acc, &c| acc.grow(c))
+    }
+
+    fn scale(&self, b: f64) -> Expansion {
+        let mut result = Expansion(Vec::new());
+        for &e in &self.0 {
+            let (product, error) = two_product(e, b);
+            result = result.grow(error).grow(product);
+        }
+        result
+    }
+
+    fn product(&self, other: &Expansion) -> Expansion {
+        other
+            .0
+            .iter()
+            .fold(Expansion(Vec::new()), |acc, &c| acc.sum(&self.scale(c)))
+    }
+
+    fn negate(&self) -> Expansion {
+        Expansion(self.0.iter().map(|c| -c).collect())
+    }
+
+    // the largest component carries the sign
+    fn estimate(&self) -> f64 {
+        self.0.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests_predicates {
+    use super::*;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    #[test]
+    fn orientation_signs() {
+        assert!(orient2d(v(0.0, 0.0), v(1.0, 0.0), v(0.0, 1.0)) > 0.0);
+        assert!(orient2d(v(0.0, 0.0), v(0.0, 1.0), v(1.0, 0.0)) < 0.0);
+        assert_eq!(orient2d(v(0.0, 0.0), v(1.0, 1.0), v(2.0, 2.0)), 0.0);
+        assert_eq!(orient2d(v(1.0, 2.0), v(1.0, 2.0), v(3.0, 5.0)), 0.0);
+    }
+
+    #[test]
+    fn orientation_of_nearly_collinear_points_is_exact() {
+        // points one ulp apart near the diagonal y = x, checked against integer arithmetic
+        let a = v(0.5, 0.5);
+        let b = v(12.0, 12.0);
+        let c = v(24.0, 24.0);
+        for i in 0..64 {
+            let p = v(0.5 + i as f32 * f32::EPSILON, 0.5);
+            let expected = exact_orient(p, b, c);
+            assert_eq!(
+                orient2d(p, b, c).partial_cmp(&0.0),
+                expected.partial_cmp(&0.0),
+                "i = {}",
+                i
+            );
+        }
+        let nudged = v(f32::from_bits(24.0f32.to_bits() + 1), 24.0);
+        assert!(orient2d(a, b, nudged) < 0.0);
+        assert!(orient2d(a, b, c) == 0.0);
+    }
+
+    // reference via i128 on coordinates that are multiples of 2^-24
+    fn exact_orient(a: Vector2D, b: Vector2D, c: Vector2D) -> f64 {
+        let scale = |x: f32| (x as f64 * (1u64 << 24) as f64) as i128;
+        let (ax, ay, bx, by, cx, cy) = (
+            scale(a.x),
+            scale(a.y),
+            scale(b.x),
+            scale(b.y),
+            scale(c.x),
+            scale(c.y),
+        );
+        ((bx - ax) * (cy - ay) - (by - ay) * (cx - ax)) as f64
+    }
+
+    #[test]
+    fn in_circle_signs() {
+        let (a, b, c) = (v(0.0, 0.0), v(1.0, 0.0), v(0.0, 1.0));
+        assert!(in_circle(a, b, c, v(0.5, 0.5)) > 0.0);
+        assert!(in_circle(a, b, c, v(2.0, 2.0)) < 0.0);
+        assert_eq!(in_circle(a, b, c, v(1.0, 1.0)), 0.0);
+        // clockwise triangle flips the sign
+        assert!(in_circle(a, c, b, v(0.5, 0.5)) < 0.0);
+    }
+
+    #[test]
+    fn in_circle_of_cocircular_points_far_from_origin() {
+        // a square far away: all four corners are exactly cocircular
+        let base = 1_000_000.0;
+        let (a, b, c, d) = (
+            v(base, base),
+            v(base + 1.0, base),
+            v(base + 1.0, base + 1.0),
+            v(base, base + 1.0),
+        );
+        assert_eq!(in_circle(a, b, c, d), 0.0);
+
+        let inside = v(base + 0.5, base + 1.0);
+        assert!(in_circle(a, b, c, inside) > 0.0);
+        let outside = v(base, base + 1.0625);
+        assert!(in_circle(a, b, c, outside) < 0.0);
+    }
+
+    #[test]
+    fn expansion_arithmetic_is_exact() {
+        let big = Expansion::difference(1e16, -1.0);
+        let small = Expansion::difference(0.0, 1e16);
+        assert_eq!(big.sum(&small).estimate(), 1.0);
+
+        let x = Expansion::difference(1.0 + f64::EPSILON, 0.0);
+        let squared = x.product(&x);
+        let one = Expansion::difference(1.0, 0.0);
+        let two_eps = Expansion::difference(2.0 * f64::EPSILON, 0.0);
+        let rest = squared.sum(&one.negate()).sum(&two_eps.negate());
+        assert_eq!(rest.estimate(), f64::EPSILON * f64::EPSILON);
+    }
+}
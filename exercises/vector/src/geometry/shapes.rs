@@ -0,0 +1,1325 @@
+use crate::geometry::algorithms::{self, Containment};
+use crate::vector2d::Vector2D;
+
+// Geometric primitives built on Vector2D.
+//
+// Every shape describes its boundary as a list of `Element`s (pieces of lines
+// and circles). Intersection, containment and closest-point queries between
+// any two shapes are computed from those elements, so each shape only has to
+// provide its boundary and a point-in-shape test.
+//
+// Comparisons use the absolute `TOLERANCE`, which suits coordinates of
+// roughly unit magnitude; touching shapes count as intersecting.
+
+pub const TOLERANCE: f32 = 1e-5;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Segment {
+    pub start: Vector2D,
+    pub end: Vector2D,
+}
+
+/// Half-line `origin + t * direction` for t >= 0.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Ray {
+    pub origin: Vector2D,
+    pub direction: Vector2D,
+}
+
+/// Infinite line through `point` along `direction`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Line {
+    pub point: Vector2D,
+    pub direction: Vector2D,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Circle {
+    pub center: Vector2D,
+    pub radius: f32,
+}
+
+/// Axis-aligned bounding box.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Aabb {
+    pub min: Vector2D,
+    pub max: Vector2D,
+}
+
+/// Simple polygon (convex or concave, no self-intersections) in either winding order.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Polygon {
+    vertices: Vec<Vector2D>,
+}
+
+/// Where a linear shape (segment, ray, line) crosses the boundary of another shape.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Hit {
+    pub point: Vector2D,
+    /// Unit normal of the boundary that was hit: outward for solid shapes,
+    /// facing against the query direction for segments, rays and lines.
+    pub normal: Vector2D,
+    /// Parameter along the query shape: `point = origin + t * direction`.
+    pub t: f32,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ClosestPoints {
+    pub on_self: Vector2D,
+    pub on_other: Vector2D,
+    pub distance: f32,
+}
+
+/// Part of the line `origin + t * direction` with t in [t_min, t_max]
+/// (the bounds may be infinite).
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct LinearSpan {
+    pub origin: Vector2D,
+    pub direction: Vector2D,
+    pub t_min: f32,
+    pub t_max: f32,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Element {
+    /// Edge of a solid shape, traversed counter-clockwise so the outward normal points right.
+    Edge(LinearSpan),
+    /// Segment, ray or line that does not enclose anything.
+    Linear(LinearSpan),
+    Arc(Circle),
+}
+
+/////////////////////////////////////////////////////////
+// Constructors and shape-specific helpers
+
+impl Segment {
+    pub fn new(start: Vector2D, end: Vector2D) -> Self {
+        Segment { start, end }
+    }
+
+    pub fn direction(&self) -> Vector2D {
+        self.end - self.start
+    }
+
+    pub fn length(&self) -> f32 {
+        self.direction().length()
+    }
+
+    pub fn midpoint(&self) -> Vector2D {
+        self.start.lerp(&self.end, 0.5)
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2D {
+        self.start + self.direction() * t
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.start, self.end)
+    }
+}
+
+impl Ray {
+    pub fn new(origin: Vector2D, direction: Vector2D) -> Self {
+        Ray { origin, direction }
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2D {
+        self.origin + self.direction * t
+    }
+}
+
+impl Line {
+    pub fn new(point: Vector2D, direction: Vector2D) -> Self {
+        Line { point, direction }
+    }
+
+    pub fn through(a: Vector2D, b: Vector2D) -> Self {
+        Line::new(a, b - a)
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2D {
+        self.point + self.direction * t
+    }
+
+    /// Signed distance, positive on the left of the direction.
+    pub fn signed_distance(&self, p: Vector2D) -> f32 {
+        self.direction.perp_dot(&(p - self.point)) / self.direction.length()
+    }
+}
+
+impl Circle {
+    pub fn new(center: Vector2D, radius: f32) -> Self {
+        Circle { center, radius }
+    }
+
+    pub fn area(&self) -> f32 {
+        std::f32::consts::PI * self.radius * self.radius
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        let r = Vector2D::new(self.radius, self.radius);
+        Aabb::new(self.center - r, self.center + r)
+    }
+}
+
+impl Aabb {
+    /// Box spanned by two opposite corners given in any order.
+    pub fn new(a: Vector2D, b: Vector2D) -> Self {
+        Aabb {
+            min: Vector2D::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Vector2D::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// Smallest box containing all points, `None` for no points.
+    pub fn from_points(points: impl IntoIterator<Item = Vector2D>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Aabb::new(first, first), |aabb, p| {
+            aabb.union(&Aabb::new(p, p))
+        }))
+    }
+
+    pub fn center(&self) -> Vector2D {
+        self.min.lerp(&self.max, 0.5)
+    }
+
+    pub fn size(&self) -> Vector2D {
+        self.max - self.min
+    }
+
+    pub fn area(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: Vector2D::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Vector2D::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    pub fn expand(&self, margin: f32) -> Aabb {
+        let m = Vector2D::new(margin, margin);
+        Aabb::new(self.min - m, self.max + m)
+    }
+
+    /// Corners in counter-clockwise order starting at `min`.
+    pub fn corners(&self) -> [Vector2D; 4] {
+        [
+            self.min,
+            Vector2D::new(self.max.x, self.min.y),
+            self.max,
+            Vector2D::new(self.min.x, self.max.y),
+        ]
+    }
+
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon::new(self.corners().to_vec())
+    }
+}
+
+impl Polygon {
+    /// Panics with fewer than three vertices.
+    pub fn new(vertices: Vec<Vector2D>) -> Self {
+        assert!(vertices.len() >= 3, "polygon needs at least 3 vertices");
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Vector2D] {
+        &self.vertices
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| Segment::new(self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Positive for counter-clockwise winding (shoelace formula).
+    pub fn signed_area(&self) -> f32 {
+        algorithms::polygon_signed_area(&self.vertices)
+    }
+
+    pub fn is_ccw(&self) -> bool {
+        self.signed_area() > 0.0
+    }
+
+    pub fn is_convex(&self) -> bool {
+        let n = self.vertices.len();
+        let mut sign = 0.0;
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            let c = self.vertices[(i + 2) % n];
+            let turn = (b - a).perp_dot(&(c - b));
+            if turn.abs() <= TOLERANCE {
+                continue;
+            }
+            if sign == 0.0 {
+                sign = turn.signum();
+            } else if turn.signum() != sign {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.vertices.iter().copied()).unwrap()
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Shape trait
+
+pub trait Shape {
+    /// Boundary pieces; solid shapes list their edges counter-clockwise.
+    fn boundary(&self) -> Vec<Element>;
+
+    /// Closed test: points on the boundary are contained.
+    fn contains_point(&self, p: Vector2D) -> bool;
+
+    /// Solid shapes enclose an area (circle, box, polygon).
+    fn is_solid(&self) -> bool;
+
+    fn is_bounded(&self) -> bool;
+
+    /// Any point belonging to the shape.
+    fn reference_point(&self) -> Vector2D;
+
+    /// Closest point of the shape (its interior included for solids).
+    fn closest_point(&self, p: Vector2D) -> Vector2D {
+        if self.is_solid() && self.contains_point(p) {
+            return p;
+        }
+        self.boundary()
+            .iter()
+            .map(|e| e.closest_point(p))
+            .min_by(|a, b| a.distance_squared(&p).total_cmp(&b.distance_squared(&p)))
+            .unwrap_or_else(|| self.reference_point())
+    }
+
+    fn distance_to_point(&self, p: Vector2D) -> f32 {
+        self.closest_point(p).distance(&p)
+    }
+
+    /// Points where the boundaries of the two shapes meet.
+    fn intersection_points<S: Shape + ?Sized>(&self, other: &S) -> Vec<Vector2D> {
+        let mut points: Vec<Vector2D> = Vec::new();
+        let other_boundary = other.boundary();
+        for a in self.boundary() {
+            for b in &other_boundary {
+                for p in a.intersections(b) {
+                    if points.iter().all(|q| q.distance(&p) > TOLERANCE) {
+                        points.push(p);
+                    }
+                }
+            }
+        }
+        points
+    }
+
+    fn intersects<S: Shape + ?Sized>(&self, other: &S) -> bool {
+        (other.is_solid() && other.contains_point(self.reference_point()))
+            || (self.is_solid() && self.contains_point(other.reference_point()))
+            || !self.intersection_points(other).is_empty()
+    }
+
+    /// True when every point of `other` belongs to `self`.
+    fn contains<S: Shape + ?Sized>(&self, other: &S) -> bool {
+        if !other.is_bounded() || !self.contains_point(other.reference_point()) {
+            return false;
+        }
+
+        let boundary = self.boundary();
+        other.boundary().iter().all(|element| match element {
+            Element::Edge(span) | Element::Linear(span) => {
+                // the span is inside if its end points and every piece between
+                // two crossings of our boundary are inside
+                let mut ts = vec![span.t_min, span.t_max];
+                for own in &boundary {
+                    ts.extend(span.parameters_of(&element.intersections(own)));
+                }
+                ts.sort_by(f32::total_cmp);
+                ts.iter()
+                    .chain(
+                        ts.windows(2)
+                            .map(|w| (w[0] + w[1]) / 2.0)
+                            .collect::<Vec<_>>()
+                            .iter(),
+                    )
+                    .all(|&t| self.contains_point(span.point_at(t)))
+            }
+            Element::Arc(circle) => {
+                self.is_solid()
+                    && self.contains_point(circle.center)
+                    && boundary.iter().all(|own| {
+                        own.closest_point(circle.center).distance(&circle.center)
+                            >= circle.radius - TOLERANCE
+                    })
+            }
+        })
+    }
+
+    fn closest_points<S: Shape + ?Sized>(&self, other: &S) -> ClosestPoints {
+        let common = if other.is_solid() && other.contains_point(self.reference_point()) {
+            Some(self.reference_point())
+        } else if self.is_solid() && self.contains_point(other.reference_point()) {
+            Some(other.reference_point())
+        } else {
+            self.intersection_points(other).first().copied()
+        };
+        if let Some(p) = common {
+            return ClosestPoints {
+                on_self: p,
+                on_other: p,
+                distance: 0.0,
+            };
+        }
+
+        let other_boundary = other.boundary();
+        self.boundary()
+            .iter()
+            .flat_map(|a| other_boundary.iter().map(move |b| a.closest_points(b)))
+            .map(|(on_self, on_other)| ClosestPoints {
+                on_self,
+                on_other,
+                distance: on_self.distance(&on_other),
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+            .unwrap_or_else(|| {
+                // a boundary made only of zero-length pieces is empty, and
+                // the shape is the single point `closest_point` falls back to
+                let (on_self, on_other) = if other_boundary.is_empty() {
+                    let q = other.reference_point();
+                    (self.closest_point(q), q)
+                } else {
+                    let p = self.reference_point();
+                    (p, other.closest_point(p))
+                };
+                ClosestPoints {
+                    on_self,
+                    on_other,
+                    distance: on_self.distance(&on_other),
+                }
+            })
+    }
+}
+
+/// Segments, rays and lines can be cast against other shapes.
+pub trait Linear: Shape {
+    fn span(&self) -> LinearSpan;
+
+    /// All boundary crossings of `other`, ordered by the parameter along `self`.
+    fn hits<S: Shape + ?Sized>(&self, other: &S) -> Vec<Hit> {
+        let span = self.span();
+        let query = Element::Linear(span);
+        let mut hits: Vec<Hit> = Vec::new();
+
+        for element in other.boundary() {
+            for point in query.intersections(&element) {
+                let mut normal = element.normal_at(point);
+                if matches!(element, Element::Linear(_)) && normal.dot(&span.direction) > 0.0 {
+                    normal = -normal;
+                }
+                hits.push(Hit {
+                    point,
+                    normal,
+                    t: span.parameter_of(point),
+                });
+            }
+        }
+
+        hits.sort_by(|a, b| a.t.total_cmp(&b.t));
+        // a corner hit is reported by both adjacent edges
+        hits.dedup_by(|a, b| a.point.distance(&b.point) <= TOLERANCE);
+        hits
+    }
+
+    fn first_hit<S: Shape + ?Sized>(&self, other: &S) -> Option<Hit> {
+        self.hits(other).into_iter().next()
+    }
+}
+
+impl Shape for Segment {
+    fn boundary(&self) -> Vec<Element> {
+        vec![Element::Linear(self.span())]
+    }
+
+    fn contains_point(&self, p: Vector2D) -> bool {
+        self.span().closest_point(p).distance(&p) <= TOLERANCE
+    }
+
+    fn is_solid(&self) -> bool {
+        false
+    }
+
+    fn is_bounded(&self) -> bool {
+        true
+    }
+
+    fn reference_point(&self) -> Vector2D {
+        self.start
+    }
+}
+
+impl Linear for Segment {
+    fn span(&self) -> LinearSpan {
+        LinearSpan::new(self.start, self.direction(), 0.0, 1.0)
+    }
+}
+
+impl Shape for Ray {
+    fn boundary(&self) -> Vec<Element> {
+        vec![Element::Linear(self.span())]
+    }
+
+    fn contains_point(&self, p: Vector2D) -> bool {
+        self.span().closest_point(p).distance(&p) <= TOLERANCE
+    }
+
+    fn is_solid(&self) -> bool {
+        false
+    }
+
+    fn is_bounded(&self) -> bool {
+        false
+    }
+
+    fn reference_point(&self) -> Vector2D {
+        self.origin
+    }
+}
+
+impl Linear for Ray {
+    fn span(&self) -> LinearSpan {
+        LinearSpan::new(self.origin, self.direction, 0.0, f32::INFINITY)
+    }
+}
+
+impl Shape for Line {
+    fn boundary(&self) -> Vec<Element> {
+        vec![Element::Linear(self.span())]
+    }
+
+    fn contains_point(&self, p: Vector2D) -> bool {
+        self.span().closest_point(p).distance(&p) <= TOLERANCE
+    }
+
+    fn is_solid(&self) -> bool {
+        false
+    }
+
+    fn is_bounded(&self) -> bool {
+        false
+    }
+
+    fn reference_point(&self) -> Vector2D {
+        self.point
+    }
+}
+
+impl Linear for Line {
+    fn span(&self) -> LinearSpan {
+        LinearSpan::new(self.point, self.direction, f32::NEG_INFINITY, f32::INFINITY)
+    }
+}
+
+impl Shape for Circle {
+    fn boundary(&self) -> Vec<Element> {
+        vec![Element::Arc(*self)]
+    }
+
+    fn contains_point(&self, p: Vector2D) -> bool {
+        p.distance(&self.center) <= self.radius + TOLERANCE
+    }
+
+    fn is_solid(&self) -> bool {
+        true
+    }
+
+    fn is_bounded(&self) -> bool {
+        true
+    }
+
+    fn reference_point(&self) -> Vector2D {
+        self.center
+    }
+}
+
+impl Shape for Aabb {
+    fn boundary(&self) -> Vec<Element> {
+        polygon_boundary(&self.corners())
+    }
+
+    fn contains_point(&self, p: Vector2D) -> bool {
+        p.x >= self.min.x - TOLERANCE
+            && p.x <= self.max.x + TOLERANCE
+            && p.y >= self.min.y - TOLERANCE
+            && p.y <= self.max.y + TOLERANCE
+    }
+
+    fn is_solid(&self) -> bool {
+        true
+    }
+
+    fn is_bounded(&self) -> bool {
+        true
+    }
+
+    fn reference_point(&self) -> Vector2D {
+        self.min
+    }
+}
+
+impl Shape for Polygon {
+    fn boundary(&self) -> Vec<Element> {
+        if self.is_ccw() {
+            polygon_boundary(&self.vertices)
+        } else {
+            let reversed: Vec<Vector2D> = self.vertices.iter().rev().copied().collect();
+            polygon_boundary(&reversed)
+        }
+    }
+
+    fn contains_point(&self, p: Vector2D) -> bool {
+        algorithms::point_in_polygon(p, &self.vertices) != Containment::Outside
+    }
+
+    fn is_solid(&self) -> bool {
+        true
+    }
+
+    fn is_bounded(&self) -> bool {
+        true
+    }
+
+    fn reference_point(&self) -> Vector2D {
+        self.vertices[0]
+    }
+}
+
+fn polygon_boundary(ccw_vertices: &[Vector2D]) -> Vec<Element> {
+    let n = ccw_vertices.len();
+    (0..n)
+        .map(|i| (ccw_vertices[i], ccw_vertices[(i + 1) % n]))
+        .filter(|(a, b)| a != b)
+        .map(|(a, b)| Element::Edge(LinearSpan::new(a, b - a, 0.0, 1.0)))
+        .collect()
+}
+
+/////////////////////////////////////////////////////////
+// Boundary elements
+
+impl LinearSpan {
+    pub fn new(origin: Vector2D, direction: Vector2D, t_min: f32, t_max: f32) -> Self {
+        LinearSpan {
+            origin,
+            direction,
+            t_min,
+            t_max,
+        }
+    }
+
+    pub fn point_at(&self, t: f32) -> Vector2D {
+        self.origin + self.direction * t
+    }
+
+    /// Parameter of the projection of `p` onto the (unbounded) line.
+    pub fn parameter_of(&self, p: Vector2D) -> f32 {
+        let length_squared = self.direction.length_squared();
+        if length_squared == 0.0 {
+            return 0.0;
+        }
+        (p - self.origin).dot(&self.direction) / length_squared
+    }
+
+    fn parameters_of(&self, points: &[Vector2D]) -> Vec<f32> {
+        points.iter().map(|&p| self.parameter_of(p)).collect()
+    }
+
+    pub fn closest_point(&self, p: Vector2D) -> Vector2D {
+        let t = self.parameter_of(p).clamp(self.t_min, self.t_max);
+        self.point_at(t)
+    }
+
+    fn contains_point(&self, p: Vector2D) -> bool {
+        self.closest_point(p).distance(&p) <= TOLERANCE
+    }
+
+    fn contains_parameter(&self, t: f32) -> bool {
+        let slack = TOLERANCE / self.direction.length().max(TOLERANCE);
+        t >= self.t_min - slack && t <= self.t_max + slack
+    }
+
+    fn finite_end_points(&self) -> Vec<Vector2D> {
+        [self.t_min, self.t_max]
+            .into_iter()
+            .filter(|t| t.is_finite())
+            .map(|t| self.point_at(t))
+            .collect()
+    }
+
+    fn intersect_span(&self, other: &LinearSpan) -> Vec<Vector2D> {
+        // a zero-length span is a single point, which the parallel and
+        // collinear tests below would accept for any other span
+        if self.direction == Vector2D::ZERO {
+            return if other.contains_point(self.origin) {
+                vec![self.origin]
+            } else {
+                vec![]
+            };
+        }
+        if other.direction == Vector2D::ZERO {
+            return other.intersect_span(self);
+        }
+
+        let cross = self.direction.perp_dot(&other.direction);
+        let w = other.origin - self.origin;
+        let scale = self.direction.length() * other.direction.length();
+
+        if cross.abs() > TOLERANCE * scale {
+            let t = w.perp_dot(&other.direction) / cross;
+            let u = w.perp_dot(&self.direction) / cross;
+            return if self.contains_parameter(t) && other.contains_parameter(u) {
+                vec![self.point_at(t)]
+            } else {
+                vec![]
+            };
+        }
+
+        // parallel: only collinear spans can meet
+        if w.perp_dot(&self.direction).abs() > TOLERANCE * self.direction.length() {
+            return vec![];
+        }
+
+        // overlap of the two parameter ranges expressed along `self`; the
+        // mapping is affine, so no points far along `other` are formed
+        let offset = self.parameter_of(other.origin);
+        let rate = other.direction.dot(&self.direction) / self.direction.length_squared();
+        let along = |t: f32| {
+            if t.is_finite() {
+                offset + t * rate
+            } else if (t > 0.0) == (rate > 0.0) {
+                f32::INFINITY
+            } else {
+                f32::NEG_INFINITY
+            }
+        };
+        let (a, b) = (along(other.t_min), along(other.t_max));
+        let lo = self.t_min.max(a.min(b));
+        let hi = self.t_max.min(a.max(b));
+        if lo > hi + TOLERANCE {
+            return vec![];
+        }
+
+        let mut points: Vec<Vector2D> = [lo, hi]
+            .into_iter()
+            .filter(|t| t.is_finite())
+            .map(|t| self.point_at(t))
+            .collect();
+        points.dedup_by(|p, q| p.distance(q) <= TOLERANCE);
+        if points.is_empty() {
+            // two overlapping infinite lines
+            points.push(self.point_at(0.0f32.clamp(lo, hi)));
+        }
+        points
+    }
+
+    fn intersect_circle(&self, circle: &Circle) -> Vec<Vector2D> {
+        let f = self.origin - circle.center;
+        let a = self.direction.length_squared();
+        if a == 0.0 {
+            return vec![];
+        }
+        let b = 2.0 * f.dot(&self.direction);
+        let c = f.length_squared() - circle.radius * circle.radius;
+        let discriminant = b * b - 4.0 * a * c;
+
+        // tangent lines touch within tolerance of the radius
+        let closest = self.point_at(-b / (2.0 * a));
+        let miss = closest.distance(&circle.center) - circle.radius;
+        if miss > TOLERANCE {
+            return vec![];
+        }
+
+        let ts = if miss.abs() <= TOLERANCE || discriminant <= 0.0 {
+            vec![-b / (2.0 * a)]
+        } else {
+            let root = discriminant.sqrt();
+            vec![(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+        };
+        ts.into_iter()
+            .filter(|&t| self.contains_parameter(t))
+            .map(|t| self.point_at(t))
+            .collect()
+    }
+}
+
+fn intersect_circles(a: &Circle, b: &Circle) -> Vec<Vector2D> {
+    let offset = b.center - a.center;
+    let d = offset.length();
+    if d == 0.0
+        || d > a.radius + b.radius + TOLERANCE
+        || d < (a.radius - b.radius).abs() - TOLERANCE
+    {
+        return vec![];
+    }
+
+    let along = (a.radius * a.radius - b.radius * b.radius + d * d) / (2.0 * d);
+    let h = (a.radius * a.radius - along * along).max(0.0).sqrt();
+    let base = a.center + offset * (along / d);
+    if h <= TOLERANCE {
+        return vec![base];
+    }
+    let across = offset.perpendicular() * (h / d);
+    vec![base + across, base - across]
+}
+
+impl Element {
+    pub fn closest_point(&self, p: Vector2D) -> Vector2D {
+        match self {
+            Element::Edge(span) | Element::Linear(span) => span.closest_point(p),
+            Element::Arc(circle) => {
+                let direction = (p - circle.center).normalize().unwrap_or(Vector2D::UNIT_X);
+                circle.center + direction * circle.radius
+            }
+        }
+    }
+
+    /// Unit normal at a point on the element: outward for edges and arcs,
+    /// to the left of the direction for plain linear elements.
+    pub fn normal_at(&self, p: Vector2D) -> Vector2D {
+        match self {
+            Element::Edge(span) => -span
+                .direction
+                .perpendicular()
+                .normalize()
+                .unwrap_or_default(),
+            Element::Linear(span) => span
+                .direction
+                .perpendicular()
+                .normalize()
+                .unwrap_or_default(),
+            Element::Arc(circle) => (p - circle.center).normalize().unwrap_or(Vector2D::UNIT_X),
+        }
+    }
+
+    pub fn intersections(&self, other: &Element) -> Vec<Vector2D> {
+        match (self, other) {
+            (Element::Edge(a) | Element::Linear(a), Element::Edge(b) | Element::Linear(b)) => {
+                a.intersect_span(b)
+            }
+            (Element::Edge(span) | Element::Linear(span), Element::Arc(circle))
+            | (Element::Arc(circle), Element::Edge(span) | Element::Linear(span)) => {
+                span.intersect_circle(circle)
+            }
+            (Element::Arc(a), Element::Arc(b)) => intersect_circles(a, b),
+        }
+    }
+
+    /// Closest pair of points (on self, on other) between the two elements.
+    pub fn closest_points(&self, other: &Element) -> (Vector2D, Vector2D) {
+        if let Some(&p) = self.intersections(other).first() {
+            return (p, p);
+        }
+
+        match (self, other) {
+            (Element::Edge(a) | Element::Linear(a), Element::Edge(b) | Element::Linear(b)) => {
+                let mut candidates: Vec<(Vector2D, Vector2D)> = Vec::new();
+                candidates.extend(
+                    a.finite_end_points()
+                        .into_iter()
+                        .map(|p| (p, b.closest_point(p))),
+                );
+                candidates.extend(
+                    b.finite_end_points()
+                        .into_iter()
+                        .map(|q| (a.closest_point(q), q)),
+                );
+                if candidates.is_empty() {
+                    // parallel infinite lines
+                    candidates.push((a.origin, b.closest_point(a.origin)));
+                }
+                closest_pair(candidates)
+            }
+            (Element::Edge(span) | Element::Linear(span), Element::Arc(circle)) => {
+                span_circle_closest(span, circle)
+            }
+            (Element::Arc(circle), Element::Edge(span) | Element::Linear(span)) => {
+                let (on_span, on_circle) = span_circle_closest(span, circle);
+                (on_circle, on_span)
+            }
+            (Element::Arc(a), Element::Arc(b)) => {
+                let offset = b.center - a.center;
+                let u = offset.normalize().unwrap_or(Vector2D::UNIT_X);
+                let d = offset.length();
+                if d >= a.radius + b.radius {
+                    (a.center + u * a.radius, b.center - u * b.radius)
+                } else if a.radius >= b.radius {
+                    (a.center + u * a.radius, b.center + u * b.radius)
+                } else {
+                    (a.center - u * a.radius, b.center - u * b.radius)
+                }
+            }
+        }
+    }
+}
+
+fn span_circle_closest(span: &LinearSpan, circle: &Circle) -> (Vector2D, Vector2D) {
+    let on_circle = |p: Vector2D| {
+        circle.center + (p - circle.center).normalize().unwrap_or(Vector2D::UNIT_X) * circle.radius
+    };
+
+    let q = span.closest_point(circle.center);
+    if q.distance(&circle.center) > circle.radius {
+        return (q, on_circle(q));
+    }
+
+    // the span lies completely inside the circle: its farthest end point is closest
+    closest_pair(
+        span.finite_end_points()
+            .into_iter()
+            .map(|p| (p, on_circle(p)))
+            .collect(),
+    )
+}
+
+fn closest_pair(candidates: Vec<(Vector2D, Vector2D)>) -> (Vector2D, Vector2D) {
+    candidates
+        .into_iter()
+        .min_by(|(a, b), (c, d)| a.distance_squared(b).total_cmp(&c.distance_squared(d)))
+        .expect("at least one candidate")
+}
+
+#[cfg(test)]
+mod tests_shapes {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    const EPS: f32 = 1e-5;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    fn unit_square() -> Polygon {
+        Polygon::new(vec![v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0)])
+    }
+
+    // U-shaped concave polygon with a notch from (1,1) to (2,3)
+    fn u_shape() -> Polygon {
+        Polygon::new(vec![
+            v(0.0, 0.0),
+            v(3.0, 0.0),
+            v(3.0, 3.0),
+            v(2.0, 3.0),
+            v(2.0, 1.0),
+            v(1.0, 1.0),
+            v(1.0, 3.0),
+            v(0.0, 3.0),
+        ])
+    }
+
+    #[test]
+    fn constructors_and_helpers() {
+        let s = Segment::new(v(0.0, 0.0), v(4.0, 3.0));
+        assert_eq!(s.length(), 5.0);
+        assert_eq!(s.midpoint(), v(2.0, 1.5));
+
+        let aabb = Aabb::new(v(2.0, -1.0), v(0.0, 1.0));
+        assert_eq!(aabb.min, v(0.0, -1.0));
+        assert_eq!(aabb.max, v(2.0, 1.0));
+        assert_eq!(aabb.area(), 4.0);
+        assert_eq!(aabb.center(), v(1.0, 0.0));
+
+        assert_eq!(
+            Aabb::from_points([v(1.0, 5.0), v(-2.0, 0.0), v(3.0, 1.0)]),
+            Some(Aabb::new(v(-2.0, 0.0), v(3.0, 5.0)))
+        );
+        assert_eq!(Aabb::from_points(Vec::new()), None);
+
+        assert_eq!(unit_square().signed_area(), 1.0);
+        assert!(unit_square().is_convex());
+        assert!(!u_shape().is_convex());
+        assert_eq!(u_shape().aabb(), Aabb::new(v(0.0, 0.0), v(3.0, 3.0)));
+        assert_approx_eq!(
+            Line::through(v(0.0, 0.0), v(1.0, 0.0)).signed_distance(v(5.0, 2.0)),
+            2.0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "at least 3 vertices")]
+    fn polygon_needs_three_vertices() {
+        Polygon::new(vec![v(0.0, 0.0), v(1.0, 0.0)]);
+    }
+
+    #[test]
+    fn point_containment() {
+        let circle = Circle::new(v(0.0, 0.0), 1.0);
+        assert!(circle.contains_point(v(0.5, 0.5)));
+        assert!(circle.contains_point(v(1.0, 0.0)));
+        assert!(!circle.contains_point(v(1.0, 0.1)));
+
+        let aabb = Aabb::new(v(0.0, 0.0), v(2.0, 1.0));
+        assert!(aabb.contains_point(v(2.0, 0.5)));
+        assert!(!aabb.contains_point(v(2.1, 0.5)));
+
+        let u = u_shape();
+        assert!(u.contains_point(v(0.5, 2.0)));
+        assert!(u.contains_point(v(1.5, 1.0)));
+        assert!(!u.contains_point(v(1.5, 2.0)));
+        assert!(!u.contains_point(v(-0.5, 0.5)));
+
+        let segment = Segment::new(v(0.0, 0.0), v(2.0, 2.0));
+        assert!(segment.contains_point(v(1.0, 1.0)));
+        assert!(!segment.contains_point(v(3.0, 3.0)));
+        assert!(Ray::new(v(0.0, 0.0), v(1.0, 1.0)).contains_point(v(3.0, 3.0)));
+        assert!(!Ray::new(v(0.0, 0.0), v(1.0, 1.0)).contains_point(v(-1.0, -1.0)));
+        assert!(Line::new(v(0.0, 0.0), v(1.0, 1.0)).contains_point(v(-1.0, -1.0)));
+    }
+
+    #[test]
+    fn closest_point_to_point() {
+        assert_eq!(
+            Segment::new(v(0.0, 0.0), v(2.0, 0.0)).closest_point(v(1.0, 3.0)),
+            v(1.0, 0.0)
+        );
+        assert_eq!(
+            Segment::new(v(0.0, 0.0), v(2.0, 0.0)).closest_point(v(5.0, 1.0)),
+            v(2.0, 0.0)
+        );
+        assert_eq!(
+            Ray::new(v(0.0, 0.0), v(1.0, 0.0)).closest_point(v(-3.0, 1.0)),
+            v(0.0, 0.0)
+        );
+        assert_eq!(
+            Line::new(v(0.0, 0.0), v(1.0, 0.0)).closest_point(v(-3.0, 1.0)),
+            v(-3.0, 0.0)
+        );
+        assert_approx_eq!(
+            Circle::new(v(0.0, 0.0), 2.0).closest_point(v(3.0, 4.0)),
+            v(1.2, 1.6)
+        );
+        assert_eq!(
+            Circle::new(v(0.0, 0.0), 2.0).closest_point(v(0.5, 0.5)),
+            v(0.5, 0.5)
+        );
+        assert_eq!(
+            Aabb::new(v(0.0, 0.0), v(1.0, 1.0)).closest_point(v(3.0, 0.5)),
+            v(1.0, 0.5)
+        );
+        assert_eq!(u_shape().closest_point(v(1.25, 2.0)), v(1.0, 2.0));
+        assert_eq!(u_shape().distance_to_point(v(1.5, 2.5)), 0.5);
+    }
+
+    #[test]
+    fn segment_hits_segment() {
+        let a = Segment::new(v(0.0, 0.0), v(2.0, 2.0));
+        let b = Segment::new(v(0.0, 2.0), v(2.0, 0.0));
+
+        let hits = a.hits(&b);
+        assert_eq!(hits.len(), 1);
+        assert_approx_eq!(hits[0].point, v(1.0, 1.0));
+        assert_approx_eq!(hits[0].t, 0.5);
+        assert_approx_eq!(hits[0].normal, v(-1.0, -1.0).normalize().unwrap());
+
+        let c = Segment::new(v(3.0, 0.0), v(3.0, 5.0));
+        assert!(a.hits(&c).is_empty());
+        assert!(!a.intersects(&c));
+    }
+
+    #[test]
+    fn collinear_overlapping_segments() {
+        let a = Segment::new(v(0.0, 0.0), v(4.0, 0.0));
+        let b = Segment::new(v(3.0, 0.0), v(6.0, 0.0));
+        let points = a.intersection_points(&b);
+        assert_eq!(points, vec![v(3.0, 0.0), v(4.0, 0.0)]);
+
+        let parallel = Segment::new(v(0.0, 1.0), v(4.0, 1.0));
+        assert!(a.intersection_points(&parallel).is_empty());
+        assert_eq!(a.closest_points(&parallel).distance, 1.0);
+    }
+
+    #[test]
+    fn zero_length_segments_are_points() {
+        let segment = Segment::new(v(0.0, 0.0), v(4.0, 0.0));
+        let far = Segment::new(v(100.0, 0.0), v(100.0, 0.0));
+        assert!(far.intersection_points(&segment).is_empty());
+        assert!(segment.intersection_points(&far).is_empty());
+        assert!(!far.intersects(&segment));
+
+        let on = Segment::new(v(1.0, 0.0), v(1.0, 0.0));
+        assert_eq!(on.intersection_points(&segment), vec![v(1.0, 0.0)]);
+        assert_eq!(segment.intersection_points(&on), vec![v(1.0, 0.0)]);
+        assert_eq!(on.intersection_points(&on), vec![v(1.0, 0.0)]);
+        let line = Line::new(v(-5.0, 5.0), v(1.0, -1.0));
+        assert_eq!(far.intersection_points(&line), vec![]);
+        assert_eq!(
+            Segment::new(v(0.0, 0.0), v(0.0, 0.0)).intersection_points(&line),
+            vec![v(0.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn shapes_without_boundary_are_points() {
+        let dot = Polygon::new(vec![v(5.0, 0.0); 3]);
+        assert!(dot.boundary().is_empty());
+        let circle = Circle::new(v(0.0, 0.0), 1.0);
+
+        let closest = dot.closest_points(&circle);
+        assert_eq!(
+            (closest.on_self, closest.on_other),
+            (v(5.0, 0.0), v(1.0, 0.0))
+        );
+        assert_approx_eq!(closest.distance, 4.0);
+        let closest = circle.closest_points(&dot);
+        assert_eq!(
+            (closest.on_self, closest.on_other),
+            (v(1.0, 0.0), v(5.0, 0.0))
+        );
+        assert_eq!(dot.closest_points(&dot).distance, 0.0);
+    }
+
+    #[test]
+    fn collinear_spans_with_long_directions_stay_finite() {
+        let line = Line::new(v(0.0, 0.0), v(1e15, 0.0));
+        let ray = Ray::new(v(2.0, 0.0), v(-1e15, 0.0));
+        let points = line.intersection_points(&ray);
+        assert!(!points.is_empty());
+        assert!(points.iter().all(|p| p.x.is_finite() && p.y == 0.0));
+
+        let segment = Segment::new(v(1.0, 0.0), v(3.0, 0.0));
+        assert_eq!(
+            segment.intersection_points(&ray),
+            vec![v(1.0, 0.0), v(2.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn ray_hits_circle() {
+        let ray = Ray::new(v(-5.0, 0.0), v(1.0, 0.0));
+        let circle = Circle::new(v(0.0, 0.0), 2.0);
+
+        let hits = ray.hits(&circle);
+        assert_eq!(hits.len(), 2);
+        assert_approx_eq!(hits[0].point, v(-2.0, 0.0), epsilon = EPS);
+        assert_approx_eq!(hits[0].normal, v(-1.0, 0.0), epsilon = EPS);
+        assert_approx_eq!(hits[0].t, 3.0, epsilon = EPS);
+        assert_approx_eq!(hits[1].point, v(2.0, 0.0), epsilon = EPS);
+        assert_approx_eq!(hits[1].normal, v(1.0, 0.0), epsilon = EPS);
+
+        let tangent = Ray::new(v(-5.0, 2.0), v(1.0, 0.0));
+        assert_eq!(tangent.hits(&circle).len(), 1);
+
+        let away = Ray::new(v(-5.0, 0.0), v(-1.0, 0.0));
+        assert!(away.hits(&circle).is_empty());
+        assert!(!away.intersects(&circle));
+
+        let from_inside = Ray::new(v(0.0, 0.0), v(0.0, 1.0));
+        let hit = from_inside.first_hit(&circle).unwrap();
+        assert_approx_eq!(hit.point, v(0.0, 2.0), epsilon = EPS);
+        assert!(from_inside.intersects(&circle));
+    }
+
+    #[test]
+    fn ray_hits_aabb_with_outward_normals() {
+        let aabb = Aabb::new(v(1.0, -1.0), v(3.0, 1.0));
+        let ray = Ray::new(v(0.0, 0.0), v(1.0, 0.0));
+
+        let hits = ray.hits(&aabb);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].point, v(1.0, 0.0));
+        assert_eq!(hits[0].normal, v(-1.0, 0.0));
+        assert_eq!(hits[0].t, 1.0);
+        assert_eq!(hits[1].point, v(3.0, 0.0));
+        assert_eq!(hits[1].normal, v(1.0, 0.0));
+
+        // through a corner: reported once
+        let diagonal = Ray::new(v(0.0, -2.0), v(1.0, 1.0));
+        assert_eq!(diagonal.hits(&aabb).len(), 2);
+    }
+
+    #[test]
+    fn line_hits_concave_polygon() {
+        let line = Line::new(v(0.0, 2.0), v(1.0, 0.0));
+        let hits = line.hits(&u_shape());
+
+        let xs: Vec<f32> = hits.iter().map(|h| h.point.x).collect();
+        assert_eq!(xs, vec![0.0, 1.0, 2.0, 3.0]);
+        let normals: Vec<Vector2D> = hits.iter().map(|h| h.normal).collect();
+        assert_eq!(
+            normals,
+            vec![v(-1.0, 0.0), v(1.0, 0.0), v(-1.0, 0.0), v(1.0, 0.0)]
+        );
+        assert_eq!(hits[0].t, 0.0);
+    }
+
+    #[test]
+    fn linear_hits_linear_normal_faces_query() {
+        let segment = Segment::new(v(0.0, -1.0), v(0.0, 1.0));
+        let ray = Ray::new(v(-1.0, 0.0), v(1.0, 0.0));
+        assert_eq!(ray.first_hit(&segment).unwrap().normal, v(-1.0, 0.0));
+
+        let back = Ray::new(v(1.0, 0.0), v(-1.0, 0.0));
+        assert_eq!(back.first_hit(&segment).unwrap().normal, v(1.0, 0.0));
+
+        let line = Line::new(v(5.0, 5.0), v(1.0, 1.0));
+        let other = Line::new(v(0.0, 2.0), v(1.0, 0.0));
+        assert_eq!(line.first_hit(&other).unwrap().point, v(2.0, 2.0));
+        assert!(
+            Line::new(v(0.0, 0.0), v(1.0, 0.0)).intersects(&Line::new(v(3.0, 0.0), v(-2.0, 0.0)))
+        );
+    }
+
+    #[test]
+    fn solid_shape_intersections() {
+        let a = Circle::new(v(0.0, 0.0), 1.0);
+        let b = Circle::new(v(1.5, 0.0), 1.0);
+        let far = Circle::new(v(5.0, 0.0), 1.0);
+        let inner = Circle::new(v(0.1, 0.0), 0.2);
+
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection_points(&b).len(), 2);
+        assert!(!a.intersects(&far));
+        assert!(a.intersects(&inner));
+        assert!(a.intersection_points(&inner).is_empty());
+
+        let aabb = Aabb::new(v(0.9, -0.1), v(2.0, 0.1));
+        assert!(a.intersects(&aabb));
+        assert!(aabb.intersects(&unit_square()));
+        assert!(!Aabb::new(v(5.0, 5.0), v(6.0, 6.0)).intersects(&unit_square()));
+
+        let touching = Aabb::new(v(1.0, 0.0), v(2.0, 1.0));
+        assert!(touching.intersects(&unit_square()));
+
+        // a circle sitting in the notch of the U does not touch it
+        let in_notch = Circle::new(v(1.5, 2.0), 0.4);
+        assert!(!u_shape().intersects(&in_notch));
+        assert!(u_shape().intersects(&Circle::new(v(1.5, 2.0), 0.6)));
+    }
+
+    #[test]
+    fn segment_inside_solid_intersects_without_boundary_crossing() {
+        let segment = Segment::new(v(0.2, 0.2), v(0.8, 0.8));
+        assert!(segment.intersects(&unit_square()));
+        assert!(unit_square().intersects(&segment));
+        assert!(segment.hits(&unit_square()).is_empty());
+    }
+
+    #[test]
+    fn containment() {
+        let big = Circle::new(v(0.0, 0.0), 5.0);
+        assert!(big.contains(&Circle::new(v(1.0, 1.0), 2.0)));
+        assert!(!big.contains(&Circle::new(v(4.0, 0.0), 2.0)));
+        assert!(big.contains(&Aabb::new(v(-1.0, -1.0), v(1.0, 1.0))));
+        assert!(big.contains(&Segment::new(v(-3.0, 0.0), v(3.0, 0.0))));
+        assert!(!big.contains(&Ray::new(v(0.0, 0.0), v(1.0, 0.0))));
+        assert!(!big.contains(&Line::new(v(0.0, 0.0), v(1.0, 0.0))));
+
+        let aabb = Aabb::new(v(0.0, 0.0), v(10.0, 10.0));
+        assert!(aabb.contains(&Circle::new(v(5.0, 5.0), 5.0)));
+        assert!(!aabb.contains(&Circle::new(v(5.0, 5.0), 5.5)));
+        assert!(aabb.contains(&unit_square()));
+        assert!(aabb.contains(&Aabb::new(v(0.0, 0.0), v(10.0, 10.0))));
+
+        assert!(unit_square().contains(&Segment::new(v(0.0, 0.0), v(1.0, 1.0))));
+        assert!(!Circle::new(v(0.0, 0.0), 0.5).contains(&unit_square()));
+    }
+
+    #[test]
+    fn concave_containment() {
+        let u = u_shape();
+        // both end points inside, but the segment passes through the notch
+        let across = Segment::new(v(0.5, 2.0), v(2.5, 2.0));
+        assert!(u.contains_point(across.start) && u.contains_point(across.end));
+        assert!(!u.contains(&across));
+
+        let below_notch = Segment::new(v(0.5, 0.5), v(2.5, 0.5));
+        assert!(u.contains(&below_notch));
+
+        assert!(u.contains(&Circle::new(v(0.5, 2.0), 0.5)));
+        assert!(!u.contains(&Circle::new(v(0.5, 2.0), 0.6)));
+        assert!(!u.contains(&Aabb::new(v(0.5, 0.5), v(2.5, 2.5))));
+        assert!(u.contains(&Aabb::new(v(0.0, 0.0), v(3.0, 1.0))));
+    }
+
+    #[test]
+    fn linear_containment() {
+        let s = Segment::new(v(0.0, 0.0), v(4.0, 0.0));
+        assert!(s.contains(&Segment::new(v(1.0, 0.0), v(3.0, 0.0))));
+        assert!(!s.contains(&Segment::new(v(1.0, 0.0), v(5.0, 0.0))));
+        assert!(Line::new(v(0.0, 0.0), v(1.0, 0.0)).contains(&s));
+        assert!(!s.contains(&Circle::new(v(1.0, 0.0), 0.5)));
+    }
+
+    #[test]
+    fn closest_points_between_shapes() {
+        let circle = Circle::new(v(0.0, 0.0), 1.0);
+        let other = Circle::new(v(4.0, 0.0), 1.0);
+        let closest = circle.closest_points(&other);
+        assert_approx_eq!(closest.on_self, v(1.0, 0.0));
+        assert_approx_eq!(closest.on_other, v(3.0, 0.0));
+        assert_approx_eq!(closest.distance, 2.0);
+
+        let aabb = Aabb::new(v(3.0, 3.0), v(4.0, 4.0));
+        let closest = unit_square().closest_points(&aabb);
+        assert_eq!(closest.on_self, v(1.0, 1.0));
+        assert_eq!(closest.on_other, v(3.0, 3.0));
+
+        let segment = Segment::new(v(-2.0, 3.0), v(2.0, 3.0));
+        let closest = circle.closest_points(&segment);
+        assert_approx_eq!(closest.on_self, v(0.0, 1.0));
+        assert_approx_eq!(closest.on_other, v(0.0, 3.0));
+        assert_approx_eq!(closest.distance, 2.0);
+
+        let ray = Ray::new(v(5.0, 0.0), v(1.0, 0.0));
+        let closest = ray.closest_points(&unit_square());
+        assert_eq!(closest.on_self, v(5.0, 0.0));
+        assert_eq!(closest.on_other, v(1.0, 0.0));
+
+        let line = Line::new(v(0.0, 5.0), v(1.0, 0.0));
+        assert_approx_eq!(line.closest_points(&circle).distance, 4.0);
+        assert_approx_eq!(
+            line.closest_points(&Line::new(v(0.0, -5.0), v(-2.0, 0.0)))
+                .distance,
+            10.0
+        );
+
+        assert_eq!(circle.closest_points(&unit_square()).distance, 0.0);
+    }
+
+    #[test]
+    fn closest_points_with_segment_inside_circle_boundary() {
+        // the segment is inside the solid circle, so the shapes overlap
+        let circle = Circle::new(v(0.0, 0.0), 5.0);
+        let segment = Segment::new(v(1.0, 0.0), v(2.0, 0.0));
+        assert_eq!(circle.closest_points(&segment).distance, 0.0);
+
+        // between boundary elements the farthest end point is closest to the arc
+        let (on_span, on_arc) =
+            Element::Linear(segment.span()).closest_points(&Element::Arc(circle));
+        assert_eq!(on_span, v(2.0, 0.0));
+        assert_eq!(on_arc, v(5.0, 0.0));
+    }
+
+    #[test]
+    fn nested_circle_boundaries() {
+        let outer = Element::Arc(Circle::new(v(0.0, 0.0), 5.0));
+        let inner = Element::Arc(Circle::new(v(1.0, 0.0), 1.0));
+        assert_eq!(outer.closest_points(&inner), (v(5.0, 0.0), v(2.0, 0.0)));
+        assert_eq!(inner.closest_points(&outer), (v(2.0, 0.0), v(5.0, 0.0)));
+    }
+
+    #[test]
+    fn winding_order_does_not_matter() {
+        let cw = Polygon::new(unit_square().vertices().iter().rev().copied().collect());
+        assert!(!cw.is_ccw());
+
+        let ray = Ray::new(v(-1.0, 0.5), v(1.0, 0.0));
+        assert_eq!(ray.hits(&cw), ray.hits(&unit_square()));
+        assert_eq!(ray.first_hit(&cw).unwrap().normal, v(-1.0, 0.0));
+    }
+}
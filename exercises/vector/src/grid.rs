@@ -0,0 +1,637 @@
+use crate::vector2d::Vector2D;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+// Integer vectors and algorithms on rectangular grids of cells.
+//
+// `IVec2` is `Vector2D<i32>`, so it has every operator the float vectors
+// have; this module adds what only makes sense on a lattice. Grid cells are
+// addressed by `IVec2` with x to the right and y down, row-major in memory.
+// Searches visit neighbours in a fixed order and break ties by cell index,
+// so paths and fill orders are reproducible.
+
+pub type IVec2 = Vector2D<i32>;
+
+/// How to turn float coordinates into integers.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    /// To the nearest integer, halves away from zero.
+    Round,
+    /// Towards zero.
+    Trunc,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Neighbourhood {
+    /// Orthogonal neighbours (von Neumann).
+    Four,
+    /// Orthogonal and diagonal neighbours (Moore).
+    Eight,
+}
+
+impl IVec2 {
+    /// Orthogonal directions, clockwise from +x with y pointing down.
+    pub const DIRECTIONS_4: [IVec2; 4] = [
+        IVec2 { x: 1, y: 0 },
+        IVec2 { x: 0, y: 1 },
+        IVec2 { x: -1, y: 0 },
+        IVec2 { x: 0, y: -1 },
+    ];
+
+    /// All eight directions, clockwise from +x with y pointing down.
+    pub const DIRECTIONS_8: [IVec2; 8] = [
+        IVec2 { x: 1, y: 0 },
+        IVec2 { x: 1, y: 1 },
+        IVec2 { x: 0, y: 1 },
+        IVec2 { x: -1, y: 1 },
+        IVec2 { x: -1, y: 0 },
+        IVec2 { x: -1, y: -1 },
+        IVec2 { x: 0, y: -1 },
+        IVec2 { x: 1, y: -1 },
+    ];
+
+    /// Converts with the given rounding, saturating at the `i32` range.
+    pub fn from_vector(v: Vector2D<f32>, rounding: Rounding) -> Self {
+        let round = |c: f32| match rounding {
+            Rounding::Floor => c.floor(),
+            Rounding::Ceil => c.ceil(),
+            Rounding::Round => c.round(),
+            Rounding::Trunc => c.trunc(),
+        } as i32;
+        IVec2::new(round(v.x), round(v.y))
+    }
+
+    pub fn to_vector(self) -> Vector2D<f32> {
+        Vector2D::new(self.x as f32, self.y as f32)
+    }
+
+    /// Taxicab distance, the number of orthogonal steps between the cells.
+    pub fn manhattan(&self, other: &Self) -> u64 {
+        self.x.abs_diff(other.x) as u64 + self.y.abs_diff(other.y) as u64
+    }
+
+    /// Chessboard distance, the number of king moves between the cells.
+    pub fn chebyshev(&self, other: &Self) -> u32 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    pub fn neighbours(self, neighbourhood: Neighbourhood) -> impl Iterator<Item = IVec2> {
+        let directions: &'static [IVec2] = match neighbourhood {
+            Neighbourhood::Four => &IVec2::DIRECTIONS_4,
+            Neighbourhood::Eight => &IVec2::DIRECTIONS_8,
+        };
+        directions.iter().map(move |d| self + *d)
+    }
+}
+
+/// Cells of the Bresenham line from `from` to `to`, both included. The
+/// cells are produced as they are walked, so a caller that stops early pays
+/// only for the cells it took.
+pub fn line(from: IVec2, to: IVec2) -> impl Iterator<Item = IVec2> {
+    // the differences, and twice the error, need more than 32 bits when the
+    // end points are far apart
+    let dx = (to.x as i64 - from.x as i64).abs();
+    let dy = -(to.y as i64 - from.y as i64).abs();
+    let step = IVec2::new(to.x.cmp(&from.x) as i32, to.y.cmp(&from.y) as i32);
+    let mut error = dx + dy;
+    let mut next = Some(from);
+    std::iter::from_fn(move || {
+        let p = next?;
+        next = (p != to).then(|| {
+            let mut q = p;
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                q.x += step.x;
+            }
+            if doubled <= dx {
+                error += dx;
+                q.y += step.y;
+            }
+            q
+        });
+        Some(p)
+    })
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Path {
+    /// From start to goal, both included.
+    pub cells: Vec<IVec2>,
+    pub cost: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grid<T> {
+    width: i32,
+    height: i32,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: i32, height: i32, mut f: impl FnMut(IVec2) -> T) -> Self {
+        assert!(width >= 0 && height >= 0, "grid size must not be negative");
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| IVec2::new(x, y)))
+            .map(&mut f)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn contains(&self, p: IVec2) -> bool {
+        (0..self.width).contains(&p.x) && (0..self.height).contains(&p.y)
+    }
+
+    fn index(&self, p: IVec2) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y as usize * self.width as usize + p.x as usize)
+    }
+
+    fn position(&self, index: usize) -> IVec2 {
+        IVec2::new(
+            (index % self.width as usize) as i32,
+            (index / self.width as usize) as i32,
+        )
+    }
+
+    pub fn get(&self, p: IVec2) -> Option<&T> {
+        self.index(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: IVec2) -> Option<&mut T> {
+        self.index(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Positions of all cells, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = IVec2> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| IVec2::new(x, y)))
+    }
+
+    /// Cells reachable from `start` through orthogonal steps between cells
+    /// for which `passable` holds, in breadth-first order. Empty when
+    /// `start` itself is outside or not passable.
+    pub fn reachable(&self, start: IVec2, passable: impl Fn(&T) -> bool) -> Vec<IVec2> {
+        let Some(first) = self.index(start).filter(|&i| passable(&self.cells[i])) else {
+            return Vec::new();
+        };
+        let mut seen = vec![false; self.cells.len()];
+        seen[first] = true;
+        let mut queue = VecDeque::from([start]);
+        let mut order = Vec::new();
+        while let Some(p) = queue.pop_front() {
+            order.push(p);
+            for q in p.neighbours(Neighbourhood::Four) {
+                if let Some(i) = self.index(q)
+                    && !seen[i]
+                    && passable(&self.cells[i])
+                {
+                    seen[i] = true;
+                    queue.push_back(q);
+                }
+            }
+        }
+        order
+    }
+
+    /// Whether no cell strictly between `from` and `to` on their Bresenham
+    /// line is `opaque` or outside the grid. The line is always walked from
+    /// the smaller end point, so the answer does not depend on the order,
+    /// and the walk stops at the first cell that blocks it.
+    pub fn line_of_sight(&self, from: IVec2, to: IVec2, opaque: impl Fn(&T) -> bool) -> bool {
+        let (from, to) = if (from.y, from.x) <= (to.y, to.x) {
+            (from, to)
+        } else {
+            (to, from)
+        };
+        line(from, to)
+            .skip(1)
+            .take_while(|&p| p != to)
+            .all(|p| self.get(p).is_some_and(|cell| !opaque(cell)))
+    }
+
+    /// A* search for the cheapest path. Entering a cell costs `cost(cell)`,
+    /// at least 1, or is impossible for `None`. Diagonal moves cost the same
+    /// as orthogonal ones and may not cut the corner of an impassable cell.
+    /// Paths costing `u32::MAX` or more count as impossible.
+    pub fn a_star(
+        &self,
+        start: IVec2,
+        goal: IVec2,
+        neighbourhood: Neighbourhood,
+        cost: impl Fn(&T) -> Option<u32>,
+    ) -> Option<Path> {
+        let (start_index, goal_index) = (self.index(start)?, self.index(goal)?);
+        cost(&self.cells[goal_index])?;
+        let enter = |p: IVec2| {
+            self.index(p)
+                .and_then(|i| cost(&self.cells[i]))
+                .map(|c| c.max(1))
+        };
+        // with every step costing at least 1 this never overestimates
+        let heuristic = |p: IVec2| match neighbourhood {
+            Neighbourhood::Four => u32::try_from(p.manhattan(&goal)).unwrap_or(u32::MAX),
+            Neighbourhood::Eight => p.chebyshev(&goal),
+        };
+
+        let mut best = vec![u32::MAX; self.cells.len()];
+        let mut came_from = vec![usize::MAX; self.cells.len()];
+        let mut open = BinaryHeap::new();
+        best[start_index] = 0;
+        open.push(Reverse((heuristic(start), 0, start_index)));
+
+        while let Some(Reverse((_, so_far, index))) = open.pop() {
+            if so_far > best[index] {
+                continue;
+            }
+            if index == goal_index {
+                let mut cells = vec![goal];
+                let mut at = index;
+                while at != start_index {
+                    at = came_from[at];
+                    cells.push(self.position(at));
+                }
+                cells.reverse();
+                return Some(Path {
+                    cells,
+                    cost: so_far,
+                });
+            }
+
+            let p = self.position(index);
+            for q in p.neighbours(neighbourhood) {
+                let Some(step) = enter(q) else {
+                    continue;
+                };
+                let d = q - p;
+                if d.x != 0
+                    && d.y != 0
+                    && (enter(IVec2::new(q.x, p.y)).is_none()
+                        || enter(IVec2::new(p.x, q.y)).is_none())
+                {
+                    continue;
+                }
+                let next = self.index(q).unwrap();
+                let Some(total) = so_far.checked_add(step) else {
+                    continue;
+                };
+                if total < best[next] {
+                    best[next] = total;
+                    came_from[next] = index;
+                    // only the order matters, so an estimate past the
+                    // range can stay at the top of it
+                    open.push(Reverse((total.saturating_add(heuristic(q)), total, next)));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: i32, height: i32, value: T) -> Self {
+        Grid::from_fn(width, height, |_| value.clone())
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// Paint-bucket fill: sets `value` on every cell connected to `start`
+    /// through orthogonal steps over cells equal to the one at `start`.
+    /// Returns the number of cells changed.
+    pub fn flood_fill(&mut self, start: IVec2, value: T) -> usize {
+        let Some(original) = self.get(start).cloned() else {
+            return 0;
+        };
+        if original == value {
+            return 0;
+        }
+        let region = self.reachable(start, |cell| *cell == original);
+        for p in &region {
+            self[*p] = value.clone();
+        }
+        region.len()
+    }
+}
+
+impl<T> std::ops::Index<IVec2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: IVec2) -> &Self::Output {
+        match self.get(p) {
+            Some(cell) => cell,
+            None => panic!("cell {:?} outside a {}x{} grid", p, self.width, self.height),
+        }
+    }
+}
+
+impl<T> std::ops::IndexMut<IVec2> for Grid<T> {
+    fn index_mut(&mut self, p: IVec2) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(p) {
+            Some(cell) => cell,
+            None => panic!("cell {:?} outside a {}x{} grid", p, width, height),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_grid {
+    use super::*;
+    use crate::testing::random_points;
+
+    fn iv(x: i32, y: i32) -> IVec2 {
+        IVec2::new(x, y)
+    }
+
+    // One row per line, '#' for walls
+    fn parse(map: &str) -> Grid<char> {
+        let rows: Vec<Vec<char>> = map
+            .lines()
+            .map(|line| line.trim().chars().collect())
+            .collect();
+        Grid::from_fn(rows[0].len() as i32, rows.len() as i32, |p| {
+            rows[p.y as usize][p.x as usize]
+        })
+    }
+
+    fn walls(cell: &char) -> Option<u32> {
+        (*cell != '#').then_some(1)
+    }
+
+    // Breadth-first distances for checking A* with unit costs
+    fn bfs_distance(grid: &Grid<char>, start: IVec2, goal: IVec2) -> Option<u32> {
+        let mut distance = Grid::new(grid.width(), grid.height(), u32::MAX);
+        distance[start] = 0;
+        let mut queue = VecDeque::from([start]);
+        while let Some(p) = queue.pop_front() {
+            for q in p.neighbours(Neighbourhood::Four) {
+                if grid.get(q).is_some_and(|c| *c != '#') && distance[q] == u32::MAX {
+                    distance[q] = distance[p] + 1;
+                    queue.push_back(q);
+                }
+            }
+        }
+        (distance[goal] != u32::MAX).then_some(distance[goal])
+    }
+
+    #[test]
+    fn integer_vectors() {
+        let (a, b) = (iv(1, -2), iv(-3, 4));
+        assert_eq!(a + b, iv(-2, 2));
+        assert_eq!(a - b, iv(4, -6));
+        assert_eq!(a * 3, iv(3, -6));
+        assert_eq!(3 * a, iv(3, -6));
+        assert_eq!(-a, iv(-1, 2));
+        assert_eq!(a.dot(&b), -11);
+        assert_eq!(a.perp_dot(&b), -2);
+        assert_eq!(a.perpendicular(), iv(2, 1));
+        assert_eq!(a.length_squared(), 5);
+
+        assert_eq!(a.manhattan(&b), 10);
+        assert_eq!(a.chebyshev(&b), 6);
+        assert_eq!(a.manhattan(&a), 0);
+        let (min, max) = (iv(i32::MIN, i32::MIN), iv(i32::MAX, i32::MAX));
+        assert_eq!(min.manhattan(&max), 2 * u32::MAX as u64);
+        assert_eq!(max.chebyshev(&min), u32::MAX);
+
+        assert_eq!(
+            iv(0, 0).neighbours(Neighbourhood::Four).collect::<Vec<_>>(),
+            [iv(1, 0), iv(0, 1), iv(-1, 0), iv(0, -1)]
+        );
+        let around: Vec<_> = iv(5, 5).neighbours(Neighbourhood::Eight).collect();
+        assert_eq!(around.len(), 8);
+        assert!(around.iter().all(|p| p.chebyshev(&iv(5, 5)) == 1));
+    }
+
+    #[test]
+    fn rounding_conversions() {
+        let v = Vector2D::new(-1.5, 2.5);
+        assert_eq!(IVec2::from_vector(v, Rounding::Floor), iv(-2, 2));
+        assert_eq!(IVec2::from_vector(v, Rounding::Ceil), iv(-1, 3));
+        assert_eq!(IVec2::from_vector(v, Rounding::Round), iv(-2, 3));
+        assert_eq!(IVec2::from_vector(v, Rounding::Trunc), iv(-1, 2));
+        assert_eq!(
+            IVec2::from_vector(Vector2D::new(1e20, f32::NEG_INFINITY), Rounding::Round),
+            iv(i32::MAX, i32::MIN)
+        );
+        assert_eq!(iv(3, -4).to_vector(), Vector2D::new(3.0, -4.0));
+    }
+
+    #[test]
+    fn bresenham_cells() {
+        let cells = |from, to| line(from, to).collect::<Vec<_>>();
+        assert_eq!(cells(iv(0, 0), iv(0, 0)), [iv(0, 0)]);
+        assert_eq!(
+            cells(iv(0, 0), iv(4, 2)),
+            [iv(0, 0), iv(1, 1), iv(2, 1), iv(3, 2), iv(4, 2)]
+        );
+        assert_eq!(
+            cells(iv(2, 3), iv(2, 0)),
+            [iv(2, 3), iv(2, 2), iv(2, 1), iv(2, 0)]
+        );
+        for (from, to) in [(iv(-3, 7), iv(5, -2)), (iv(0, 0), iv(-6, -1))] {
+            let cells = cells(from, to);
+            assert_eq!(cells.len() as u32, from.chebyshev(&to) + 1);
+            assert!(cells.windows(2).all(|w| w[0].chebyshev(&w[1]) == 1));
+        }
+
+        // end points across the whole i32 range
+        let (min, max) = (iv(i32::MIN, i32::MIN), iv(i32::MAX, i32::MAX - 2));
+        assert_eq!(
+            line(min, max).take(3).collect::<Vec<_>>(),
+            [min, min + iv(1, 1), min + iv(2, 2)]
+        );
+        assert_eq!(line(max, min).next(), Some(max));
+    }
+
+    #[test]
+    fn grid_access() {
+        let mut grid = Grid::from_fn(3, 2, |p| p.x + 10 * p.y);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[iv(2, 1)], 12);
+        assert_eq!(grid.get(iv(3, 0)), None);
+        assert_eq!(grid.get(iv(0, -1)), None);
+        grid[iv(0, 1)] = 7;
+        *grid.get_mut(iv(1, 1)).unwrap() = 8;
+        assert_eq!(
+            grid.positions().map(|p| grid[p]).collect::<Vec<_>>(),
+            [0, 1, 2, 7, 8, 12]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "outside a 3x2 grid")]
+    fn indexing_outside_panics() {
+        let grid = Grid::new(3, 2, 0);
+        let _ = grid[iv(1, 2)];
+    }
+
+    #[test]
+    fn flood_fill_stays_inside_walls() {
+        let mut grid = parse(
+            "......
+             .###..
+             .#..#.
+             .###..
+             ......",
+        );
+        // the inner room only connects diagonally, which does not count
+        assert_eq!(grid.flood_fill(iv(2, 2), 'x'), 2);
+        assert_eq!(grid[iv(3, 2)], 'x');
+        assert_eq!(grid.flood_fill(iv(0, 0), 'o'), 20);
+        assert_eq!(grid[iv(5, 2)], 'o');
+        assert_eq!(grid[iv(1, 1)], '#');
+        assert_eq!(grid.flood_fill(iv(0, 0), 'o'), 0);
+        assert_eq!(grid.flood_fill(iv(9, 9), 'o'), 0);
+
+        let order = grid.reachable(iv(0, 0), |c| *c == 'o');
+        assert_eq!(order[..3], [iv(0, 0), iv(1, 0), iv(0, 1)]);
+        assert!(grid.reachable(iv(1, 1), |c| *c == 'o').is_empty());
+    }
+
+    #[test]
+    fn line_of_sight_is_blocked_by_walls() {
+        let grid = parse(
+            ".......
+             ...#...
+             .......",
+        );
+        let opaque = |c: &char| *c == '#';
+        assert!(grid.line_of_sight(iv(0, 0), iv(6, 0), opaque));
+        assert!(!grid.line_of_sight(iv(0, 1), iv(6, 1), opaque));
+        assert!(!grid.line_of_sight(iv(1, 0), iv(5, 2), opaque));
+        // walls themselves can be seen
+        assert!(grid.line_of_sight(iv(0, 1), iv(3, 1), opaque));
+        assert!(grid.line_of_sight(iv(2, 2), iv(2, 2), opaque));
+        assert!(!grid.line_of_sight(iv(0, 0), iv(10, 0), |_| false));
+        assert!(!grid.line_of_sight(iv(0, 0), iv(i32::MAX, 0), |_| false));
+        assert!(!grid.line_of_sight(iv(i32::MIN, i32::MIN), iv(i32::MAX, i32::MAX), |_| false));
+
+        let points = random_points(40, 3);
+        for pair in points.chunks(2) {
+            let a = IVec2::from_vector(pair[0] * 0.07, Rounding::Floor);
+            let b = IVec2::from_vector(pair[1] * 0.07, Rounding::Floor);
+            let a = iv(a.x.min(6), a.y.min(2));
+            let b = iv(b.x.min(6), b.y.min(2));
+            assert_eq!(
+                grid.line_of_sight(a, b, opaque),
+                grid.line_of_sight(b, a, opaque)
+            );
+        }
+    }
+
+    #[test]
+    fn a_star_finds_shortest_paths() {
+        let grid = parse(
+            "S.#.....
+             .##.###.
+             ...#...#
+             .#...#.G",
+        );
+        let (start, goal) = (iv(0, 0), iv(7, 3));
+        let path = grid
+            .a_star(start, goal, Neighbourhood::Four, walls)
+            .unwrap();
+        assert_eq!(path.cells.first(), Some(&start));
+        assert_eq!(path.cells.last(), Some(&goal));
+        assert_eq!(Some(path.cost), bfs_distance(&grid, start, goal));
+        assert_eq!(path.cells.len() as u32, path.cost + 1);
+        assert!(path.cells.windows(2).all(|w| w[0].manhattan(&w[1]) == 1));
+        assert!(path.cells.iter().all(|p| grid[*p] != '#'));
+
+        assert_eq!(
+            grid.a_star(start, iv(2, 0), Neighbourhood::Four, walls),
+            None
+        );
+        assert_eq!(
+            grid.a_star(start, iv(8, 0), Neighbourhood::Four, walls),
+            None
+        );
+        let trivial = grid
+            .a_star(start, start, Neighbourhood::Four, walls)
+            .unwrap();
+        assert_eq!((trivial.cells, trivial.cost), (vec![start], 0));
+
+        // diagonal steps, but never squeezing between two walls
+        let diagonal = parse(
+            "..#
+             .#.
+             ...",
+        );
+        let path = diagonal
+            .a_star(iv(0, 0), iv(2, 2), Neighbourhood::Eight, walls)
+            .unwrap();
+        assert_eq!(path.cost, 4);
+        let around = diagonal
+            .a_star(iv(1, 0), iv(2, 1), Neighbourhood::Eight, walls)
+            .unwrap();
+        assert_eq!(around.cost, 6);
+    }
+
+    #[test]
+    fn a_star_prefers_cheap_terrain() {
+        let grid = parse(
+            ".~~~.
+             .~~~.
+             .....",
+        );
+        let cost = |c: &char| Some(if *c == '~' { 5 } else { 1 });
+        let path = grid
+            .a_star(iv(0, 0), iv(4, 0), Neighbourhood::Four, cost)
+            .unwrap();
+        // around the swamp: 2 down, 4 across, 2 up
+        assert_eq!(path.cost, 8);
+        assert!(path.cells.iter().all(|p| grid[*p] == '.'));
+
+        let direct = grid
+            .a_star(iv(0, 0), iv(4, 0), Neighbourhood::Four, |_| Some(0))
+            .unwrap();
+        assert_eq!(direct.cost, 4);
+
+        let expensive = parse("....");
+        let half = |_: &char| Some(u32::MAX / 2);
+        let path = expensive
+            .a_star(iv(0, 0), iv(2, 0), Neighbourhood::Four, half)
+            .unwrap();
+        assert_eq!(path.cost, u32::MAX - 1);
+        assert_eq!(
+            expensive.a_star(iv(0, 0), iv(3, 0), Neighbourhood::Four, half),
+            None
+        );
+    }
+
+    #[test]
+    fn a_star_matches_breadth_first_search_on_random_mazes() {
+        for seed in 1..20 {
+            let noise = random_points(200, seed);
+            let grid = Grid::from_fn(20, 10, |p| {
+                let r = noise[(p.y * 20 + p.x) as usize];
+                if r.x < 30.0 { '#' } else { '.' }
+            });
+            let (start, goal) = (iv(0, 0), iv(19, 9));
+            if grid[start] == '#' || grid[goal] == '#' {
+                continue;
+            }
+            let path = grid.a_star(start, goal, Neighbourhood::Four, walls);
+            assert_eq!(
+                path.map(|p| p.cost),
+                bfs_distance(&grid, start, goal),
+                "seed {seed}"
+            );
+        }
+    }
+}
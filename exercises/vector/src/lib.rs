@@ -1 +1,15 @@
-pub mod vector2d;
\ No newline at end of file
+pub mod approx;
+pub mod batch;
+pub mod geometry;
+pub mod grid;
+pub mod matrix;
+pub mod physics;
+pub mod point2d;
+pub mod pointcloud;
+pub mod render;
+pub mod scalar;
+pub mod spatial;
+#[cfg(test)]
+mod testing;
+pub mod vector2d;
+pub mod vectorn;
@@ -10,6 +10,8 @@ fn main() {
     println!("v2: {:?}", v2);
     println!("v3: {:?}", v3);
 
-    // println!("v1 == v2: {}", v1 == v2);
-    // println!("v1 + v2: {:?}", v1 + v2);
+    println!("v1 == v2: {}", v1 == v2);
+    println!("v1 + v2: {:?}", v1 + v2);
+    println!("v1 * v2: {:?}", v1 * v2);
+    println!("2 * v1: {:?}", 2.0 * v1);
 }
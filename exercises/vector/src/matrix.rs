@@ -0,0 +1,487 @@
+use crate::vector2d::Vector2D;
+
+// Matrices are stored row-major and act on column vectors: `m * v`.
+// Composition `a * b` applies `b` first, then `a`.
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Mat2 {
+    pub m: [[f32; 2]; 2],
+}
+
+impl Mat2 {
+    pub const ZERO: Mat2 = Mat2 { m: [[0.0; 2]; 2] };
+    pub const IDENTITY: Mat2 = Mat2 {
+        m: [[1.0, 0.0], [0.0, 1.0]],
+    };
+
+    pub fn new(m00: f32, m01: f32, m10: f32, m11: f32) -> Self {
+        Mat2 {
+            m: [[m00, m01], [m10, m11]],
+        }
+    }
+
+    pub fn from_cols(c0: Vector2D, c1: Vector2D) -> Self {
+        Mat2::new(c0.x, c1.x, c0.y, c1.y)
+    }
+
+    /// Counter-clockwise rotation by `angle` radians.
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Mat2::new(cos, -sin, sin, cos)
+    }
+
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Mat2::new(sx, 0.0, 0.0, sy)
+    }
+
+    /// x' = x + shx * y, y' = shy * x + y
+    pub fn shear(shx: f32, shy: f32) -> Self {
+        Mat2::new(1.0, shx, shy, 1.0)
+    }
+
+    pub fn col(&self, index: usize) -> Vector2D {
+        Vector2D::new(self.m[0][index], self.m[1][index])
+    }
+
+    pub fn row(&self, index: usize) -> Vector2D {
+        Vector2D::new(self.m[index][0], self.m[index][1])
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.m[0][0] * self.m[1][1] - self.m[0][1] * self.m[1][0]
+    }
+
+    pub fn transpose(&self) -> Self {
+        Mat2::new(self.m[0][0], self.m[1][0], self.m[0][1], self.m[1][1])
+    }
+
+    /// `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Mat2::new(
+            self.m[1][1] / det,
+            -self.m[0][1] / det,
+            -self.m[1][0] / det,
+            self.m[0][0] / det,
+        ))
+    }
+}
+
+impl Default for Mat2 {
+    fn default() -> Self {
+        Mat2::IDENTITY
+    }
+}
+
+impl std::ops::Mul for Mat2 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let mut m = [[0.0; 2]; 2];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.row(i) * other.col(j);
+            }
+        }
+        Mat2 { m }
+    }
+}
+
+impl std::ops::Mul<Vector2D> for Mat2 {
+    type Output = Vector2D;
+
+    fn mul(self, v: Vector2D) -> Self::Output {
+        Vector2D::new(self.row(0) * v, self.row(1) * v)
+    }
+}
+
+/////////////////////////////////////////////////////////
+// General 3x3 matrix - homogeneous coordinates for 2D
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Mat3 {
+    pub m: [[f32; 3]; 3],
+}
+
+impl Mat3 {
+    pub const ZERO: Mat3 = Mat3 { m: [[0.0; 3]; 3] };
+    pub const IDENTITY: Mat3 = Mat3 {
+        m: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    };
+
+    pub fn new(m: [[f32; 3]; 3]) -> Self {
+        Mat3 { m }
+    }
+
+    // With cyclic indices the cofactor sign comes out of the ordering itself
+    fn cofactor(&self, row: usize, col: usize) -> f32 {
+        let (r1, r2) = ((row + 1) % 3, (row + 2) % 3);
+        let (c1, c2) = ((col + 1) % 3, (col + 2) % 3);
+        self.m[r1][c1] * self.m[r2][c2] - self.m[r1][c2] * self.m[r2][c1]
+    }
+
+    pub fn determinant(&self) -> f32 {
+        (0..3)
+            .map(|col| self.m[0][col] * self.cofactor(0, col))
+            .sum()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Mat3 { m }
+    }
+
+    /// Inverse via the adjugate; `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let mut m = [[0.0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.cofactor(j, i) / det;
+            }
+        }
+        Some(Mat3 { m })
+    }
+
+    fn transform(&self, x: f32, y: f32, w: f32) -> [f32; 3] {
+        let mut out = [0.0; 3];
+        for (i, value) in out.iter_mut().enumerate() {
+            *value = self.m[i][0] * x + self.m[i][1] * y + self.m[i][2] * w;
+        }
+        out
+    }
+
+    /// Transforms a position (w = 1), dividing by the resulting w for projective matrices.
+    pub fn transform_point(&self, p: Vector2D) -> Vector2D {
+        let [x, y, w] = self.transform(p.x, p.y, 1.0);
+        Vector2D::new(x / w, y / w)
+    }
+
+    /// Transforms a direction (w = 0), unaffected by translation.
+    pub fn transform_vector(&self, v: Vector2D) -> Vector2D {
+        let [x, y, _] = self.transform(v.x, v.y, 0.0);
+        Vector2D::new(x, y)
+    }
+}
+
+impl Default for Mat3 {
+    fn default() -> Self {
+        Mat3::IDENTITY
+    }
+}
+
+impl std::ops::Mul for Mat3 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        let mut m = [[0.0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..3).map(|k| self.m[i][k] * other.m[k][j]).sum();
+            }
+        }
+        Mat3 { m }
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Affine transformation: linear part followed by a translation,
+// i.e. the homogeneous matrix [[linear, translation], [0, 0, 1]].
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Affine2 {
+    pub linear: Mat2,
+    pub translation: Vector2D,
+}
+
+impl Affine2 {
+    pub const IDENTITY: Affine2 = Affine2 {
+        linear: Mat2::IDENTITY,
+        translation: Vector2D { x: 0.0, y: 0.0 },
+    };
+
+    pub fn new(linear: Mat2, translation: Vector2D) -> Self {
+        Affine2 {
+            linear,
+            translation,
+        }
+    }
+
+    pub fn translation(offset: Vector2D) -> Self {
+        Affine2::new(Mat2::IDENTITY, offset)
+    }
+
+    pub fn rotation(angle: f32) -> Self {
+        Affine2::new(Mat2::rotation(angle), Vector2D::ZERO)
+    }
+
+    /// Rotation by `angle` around `center` instead of the origin.
+    pub fn rotation_about(center: Vector2D, angle: f32) -> Self {
+        Affine2::translation(center) * Affine2::rotation(angle) * Affine2::translation(-center)
+    }
+
+    pub fn scale(sx: f32, sy: f32) -> Self {
+        Affine2::new(Mat2::scale(sx, sy), Vector2D::ZERO)
+    }
+
+    pub fn shear(shx: f32, shy: f32) -> Self {
+        Affine2::new(Mat2::shear(shx, shy), Vector2D::ZERO)
+    }
+
+    pub fn determinant(&self) -> f32 {
+        self.linear.determinant()
+    }
+
+    /// `None` when the linear part is singular.
+    pub fn inverse(&self) -> Option<Self> {
+        let linear = self.linear.inverse()?;
+        Some(Affine2::new(linear, -(linear * self.translation)))
+    }
+
+    /// Applies `self` first, then `next`.
+    pub fn then(&self, next: &Affine2) -> Self {
+        *next * *self
+    }
+
+    pub fn transform_point(&self, p: Vector2D) -> Vector2D {
+        self.linear * p + self.translation
+    }
+
+    pub fn transform_vector(&self, v: Vector2D) -> Vector2D {
+        self.linear * v
+    }
+
+    pub fn to_mat3(&self) -> Mat3 {
+        let l = &self.linear.m;
+        let t = self.translation;
+        Mat3::new([
+            [l[0][0], l[0][1], t.x],
+            [l[1][0], l[1][1], t.y],
+            [0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Default for Affine2 {
+    fn default() -> Self {
+        Affine2::IDENTITY
+    }
+}
+
+impl std::ops::Mul for Affine2 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Affine2::new(
+            self.linear * other.linear,
+            self.linear * other.translation + self.translation,
+        )
+    }
+}
+
+impl From<Affine2> for Mat3 {
+    fn from(affine: Affine2) -> Self {
+        affine.to_mat3()
+    }
+}
+
+#[cfg(test)]
+mod tests_matrix {
+    use super::*;
+    use crate::assert_approx_eq;
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    const EPS: f32 = 1e-5;
+
+    #[test]
+    fn mat2_identity_and_multiplication() {
+        let m = Mat2::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(Mat2::IDENTITY * m, m);
+        assert_eq!(m * Mat2::IDENTITY, m);
+        assert_eq!(m * m, Mat2::new(7.0, 10.0, 15.0, 22.0));
+        assert_eq!(m * Vector2D::new(1.0, 1.0), Vector2D::new(3.0, 7.0));
+        assert_eq!(Mat2::from_cols(m.col(0), m.col(1)), m);
+    }
+
+    #[test]
+    fn mat2_determinant_transpose_inverse() {
+        let m = Mat2::new(4.0, 7.0, 2.0, 6.0);
+        assert_eq!(m.determinant(), 10.0);
+        assert_eq!(m.transpose(), Mat2::new(4.0, 2.0, 7.0, 6.0));
+
+        let inv = m.inverse().unwrap();
+        assert_approx_eq!(inv, Mat2::new(0.6, -0.7, -0.2, 0.4), epsilon = EPS);
+        assert_approx_eq!(m * inv, Mat2::IDENTITY, epsilon = EPS);
+
+        assert_eq!(Mat2::new(1.0, 2.0, 2.0, 4.0).inverse(), None);
+    }
+
+    #[test]
+    fn mat2_rotation() {
+        assert_approx_eq!(
+            Mat2::rotation(FRAC_PI_2) * Vector2D::UNIT_X,
+            Vector2D::UNIT_Y,
+            epsilon = EPS
+        );
+        assert_approx_eq!(
+            Mat2::rotation(PI) * Vector2D::new(1.0, 2.0),
+            Vector2D::new(-1.0, -2.0),
+            epsilon = EPS
+        );
+        assert!((Mat2::rotation(0.3).determinant() - 1.0).abs() < EPS);
+        assert_approx_eq!(
+            Mat2::rotation(0.3).inverse().unwrap(),
+            Mat2::rotation(-0.3),
+            epsilon = EPS
+        );
+    }
+
+    #[test]
+    fn mat2_scale_and_shear() {
+        assert_eq!(
+            Mat2::scale(2.0, 3.0) * Vector2D::new(1.0, 1.0),
+            Vector2D::new(2.0, 3.0)
+        );
+        assert_eq!(
+            Mat2::shear(1.0, 0.0) * Vector2D::new(1.0, 2.0),
+            Vector2D::new(3.0, 2.0)
+        );
+        assert_eq!(Mat2::shear(0.5, 0.0).determinant(), 1.0);
+    }
+
+    #[test]
+    fn mat3_determinant_and_inverse() {
+        let m = Mat3::new([[1.0, 2.0, 3.0], [0.0, 1.0, 4.0], [5.0, 6.0, 0.0]]);
+        assert_eq!(m.determinant(), 1.0);
+
+        let inv = m.inverse().unwrap();
+        assert_eq!(
+            inv,
+            Mat3::new([[-24.0, 18.0, 5.0], [20.0, -15.0, -4.0], [-5.0, 4.0, 1.0]])
+        );
+        assert_eq!(m.transpose().transpose(), m);
+        assert_eq!(m * inv, Mat3::IDENTITY);
+
+        let singular = Mat3::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]]);
+        assert_eq!(singular.determinant(), 0.0);
+        assert_eq!(singular.inverse(), None);
+    }
+
+    #[test]
+    fn affine_translation_moves_points_not_vectors() {
+        let t = Affine2::translation(Vector2D::new(5.0, -1.0));
+        assert_eq!(
+            t.transform_point(Vector2D::new(1.0, 1.0)),
+            Vector2D::new(6.0, 0.0)
+        );
+        assert_eq!(
+            t.transform_vector(Vector2D::new(1.0, 1.0)),
+            Vector2D::new(1.0, 1.0)
+        );
+
+        let m: Mat3 = t.into();
+        assert_eq!(
+            m.transform_point(Vector2D::new(1.0, 1.0)),
+            Vector2D::new(6.0, 0.0)
+        );
+        assert_eq!(
+            m.transform_vector(Vector2D::new(1.0, 1.0)),
+            Vector2D::new(1.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn affine_composition_order() {
+        let rotate = Affine2::rotation(FRAC_PI_2);
+        let translate = Affine2::translation(Vector2D::new(1.0, 0.0));
+        let p = Vector2D::UNIT_X;
+
+        // rotate first, then translate
+        assert_approx_eq!(
+            (translate * rotate).transform_point(p),
+            Vector2D::new(1.0, 1.0),
+            epsilon = EPS
+        );
+        assert_approx_eq!(
+            rotate.then(&translate).transform_point(p),
+            Vector2D::new(1.0, 1.0),
+            epsilon = EPS
+        );
+        // translate first, then rotate
+        assert_approx_eq!(
+            (rotate * translate).transform_point(p),
+            Vector2D::new(0.0, 2.0),
+            epsilon = EPS
+        );
+    }
+
+    #[test]
+    fn affine_matches_mat3() {
+        let a = Affine2::rotation(0.7) * Affine2::scale(2.0, 0.5) * Affine2::shear(0.3, 0.1);
+        let b = Affine2::translation(Vector2D::new(-2.0, 4.0)) * Affine2::rotation(-1.2);
+        let p = Vector2D::new(1.5, -2.5);
+
+        assert_approx_eq!(
+            (a * b).transform_point(p),
+            (a.to_mat3() * b.to_mat3()).transform_point(p),
+            epsilon = EPS
+        );
+        assert!((a.determinant() - a.to_mat3().determinant()).abs() < EPS);
+        assert!((a.determinant() - 2.0 * 0.5 * (1.0 - 0.3 * 0.1)).abs() < EPS);
+    }
+
+    #[test]
+    fn affine_inverse() {
+        let a = Affine2::translation(Vector2D::new(3.0, 1.0))
+            * Affine2::rotation(0.4)
+            * Affine2::scale(2.0, 3.0);
+        let inv = a.inverse().unwrap();
+        let p = Vector2D::new(-1.0, 2.0);
+
+        assert_approx_eq!(inv.transform_point(a.transform_point(p)), p, epsilon = EPS);
+        assert_approx_eq!(a.transform_point(inv.transform_point(p)), p, epsilon = EPS);
+        assert_approx_eq!((a * inv).transform_point(p), p, epsilon = EPS);
+
+        assert_eq!(Affine2::scale(0.0, 1.0).inverse(), None);
+    }
+
+    #[test]
+    fn rotation_about_center() {
+        let r = Affine2::rotation_about(Vector2D::new(1.0, 1.0), PI);
+        assert_approx_eq!(
+            r.transform_point(Vector2D::new(2.0, 1.0)),
+            Vector2D::new(0.0, 1.0),
+            epsilon = EPS
+        );
+        assert_approx_eq!(
+            r.transform_point(Vector2D::new(1.0, 1.0)),
+            Vector2D::new(1.0, 1.0),
+            epsilon = EPS
+        );
+        assert_approx_eq!(
+            r.transform_vector(Vector2D::UNIT_X),
+            -Vector2D::UNIT_X,
+            epsilon = EPS
+        );
+    }
+
+    #[test]
+    fn projective_point_divides_by_w() {
+        let m = Mat3::new([[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 2.0]]);
+        assert_eq!(
+            m.transform_point(Vector2D::new(4.0, 2.0)),
+            Vector2D::new(2.0, 1.0)
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::vector2d::Vector2D;
+
+/// Rigid body: a point mass with an orientation.
+///
+/// Forces and torques accumulate until the next world step and are cleared
+/// afterwards. Static bodies have infinite mass (zero inverse mass) and are
+/// never moved by forces or impulses.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Body {
+    pub position: Vector2D,
+    pub velocity: Vector2D,
+    /// Orientation in radians, counter-clockwise.
+    pub angle: f32,
+    pub angular_velocity: f32,
+    pub force: Vector2D,
+    pub torque: f32,
+    inverse_mass: f32,
+    inverse_inertia: f32,
+}
+
+impl Body {
+    /// Panics if `mass` is not positive and finite.
+    pub fn new(position: Vector2D, mass: f32) -> Self {
+        assert!(
+            mass > 0.0 && mass.is_finite(),
+            "body mass must be positive and finite"
+        );
+        Body {
+            position,
+            velocity: Vector2D::ZERO,
+            angle: 0.0,
+            angular_velocity: 0.0,
+            force: Vector2D::ZERO,
+            torque: 0.0,
+            inverse_mass: 1.0 / mass,
+            inverse_inertia: 0.0,
+        }
+    }
+
+    /// Immovable body, e.g. the ground.
+    pub fn fixed(position: Vector2D) -> Self {
+        Body {
+            inverse_mass: 0.0,
+            ..Body::new(position, 1.0)
+        }
+    }
+
+    pub fn with_velocity(self, velocity: Vector2D) -> Self {
+        Body { velocity, ..self }
+    }
+
+    /// Sets the moment of inertia; bodies without one do not rotate.
+    pub fn with_inertia(self, inertia: f32) -> Self {
+        assert!(inertia > 0.0, "moment of inertia must be positive");
+        Body {
+            inverse_inertia: if self.is_static() { 0.0 } else { 1.0 / inertia },
+            ..self
+        }
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.inverse_mass == 0.0
+    }
+
+    /// `f32::INFINITY` for static bodies.
+    pub fn mass(&self) -> f32 {
+        if self.is_static() {
+            f32::INFINITY
+        } else {
+            1.0 / self.inverse_mass
+        }
+    }
+
+    pub fn inverse_mass(&self) -> f32 {
+        self.inverse_mass
+    }
+
+    pub fn inverse_inertia(&self) -> f32 {
+        self.inverse_inertia
+    }
+
+    pub fn momentum(&self) -> Vector2D {
+        if self.is_static() {
+            Vector2D::ZERO
+        } else {
+            self.velocity * self.mass()
+        }
+    }
+
+    /// Kinetic energy of translation and rotation, 0 for static bodies.
+    pub fn kinetic_energy(&self) -> f32 {
+        if self.is_static() {
+            return 0.0;
+        }
+        let linear = 0.5 * self.mass() * self.velocity.length_squared();
+        let angular = if self.inverse_inertia == 0.0 {
+            0.0
+        } else {
+            0.5 * self.angular_velocity * self.angular_velocity / self.inverse_inertia
+        };
+        linear + angular
+    }
+
+    pub fn apply_force(&mut self, force: Vector2D) {
+        self.force += force;
+    }
+
+    /// Force applied at a world-space point, which also produces torque.
+    pub fn apply_force_at(&mut self, force: Vector2D, point: Vector2D) {
+        self.force += force;
+        self.torque += (point - self.position).perp_dot(&force);
+    }
+
+    /// Instant change of momentum.
+    pub fn apply_impulse(&mut self, impulse: Vector2D) {
+        self.velocity += impulse * self.inverse_mass;
+    }
+
+    pub fn apply_impulse_at(&mut self, impulse: Vector2D, point: Vector2D) {
+        self.velocity += impulse * self.inverse_mass;
+        self.angular_velocity += (point - self.position).perp_dot(&impulse) * self.inverse_inertia;
+    }
+
+    pub fn clear_forces(&mut self) {
+        self.force = Vector2D::ZERO;
+        self.torque = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests_body {
+    use super::*;
+
+    #[test]
+    fn mass_and_energy() {
+        let body = Body::new(Vector2D::ZERO, 2.0).with_velocity(Vector2D::new(3.0, 4.0));
+        assert_eq!(body.mass(), 2.0);
+        assert_eq!(body.inverse_mass(), 0.5);
+        assert_eq!(body.momentum(), Vector2D::new(6.0, 8.0));
+        assert_eq!(body.kinetic_energy(), 25.0);
+
+        let spinning = Body {
+            angular_velocity: 2.0,
+            ..Body::new(Vector2D::ZERO, 1.0).with_inertia(0.5)
+        };
+        assert_eq!(spinning.kinetic_energy(), 1.0);
+    }
+
+    #[test]
+    fn static_bodies_ignore_impulses() {
+        let mut ground = Body::fixed(Vector2D::ZERO).with_inertia(1.0);
+        assert!(ground.is_static());
+        assert_eq!(ground.mass(), f32::INFINITY);
+        ground.apply_impulse_at(Vector2D::new(10.0, 0.0), Vector2D::new(0.0, 1.0));
+        assert_eq!(ground.velocity, Vector2D::ZERO);
+        assert_eq!(ground.angular_velocity, 0.0);
+        assert_eq!(ground.kinetic_energy(), 0.0);
+    }
+
+    #[test]
+    fn off_center_forces_and_impulses_spin() {
+        let mut body = Body::new(Vector2D::new(1.0, 1.0), 2.0).with_inertia(4.0);
+        body.apply_force_at(Vector2D::new(0.0, 3.0), Vector2D::new(2.0, 1.0));
+        assert_eq!(body.force, Vector2D::new(0.0, 3.0));
+        assert_eq!(body.torque, 3.0);
+        body.clear_forces();
+        assert_eq!(body.force, Vector2D::ZERO);
+        assert_eq!(body.torque, 0.0);
+
+        body.apply_impulse_at(Vector2D::new(0.0, 2.0), Vector2D::new(0.0, 1.0));
+        assert_eq!(body.velocity, Vector2D::new(0.0, 1.0));
+        assert_eq!(body.angular_velocity, -0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "positive and finite")]
+    fn zero_mass_panics() {
+        Body::new(Vector2D::ZERO, 0.0);
+    }
+}
@@ -0,0 +1,587 @@
+use crate::geometry::algorithms;
+use crate::geometry::shapes::{Aabb, Circle, Polygon, Shape};
+use crate::physics::body::Body;
+use crate::vector2d::Vector2D;
+
+// Narrow phase: exact overlap tests between circles and convex polygons that
+// report where and how deeply the shapes overlap. Polygons use the separating
+// axis test over both sets of edge normals; the overlapping edges are then
+// clipped against each other to find up to two contact points, which is what
+// keeps stacked boxes from rocking on a single corner.
+//
+// Broad phase: sweep and prune over the x extents of bounding boxes. The
+// sort order is kept between calls, so the insertion sort does little work
+// while bodies move smoothly.
+
+/// Collision shape in body space: the body's position is the origin and its
+/// angle rotates the shape.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Collider {
+    Circle {
+        radius: f32,
+    },
+    /// Built by `Collider::polygon`.
+    Polygon(ConvexHull),
+}
+
+/// Vertices of a polygon collider: at least three, convex and
+/// counter-clockwise. Only `Collider::polygon` can make one.
+///
+/// Building one directly does not compile:
+///
+/// ```compile_fail
+/// use vector::physics::collision::{Collider, ConvexHull};
+///
+/// let empty = Collider::Polygon(ConvexHull(Vec::new()));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ConvexHull(Vec<Vector2D>);
+
+impl ConvexHull {
+    pub fn vertices(&self) -> &[Vector2D] {
+        &self.0
+    }
+}
+
+/// Point where two shapes overlap.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Contact {
+    /// Midway between the two surfaces.
+    pub point: Vector2D,
+    /// Overlap along the manifold normal, positive when penetrating.
+    pub depth: f32,
+}
+
+/// Result of a narrow-phase test: one or two contacts sharing a normal.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Manifold {
+    /// Unit normal pointing from the first shape towards the second.
+    pub normal: Vector2D,
+    pub contacts: Vec<Contact>,
+}
+
+impl Manifold {
+    /// The same contact seen from the other shape.
+    pub fn flipped(self) -> Manifold {
+        Manifold {
+            normal: -self.normal,
+            ..self
+        }
+    }
+
+    pub fn max_depth(&self) -> f32 {
+        self.contacts.iter().map(|c| c.depth).fold(0.0, f32::max)
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Colliders
+
+// A collider placed in world space
+enum Placed {
+    Circle(Circle),
+    Polygon(Polygon),
+}
+
+impl Collider {
+    /// Panics if `radius` is not positive.
+    pub fn circle(radius: f32) -> Self {
+        assert!(radius > 0.0, "collider radius must be positive");
+        Collider::Circle { radius }
+    }
+
+    /// Convex hull of `points`, which are relative to the body's centre of mass.
+    ///
+    /// Panics if the points do not span an area.
+    pub fn polygon(points: &[Vector2D]) -> Self {
+        let vertices = algorithms::convex_hull(points);
+        assert!(
+            vertices.len() >= 3,
+            "collider polygon needs at least 3 non-collinear points"
+        );
+        Collider::Polygon(ConvexHull(vertices))
+    }
+
+    /// Box centred on the body.
+    pub fn rectangle(width: f32, height: f32) -> Self {
+        let (x, y) = (width / 2.0, height / 2.0);
+        Collider::polygon(&[
+            Vector2D::new(-x, -y),
+            Vector2D::new(x, -y),
+            Vector2D::new(x, y),
+            Vector2D::new(-x, y),
+        ])
+    }
+
+    pub fn area(&self) -> f32 {
+        match self {
+            Collider::Circle { radius } => std::f32::consts::PI * radius * radius,
+            Collider::Polygon(hull) => algorithms::polygon_area(hull.vertices()),
+        }
+    }
+
+    /// Moment of inertia about the body's origin for a uniform density,
+    /// for use with `Body::with_inertia`.
+    pub fn moment_of_inertia(&self, mass: f32) -> f32 {
+        match self {
+            Collider::Circle { radius } => 0.5 * mass * radius * radius,
+            Collider::Polygon(hull) => {
+                let vertices = hull.vertices();
+                // sum over the triangles (origin, a, b)
+                let (mut numerator, mut denominator) = (0.0, 0.0);
+                for (i, a) in vertices.iter().enumerate() {
+                    let b = vertices[(i + 1) % vertices.len()];
+                    let cross = a.perp_dot(&b);
+                    numerator += cross * (a.dot(a) + a.dot(&b) + b.dot(&b));
+                    denominator += cross;
+                }
+                mass * numerator / (6.0 * denominator)
+            }
+        }
+    }
+
+    /// Bounding box of the collider attached to `body`.
+    pub fn aabb(&self, body: &Body) -> Aabb {
+        match self.placed(body) {
+            Placed::Circle(circle) => circle.aabb(),
+            Placed::Polygon(polygon) => polygon.aabb(),
+        }
+    }
+
+    fn placed(&self, body: &Body) -> Placed {
+        match self {
+            Collider::Circle { radius } => Placed::Circle(Circle::new(body.position, *radius)),
+            Collider::Polygon(hull) => Placed::Polygon(Polygon::new(
+                hull.vertices()
+                    .iter()
+                    .map(|v| body.position + v.rotate(body.angle))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Narrow-phase test between two colliders attached to bodies; the normal
+/// points from `a` to `b`.
+pub fn collide(a: &Collider, body_a: &Body, b: &Collider, body_b: &Body) -> Option<Manifold> {
+    match (a.placed(body_a), b.placed(body_b)) {
+        (Placed::Circle(a), Placed::Circle(b)) => collide_circles(&a, &b),
+        (Placed::Circle(a), Placed::Polygon(b)) => collide_circle_polygon(&a, &b),
+        (Placed::Polygon(a), Placed::Circle(b)) => {
+            collide_circle_polygon(&b, &a).map(Manifold::flipped)
+        }
+        (Placed::Polygon(a), Placed::Polygon(b)) => collide_polygons(&a, &b),
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Narrow phase
+
+pub fn collide_circles(a: &Circle, b: &Circle) -> Option<Manifold> {
+    let offset = b.center - a.center;
+    let distance = offset.length();
+    let depth = a.radius + b.radius - distance;
+    if depth < 0.0 {
+        return None;
+    }
+    // concentric circles: any direction will do
+    let normal = offset.normalize().unwrap_or(Vector2D::UNIT_X);
+    Some(Manifold {
+        normal,
+        contacts: vec![Contact {
+            point: a.center + normal * (a.radius - depth / 2.0),
+            depth,
+        }],
+    })
+}
+
+/// `polygon` must be convex, in either winding order.
+pub fn collide_circle_polygon(circle: &Circle, polygon: &Polygon) -> Option<Manifold> {
+    let vertices = polygon.vertices();
+    let normals = outward_normals(polygon);
+
+    // the face the centre is least behind
+    let (face, separation) = (0..vertices.len())
+        .map(|i| (i, normals[i].dot(&(circle.center - vertices[i]))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    if separation > circle.radius {
+        return None;
+    }
+
+    // `outward` points from the polygon towards the circle's centre
+    let (outward, depth) = if separation <= 0.0 {
+        (normals[face], circle.radius - separation)
+    } else {
+        let closest = polygon
+            .edges()
+            .map(|edge| edge.closest_point(circle.center))
+            .min_by(|a, b| {
+                a.distance_squared(&circle.center)
+                    .total_cmp(&b.distance_squared(&circle.center))
+            })
+            .unwrap();
+        let offset = circle.center - closest;
+        let distance = offset.length();
+        if distance > circle.radius {
+            return None;
+        }
+        (offset / distance, circle.radius - distance)
+    };
+
+    let deepest = circle.center - outward * circle.radius;
+    Some(Manifold {
+        normal: -outward,
+        contacts: vec![Contact {
+            point: deepest + outward * (depth / 2.0),
+            depth,
+        }],
+    })
+}
+
+/// Both polygons must be convex, in either winding order.
+pub fn collide_polygons(a: &Polygon, b: &Polygon) -> Option<Manifold> {
+    let (normals_a, normals_b) = (outward_normals(a), outward_normals(b));
+    let (edge_a, separation_a) = max_separation(a.vertices(), &normals_a, b.vertices());
+    if separation_a > 0.0 {
+        return None;
+    }
+    let (edge_b, separation_b) = max_separation(b.vertices(), &normals_b, a.vertices());
+    if separation_b > 0.0 {
+        return None;
+    }
+
+    // Prefer `a` as the reference unless `b` is clearly better, so that
+    // nearly equal separations do not flip the choice from step to step.
+    let flip = separation_b > separation_a + 5e-4;
+    let (reference, reference_normals, edge, incident, incident_normals) = if flip {
+        (b.vertices(), &normals_b, edge_b, a.vertices(), &normals_a)
+    } else {
+        (a.vertices(), &normals_a, edge_a, b.vertices(), &normals_b)
+    };
+    let normal = reference_normals[edge];
+
+    // the incident edge faces the reference edge most directly
+    let i = (0..incident.len())
+        .min_by(|&i, &j| {
+            incident_normals[i]
+                .dot(&normal)
+                .total_cmp(&incident_normals[j].dot(&normal))
+        })
+        .unwrap();
+    let incident_edge = [incident[i], incident[(i + 1) % incident.len()]];
+
+    // clip the incident edge to the side planes of the reference edge
+    let (v1, v2) = (reference[edge], reference[(edge + 1) % reference.len()]);
+    let tangent = (v2 - v1).normalize()?;
+    let clipped = clip_segment(incident_edge, -tangent, -tangent.dot(&v1))
+        .and_then(|clipped| clip_segment(clipped, tangent, tangent.dot(&v2)))?;
+
+    let contacts: Vec<Contact> = clipped
+        .iter()
+        .filter_map(|&p| {
+            let separation = normal.dot(&(p - v1));
+            (separation <= 0.0).then(|| Contact {
+                point: p - normal * (separation / 2.0),
+                depth: -separation,
+            })
+        })
+        .collect();
+    if contacts.is_empty() {
+        return None;
+    }
+    Some(Manifold {
+        normal: if flip { -normal } else { normal },
+        contacts,
+    })
+}
+
+fn outward_normals(polygon: &Polygon) -> Vec<Vector2D> {
+    let sign = if polygon.is_ccw() { 1.0 } else { -1.0 };
+    polygon
+        .edges()
+        .map(|edge| {
+            let d = edge.direction();
+            Vector2D::new(d.y, -d.x)
+                .normalize()
+                .unwrap_or(Vector2D::ZERO)
+                * sign
+        })
+        .collect()
+}
+
+// Edge of `a` that `b` lies furthest in front of, and that distance
+fn max_separation(a: &[Vector2D], normals: &[Vector2D], b: &[Vector2D]) -> (usize, f32) {
+    (0..a.len())
+        .map(|i| {
+            let separation = b
+                .iter()
+                .map(|v| normals[i].dot(&(*v - a[i])))
+                .fold(f32::INFINITY, f32::min);
+            (i, separation)
+        })
+        .max_by(|x, y| x.1.total_cmp(&y.1))
+        .unwrap()
+}
+
+// Keeps the part of the segment where `normal . p <= offset`
+fn clip_segment([p0, p1]: [Vector2D; 2], normal: Vector2D, offset: f32) -> Option<[Vector2D; 2]> {
+    let (d0, d1) = (normal.dot(&p0) - offset, normal.dot(&p1) - offset);
+    match (d0 <= 0.0, d1 <= 0.0) {
+        (true, true) => Some([p0, p1]),
+        (false, false) => None,
+        (inside_first, _) => {
+            let crossing = p0.lerp(&p1, d0 / (d0 - d1));
+            Some(if inside_first {
+                [p0, crossing]
+            } else {
+                [crossing, p1]
+            })
+        }
+    }
+}
+
+/////////////////////////////////////////////////////////
+// Broad phase
+
+#[derive(Clone, Debug, Default)]
+pub struct SweepAndPrune {
+    // box indices sorted by min.x, reused between calls
+    order: Vec<usize>,
+}
+
+impl SweepAndPrune {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index pairs `(i, j)`, `i < j`, of overlapping (or touching) boxes in
+    /// ascending order.
+    pub fn pairs(&mut self, boxes: &[Aabb]) -> Vec<(usize, usize)> {
+        if self.order.len() != boxes.len() {
+            self.order = (0..boxes.len()).collect();
+        }
+
+        // insertion sort: nearly linear when the order barely changed
+        for i in 1..self.order.len() {
+            let mut j = i;
+            while j > 0 && boxes[self.order[j - 1]].min.x > boxes[self.order[j]].min.x {
+                self.order.swap(j - 1, j);
+                j -= 1;
+            }
+        }
+
+        let mut pairs = Vec::new();
+        let mut active: Vec<usize> = Vec::new();
+        for &i in &self.order {
+            let current = &boxes[i];
+            active.retain(|&j| boxes[j].max.x >= current.min.x);
+            for &j in &active {
+                if boxes[j].min.y <= current.max.y && boxes[j].max.y >= current.min.y {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+            active.push(i);
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests_collision {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::testing::random_points;
+
+    fn square(center: Vector2D, half: f32) -> Polygon {
+        Aabb::new(
+            center - Vector2D::new(half, half),
+            center + Vector2D::new(half, half),
+        )
+        .to_polygon()
+    }
+
+    #[test]
+    fn circles() {
+        let a = Circle::new(Vector2D::ZERO, 1.0);
+        let manifold = collide_circles(&a, &Circle::new(Vector2D::new(1.5, 0.0), 1.0)).unwrap();
+        assert_eq!(manifold.normal, Vector2D::UNIT_X);
+        assert_eq!(
+            manifold.contacts,
+            vec![Contact {
+                point: Vector2D::new(0.75, 0.0),
+                depth: 0.5
+            }]
+        );
+        assert!(collide_circles(&a, &Circle::new(Vector2D::new(0.0, 2.5), 1.0)).is_none());
+        assert_eq!(
+            collide_circles(&a, &Circle::new(Vector2D::ZERO, 0.5))
+                .unwrap()
+                .max_depth(),
+            1.5
+        );
+    }
+
+    #[test]
+    fn circle_against_polygon_face_corner_and_inside() {
+        let polygon = square(Vector2D::ZERO, 1.0);
+
+        let face =
+            collide_circle_polygon(&Circle::new(Vector2D::new(0.0, 1.5), 1.0), &polygon).unwrap();
+        assert_eq!(face.normal, Vector2D::new(0.0, -1.0));
+        assert_eq!(face.contacts[0].depth, 0.5);
+        assert_eq!(face.contacts[0].point, Vector2D::new(0.0, 0.75));
+
+        let corner = Circle::new(Vector2D::new(1.5, 1.5), 1.0);
+        let manifold = collide_circle_polygon(&corner, &polygon).unwrap();
+        assert_approx_eq!(manifold.normal, Vector2D::new(-1.0, -1.0) / 2f32.sqrt());
+        assert_approx_eq!(manifold.contacts[0].depth, 1.0 - 0.5 * 2f32.sqrt());
+        // just outside the corner's reach
+        assert!(
+            collide_circle_polygon(&Circle::new(Vector2D::new(1.72, 1.72), 1.0), &polygon)
+                .is_none()
+        );
+
+        let inside =
+            collide_circle_polygon(&Circle::new(Vector2D::new(0.8, 0.0), 0.5), &polygon).unwrap();
+        assert_approx_eq!(inside.normal, Vector2D::new(-1.0, 0.0));
+        assert_approx_eq!(inside.contacts[0].depth, 0.7);
+    }
+
+    #[test]
+    fn resting_boxes_touch_along_an_edge() {
+        let bottom = square(Vector2D::ZERO, 1.0);
+        let top = square(Vector2D::new(0.5, 1.9), 1.0);
+        let manifold = collide_polygons(&bottom, &top).unwrap();
+        assert_eq!(manifold.normal, Vector2D::new(0.0, 1.0));
+        assert_eq!(manifold.contacts.len(), 2);
+        for contact in &manifold.contacts {
+            assert_approx_eq!(contact.depth, 0.1);
+            assert_approx_eq!(contact.point.y, 0.95);
+        }
+        let mut xs: Vec<f32> = manifold.contacts.iter().map(|c| c.point.x).collect();
+        xs.sort_by(f32::total_cmp);
+        assert_approx_eq!(xs[0], -0.5);
+        assert_approx_eq!(xs[1], 1.0);
+
+        let flipped = collide_polygons(&top, &bottom).unwrap();
+        assert_eq!(flipped.normal, Vector2D::new(0.0, -1.0));
+    }
+
+    #[test]
+    fn rotated_box_lands_on_a_corner() {
+        let ground = square(Vector2D::ZERO, 1.0);
+        let diamond = Polygon::new(
+            square(Vector2D::ZERO, 0.5)
+                .vertices()
+                .iter()
+                .map(|v| v.rotate(std::f32::consts::FRAC_PI_4) + Vector2D::new(0.0, 1.6))
+                .collect(),
+        );
+        let manifold = collide_polygons(&ground, &diamond).unwrap();
+        assert_approx_eq!(manifold.normal, Vector2D::new(0.0, 1.0));
+        assert_eq!(manifold.contacts.len(), 1);
+        assert_approx_eq!(manifold.contacts[0].depth, 0.5 * 2f32.sqrt() - 0.6);
+        assert_approx_eq!(manifold.contacts[0].point.x, 0.0);
+    }
+
+    #[test]
+    fn separated_polygons_and_either_winding() {
+        let a = square(Vector2D::ZERO, 1.0);
+        assert!(collide_polygons(&a, &square(Vector2D::new(2.1, 0.0), 1.0)).is_none());
+        // separated only along an axis of the triangle
+        let triangle = Polygon::new(vec![
+            Vector2D::new(1.6, 1.6),
+            Vector2D::new(3.0, 1.0),
+            Vector2D::new(1.0, 3.0),
+        ]);
+        assert!(collide_polygons(&a, &triangle).is_none());
+
+        let clockwise = Polygon::new(
+            square(Vector2D::new(1.5, 0.0), 1.0)
+                .vertices()
+                .iter()
+                .rev()
+                .copied()
+                .collect(),
+        );
+        let manifold = collide_polygons(&a, &clockwise).unwrap();
+        assert_eq!(manifold.normal, Vector2D::UNIT_X);
+        assert_approx_eq!(manifold.max_depth(), 0.5);
+    }
+
+    #[test]
+    fn colliders_follow_their_bodies() {
+        let ball = Collider::circle(0.5);
+        let plank = Collider::rectangle(4.0, 1.0);
+        let mut body = Body::new(Vector2D::new(0.0, 1.0), 1.0);
+        let ground = Body::fixed(Vector2D::ZERO);
+        assert!(collide(&ball, &body, &plank, &ground).is_some());
+        assert_eq!(
+            collide(&plank, &ground, &ball, &body).unwrap().normal,
+            Vector2D::UNIT_Y
+        );
+
+        body.position = Vector2D::new(0.0, 2.5);
+        assert!(collide(&plank, &body, &plank, &ground).is_none());
+        // standing on end, the plank reaches down to the ground
+        body.angle = std::f32::consts::FRAC_PI_2;
+        assert!(collide(&plank, &body, &plank, &ground).is_some());
+        assert_approx_eq!(plank.aabb(&body).min, Vector2D::new(-0.5, 0.5));
+    }
+
+    #[test]
+    fn collider_mass_properties() {
+        assert_approx_eq!(Collider::rectangle(2.0, 4.0).area(), 8.0);
+        // m (w^2 + h^2) / 12
+        assert_approx_eq!(Collider::rectangle(2.0, 4.0).moment_of_inertia(3.0), 5.0);
+        assert_approx_eq!(Collider::circle(2.0).moment_of_inertia(3.0), 6.0);
+        // interior points are dropped
+        let hull = Collider::polygon(&[
+            Vector2D::new(0.0, 0.0),
+            Vector2D::new(1.0, 1.0),
+            Vector2D::new(0.5, 0.5),
+            Vector2D::new(0.0, 1.0),
+            Vector2D::new(1.0, 0.0),
+        ]);
+        let Collider::Polygon(vertices) = &hull else {
+            panic!("expected a polygon")
+        };
+        assert_eq!(vertices.vertices().len(), 4);
+        assert_eq!(hull.area(), 1.0);
+    }
+
+    #[test]
+    fn sweep_and_prune_matches_all_pairs() {
+        let points = random_points(200, 17);
+        let mut boxes: Vec<Aabb> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| Aabb::new(*p, *p).expand(1.0 + (i % 5) as f32))
+            .collect();
+        let mut broad_phase = SweepAndPrune::new();
+        for round in 0..3 {
+            let mut expected = Vec::new();
+            for i in 0..boxes.len() {
+                for j in i + 1..boxes.len() {
+                    let (a, b) = (&boxes[i], &boxes[j]);
+                    if a.min.x <= b.max.x
+                        && b.min.x <= a.max.x
+                        && a.min.y <= b.max.y
+                        && b.min.y <= a.max.y
+                    {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            assert_eq!(broad_phase.pairs(&boxes), expected, "round {}", round);
+            for (i, b) in boxes.iter_mut().enumerate() {
+                let shift = Vector2D::new((i % 7) as f32 - 3.0, 0.5);
+                *b = Aabb::new(b.min + shift, b.max + shift);
+            }
+        }
+        assert!(broad_phase.pairs(&boxes[..3]).len() <= 3);
+        assert!(broad_phase.pairs(&[]).is_empty());
+    }
+}
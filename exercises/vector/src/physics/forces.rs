@@ -0,0 +1,113 @@
+use crate::physics::body::Body;
+use crate::vector2d::Vector2D;
+
+/// Force acting on every dynamic body of a world.
+///
+/// Integrators evaluate fields at intermediate states, so the force must
+/// depend only on the state passed in, not on `body.position` or
+/// `body.velocity`. Conservative fields report their potential energy for
+/// the world's energy diagnostics.
+pub trait ForceField {
+    fn force(&self, body: &Body, position: Vector2D, velocity: Vector2D) -> Vector2D;
+
+    fn potential_energy(&self, _body: &Body, _position: Vector2D) -> f32 {
+        0.0
+    }
+}
+
+/// Uniform acceleration, e.g. `Gravity(Vector2D::new(0.0, -9.81))`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Gravity(pub Vector2D);
+
+impl ForceField for Gravity {
+    fn force(&self, body: &Body, _position: Vector2D, _velocity: Vector2D) -> Vector2D {
+        self.0 * body.mass()
+    }
+
+    fn potential_energy(&self, body: &Body, position: Vector2D) -> f32 {
+        -body.mass() * self.0.dot(&position)
+    }
+}
+
+/// Hookean spring pulling every body towards `anchor`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Spring {
+    pub anchor: Vector2D,
+    pub stiffness: f32,
+    pub rest_length: f32,
+}
+
+impl ForceField for Spring {
+    fn force(&self, _body: &Body, position: Vector2D, _velocity: Vector2D) -> Vector2D {
+        let offset = position - self.anchor;
+        let length = offset.length();
+        if length == 0.0 {
+            return Vector2D::ZERO;
+        }
+        offset * (-self.stiffness * (length - self.rest_length) / length)
+    }
+
+    fn potential_energy(&self, _body: &Body, position: Vector2D) -> f32 {
+        let stretch = position.distance(&self.anchor) - self.rest_length;
+        0.5 * self.stiffness * stretch * stretch
+    }
+}
+
+/// Linear drag opposing the velocity.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Drag(pub f32);
+
+impl ForceField for Drag {
+    fn force(&self, _body: &Body, _position: Vector2D, velocity: Vector2D) -> Vector2D {
+        velocity * -self.0
+    }
+}
+
+#[cfg(test)]
+mod tests_forces {
+    use super::*;
+
+    #[test]
+    fn gravity_scales_with_mass() {
+        let body = Body::new(Vector2D::new(0.0, 10.0), 2.0);
+        let gravity = Gravity(Vector2D::new(0.0, -10.0));
+        assert_eq!(
+            gravity.force(&body, body.position, Vector2D::ZERO),
+            Vector2D::new(0.0, -20.0)
+        );
+        assert_eq!(gravity.potential_energy(&body, body.position), 200.0);
+    }
+
+    #[test]
+    fn spring_pulls_towards_rest_length() {
+        let body = Body::new(Vector2D::ZERO, 1.0);
+        let spring = Spring {
+            anchor: Vector2D::ZERO,
+            stiffness: 4.0,
+            rest_length: 1.0,
+        };
+        assert_eq!(
+            spring.force(&body, Vector2D::new(3.0, 0.0), Vector2D::ZERO),
+            Vector2D::new(-8.0, 0.0)
+        );
+        assert_eq!(
+            spring.force(&body, Vector2D::new(0.0, 0.5), Vector2D::ZERO),
+            Vector2D::new(0.0, 2.0)
+        );
+        assert_eq!(
+            spring.force(&body, Vector2D::ZERO, Vector2D::ZERO),
+            Vector2D::ZERO
+        );
+        assert_eq!(spring.potential_energy(&body, Vector2D::new(3.0, 0.0)), 8.0);
+    }
+
+    #[test]
+    fn drag_opposes_velocity() {
+        let body = Body::new(Vector2D::ZERO, 1.0);
+        assert_eq!(
+            Drag(0.5).force(&body, Vector2D::ZERO, Vector2D::new(2.0, -4.0)),
+            Vector2D::new(-1.0, 2.0)
+        );
+        assert_eq!(Drag(0.5).potential_energy(&body, Vector2D::ZERO), 0.0);
+    }
+}
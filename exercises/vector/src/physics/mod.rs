@@ -0,0 +1,5 @@
+pub mod body;
+pub mod collision;
+pub mod forces;
+pub mod solver;
+pub mod world;
@@ -0,0 +1,281 @@
+use crate::physics::body::Body;
+use crate::physics::collision::Manifold;
+use crate::vector2d::Vector2D;
+
+// Sequential impulses: every contact applies an impulse that removes the
+// approaching velocity along its normal (plus a restitution bounce) and a
+// friction impulse along the tangent. Impulses are accumulated and clamped
+// rather than applied once, so that after a few iterations the contacts of a
+// stack agree on how much weight each one carries. The solve starts from the
+// impulses a collision already carries; a world hands over the totals of the
+// previous step for contacts that persist, so stacks converge over several
+// steps instead of within one.
+//
+// Velocities alone let resting bodies sink by a little every step, so
+// afterwards the remaining overlap is removed by moving the bodies apart
+// directly, again iterating so that corrections propagate through stacks.
+
+/// Collision between the bodies with indices `a` and `b`; the manifold
+/// normal points from `a` to `b`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Collision {
+    pub a: usize,
+    pub b: usize,
+    pub manifold: Manifold,
+    /// Total (normal, tangent) impulse per contact, filled in by the solver.
+    /// Missing entries count as zero.
+    pub impulses: Vec<(f32, f32)>,
+}
+
+impl Collision {
+    pub fn new(a: usize, b: usize, manifold: Manifold) -> Self {
+        Collision {
+            a,
+            b,
+            manifold,
+            impulses: Vec::new(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Solver {
+    /// Passes over all contacts per step.
+    pub iterations: usize,
+    /// 0 for no bounce, 1 for perfectly elastic collisions.
+    pub restitution: f32,
+    /// Coulomb friction coefficient.
+    pub friction: f32,
+    /// Slower impacts do not bounce, which lets resting contacts settle.
+    pub bounce_threshold: f32,
+    /// Overlap that is tolerated without positional correction.
+    pub slop: f32,
+    /// Fraction of the overlap beyond `slop` removed per iteration.
+    pub correction: f32,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver {
+            iterations: 10,
+            restitution: 0.0,
+            friction: 0.5,
+            bounce_threshold: 1.0,
+            slop: 0.005,
+            correction: 0.4,
+        }
+    }
+}
+
+// Per-contact state for one solve
+struct Constraint {
+    a: usize,
+    b: usize,
+    point: Vector2D,
+    normal: Vector2D,
+    normal_mass: f32,
+    tangent_mass: f32,
+    bounce: f32,
+    normal_impulse: f32,
+    tangent_impulse: f32,
+}
+
+fn relative_velocity(a: &Body, b: &Body, point: Vector2D) -> Vector2D {
+    let velocity_at = |body: &Body| {
+        body.velocity + (point - body.position).perpendicular() * body.angular_velocity
+    };
+    velocity_at(b) - velocity_at(a)
+}
+
+// Inverse of the effective mass of the pair along `direction` at `point`
+fn effective_mass(a: &Body, b: &Body, point: Vector2D, direction: Vector2D) -> f32 {
+    let ra = (point - a.position).perp_dot(&direction);
+    let rb = (point - b.position).perp_dot(&direction);
+    let inverse = a.inverse_mass()
+        + b.inverse_mass()
+        + a.inverse_inertia() * ra * ra
+        + b.inverse_inertia() * rb * rb;
+    if inverse > 0.0 { 1.0 / inverse } else { 0.0 }
+}
+
+impl Solver {
+    /// Resolves the collisions by changing the velocities and positions of
+    /// `bodies`, and records the applied impulses in each collision.
+    /// Collisions are processed in the order given.
+    pub fn resolve(&self, bodies: &mut [Body], collisions: &mut [Collision]) {
+        let mut constraints: Vec<Constraint> = Vec::new();
+        for collision in collisions.iter() {
+            let (a, b) = (&bodies[collision.a], &bodies[collision.b]);
+            let normal = collision.manifold.normal;
+            for (k, contact) in collision.manifold.contacts.iter().enumerate() {
+                let approach = relative_velocity(a, b, contact.point).dot(&normal);
+                let (normal_impulse, tangent_impulse) =
+                    collision.impulses.get(k).copied().unwrap_or((0.0, 0.0));
+                constraints.push(Constraint {
+                    a: collision.a,
+                    b: collision.b,
+                    point: contact.point,
+                    normal,
+                    normal_mass: effective_mass(a, b, contact.point, normal),
+                    tangent_mass: effective_mass(a, b, contact.point, normal.perpendicular()),
+                    bounce: if approach < -self.bounce_threshold {
+                        -self.restitution * approach
+                    } else {
+                        0.0
+                    },
+                    normal_impulse,
+                    tangent_impulse,
+                });
+            }
+        }
+
+        // warm start
+        for c in &constraints {
+            let impulse =
+                c.normal * c.normal_impulse + c.normal.perpendicular() * c.tangent_impulse;
+            bodies[c.a].apply_impulse_at(-impulse, c.point);
+            bodies[c.b].apply_impulse_at(impulse, c.point);
+        }
+
+        for _ in 0..self.iterations {
+            for c in &mut constraints {
+                let tangent = c.normal.perpendicular();
+
+                let velocity = relative_velocity(&bodies[c.a], &bodies[c.b], c.point);
+                let impulse = -c.normal_mass * (velocity.dot(&c.normal) - c.bounce);
+                let total = (c.normal_impulse + impulse).max(0.0);
+                let applied = c.normal * (total - c.normal_impulse);
+                c.normal_impulse = total;
+                bodies[c.a].apply_impulse_at(-applied, c.point);
+                bodies[c.b].apply_impulse_at(applied, c.point);
+
+                let velocity = relative_velocity(&bodies[c.a], &bodies[c.b], c.point);
+                let impulse = -c.tangent_mass * velocity.dot(&tangent);
+                let limit = self.friction * c.normal_impulse;
+                let total = (c.tangent_impulse + impulse).clamp(-limit, limit);
+                let applied = tangent * (total - c.tangent_impulse);
+                c.tangent_impulse = total;
+                bodies[c.a].apply_impulse_at(-applied, c.point);
+                bodies[c.b].apply_impulse_at(applied, c.point);
+            }
+        }
+
+        let mut solved = constraints.iter();
+        for collision in collisions.iter_mut() {
+            collision.impulses = solved
+                .by_ref()
+                .take(collision.manifold.contacts.len())
+                .map(|c| (c.normal_impulse, c.tangent_impulse))
+                .collect();
+        }
+
+        // The overlaps were measured before any correction, so the current
+        // overlap subtracts how far the pair has since moved apart.
+        let start: Vec<Vector2D> = bodies.iter().map(|b| b.position).collect();
+        for _ in 0..self.iterations {
+            for collision in collisions.iter() {
+                let (a, b) = (collision.a, collision.b);
+                let (a_inverse_mass, b_inverse_mass) =
+                    (bodies[a].inverse_mass(), bodies[b].inverse_mass());
+                let total_inverse_mass = a_inverse_mass + b_inverse_mass;
+                let normal = collision.manifold.normal;
+                let separated = ((bodies[b].position - start[b]) - (bodies[a].position - start[a]))
+                    .dot(&normal);
+                let overlap = collision.manifold.max_depth() - separated - self.slop;
+                if total_inverse_mass == 0.0 || overlap <= 0.0 {
+                    continue;
+                }
+                let push = normal * (self.correction * overlap / total_inverse_mass);
+                bodies[a].position -= push * a_inverse_mass;
+                bodies[b].position += push * b_inverse_mass;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_solver {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::physics::collision::Contact;
+
+    fn head_on(a: Body, b: Body) -> [Body; 2] {
+        let mut bodies = [a, b];
+        let collision = Collision::new(
+            0,
+            1,
+            Manifold {
+                normal: Vector2D::UNIT_X,
+                contacts: vec![Contact {
+                    point: Vector2D::new(0.5, 0.0),
+                    depth: 0.0,
+                }],
+            },
+        );
+        let solver = Solver {
+            restitution: 1.0,
+            ..Solver::default()
+        };
+        solver.resolve(&mut bodies, &mut [collision]);
+        bodies
+    }
+
+    #[test]
+    fn elastic_collision_swaps_equal_velocities() {
+        let [a, b] = head_on(
+            Body::new(Vector2D::ZERO, 1.0).with_velocity(Vector2D::new(2.0, 0.0)),
+            Body::new(Vector2D::new(1.0, 0.0), 1.0),
+        );
+        assert_approx_eq!(a.velocity, Vector2D::ZERO);
+        assert_approx_eq!(b.velocity, Vector2D::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn static_bodies_reflect_and_do_not_move() {
+        let [ball, wall] = head_on(
+            Body::new(Vector2D::ZERO, 1.0).with_velocity(Vector2D::new(3.0, 1.0)),
+            Body::fixed(Vector2D::new(1.0, 0.0)),
+        );
+        // normal component reversed, tangential one reduced by friction
+        assert_approx_eq!(ball.velocity.x, -3.0);
+        assert_approx_eq!(ball.velocity.y, 0.0);
+        assert_eq!(wall.velocity, Vector2D::ZERO);
+        assert_eq!(wall.position, Vector2D::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn separating_bodies_are_left_alone() {
+        let [a, b] = head_on(
+            Body::new(Vector2D::ZERO, 1.0).with_velocity(Vector2D::new(-1.0, 0.0)),
+            Body::new(Vector2D::new(1.0, 0.0), 1.0),
+        );
+        assert_eq!(a.velocity, Vector2D::new(-1.0, 0.0));
+        assert_eq!(b.velocity, Vector2D::ZERO);
+    }
+
+    #[test]
+    fn overlap_is_corrected_by_inverse_mass() {
+        let mut bodies = [
+            Body::new(Vector2D::ZERO, 1.0),
+            Body::new(Vector2D::new(1.0, 0.0), 3.0),
+        ];
+        let collision = Collision::new(
+            0,
+            1,
+            Manifold {
+                normal: Vector2D::UNIT_X,
+                contacts: vec![Contact {
+                    point: Vector2D::new(0.5, 0.0),
+                    depth: 0.405,
+                }],
+            },
+        );
+        let solver = Solver {
+            correction: 1.0,
+            ..Solver::default()
+        };
+        solver.resolve(&mut bodies, &mut [collision]);
+        assert_approx_eq!(bodies[0].position, Vector2D::new(-0.3, 0.0));
+        assert_approx_eq!(bodies[1].position, Vector2D::new(1.1, 0.0));
+    }
+}
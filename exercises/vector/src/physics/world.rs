@@ -0,0 +1,639 @@
+use crate::physics::body::Body;
+use crate::physics::collision::{Collider, Manifold, SweepAndPrune, collide};
+use crate::physics::forces::ForceField;
+use crate::physics::solver::{Collision, Solver};
+use crate::vector2d::Vector2D;
+
+// Fixed-timestep simulation. `advance` accumulates real elapsed time and runs
+// whole steps of `timestep`, so results depend only on the number of steps
+// taken, never on the frame rate. Bodies are processed in order and nothing
+// is randomised, which makes runs bit-for-bit reproducible.
+//
+// The accumulator is an f64 so that small steps still register against a
+// large backlog, and one call runs at most `max_steps` steps: after a long
+// stall the simulation drops the backlog instead of trying to catch up in a
+// single frame, which would make that frame slower still.
+//
+// Each step integrates the bodies first and then resolves the collisions
+// between those that have a collider. Contacts that persist from one step to
+// the next keep their impulses, which the solver uses as a starting point.
+
+// Contacts of the same pair that moved less than this between steps are
+// treated as the same contact
+const CONTACT_MATCH_DISTANCE: f32 = 0.05;
+
+const DEFAULT_MAX_STEPS: usize = 120;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Integrator {
+    /// First order; gains energy on oscillators.
+    ExplicitEuler,
+    /// First order and symplectic: velocity first, then position.
+    #[default]
+    SemiImplicitEuler,
+    /// Velocity Verlet, second order and symplectic.
+    Verlet,
+    /// Classic fourth-order Runge-Kutta.
+    Rk4,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Energy {
+    pub kinetic: f32,
+    pub potential: f32,
+}
+
+impl Energy {
+    pub fn total(&self) -> f32 {
+        self.kinetic + self.potential
+    }
+}
+
+pub struct World {
+    pub bodies: Vec<Body>,
+    pub fields: Vec<Box<dyn ForceField>>,
+    pub integrator: Integrator,
+    pub solver: Solver,
+    // indexed like `bodies`, missing entries have no collider
+    colliders: Vec<Option<Collider>>,
+    broad_phase: SweepAndPrune,
+    collisions: Vec<Collision>,
+    timestep: f32,
+    accumulator: f64,
+    max_steps: usize,
+    steps: u64,
+}
+
+impl World {
+    /// Panics if `timestep` is not positive.
+    pub fn new(timestep: f32) -> Self {
+        assert!(timestep > 0.0, "timestep must be positive");
+        World {
+            bodies: Vec::new(),
+            fields: Vec::new(),
+            integrator: Integrator::default(),
+            solver: Solver::default(),
+            colliders: Vec::new(),
+            broad_phase: SweepAndPrune::new(),
+            collisions: Vec::new(),
+            timestep,
+            accumulator: 0.0,
+            max_steps: DEFAULT_MAX_STEPS,
+            steps: 0,
+        }
+    }
+
+    pub fn with_integrator(self, integrator: Integrator) -> Self {
+        World { integrator, ..self }
+    }
+
+    /// Limits the steps one call to `advance` runs, 120 by default.
+    ///
+    /// Panics if `max_steps` is zero.
+    pub fn with_max_steps(self, max_steps: usize) -> Self {
+        assert!(max_steps > 0, "max_steps must be positive");
+        World { max_steps, ..self }
+    }
+
+    /// Returns the index of the body.
+    pub fn add_body(&mut self, body: Body) -> usize {
+        self.bodies.push(body);
+        self.bodies.len() - 1
+    }
+
+    /// Makes the body with index `body` collide with the other bodies that
+    /// have a collider.
+    pub fn set_collider(&mut self, body: usize, collider: Collider) {
+        assert!(body < self.bodies.len(), "no body with index {}", body);
+        if self.colliders.len() <= body {
+            self.colliders.resize(body + 1, None);
+        }
+        self.colliders[body] = Some(collider);
+    }
+
+    pub fn collider(&self, body: usize) -> Option<&Collider> {
+        self.colliders.get(body).and_then(Option::as_ref)
+    }
+
+    /// Collisions resolved during the last step.
+    pub fn collisions(&self) -> &[Collision] {
+        &self.collisions
+    }
+
+    pub fn add_field(&mut self, field: impl ForceField + 'static) {
+        self.fields.push(Box::new(field));
+    }
+
+    pub fn timestep(&self) -> f32 {
+        self.timestep
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Simulated time, `steps * timestep`.
+    pub fn time(&self) -> f64 {
+        self.steps as f64 * self.timestep as f64
+    }
+
+    /// Runs as many whole steps as fit into the accumulated time, up to the
+    /// step limit, and returns how many were taken. The remainder of a step
+    /// carries over to the next call; whole steps beyond the limit are
+    /// dropped.
+    ///
+    /// Panics if `elapsed` is negative or not finite.
+    pub fn advance(&mut self, elapsed: f32) -> usize {
+        assert!(
+            elapsed.is_finite() && elapsed >= 0.0,
+            "elapsed time must be finite and non-negative"
+        );
+        let timestep = self.timestep as f64;
+        self.accumulator += elapsed as f64;
+        let mut taken = 0;
+        while self.accumulator >= timestep && taken < self.max_steps {
+            self.accumulator -= timestep;
+            self.step();
+            taken += 1;
+        }
+        if self.accumulator >= timestep {
+            self.accumulator %= timestep;
+        }
+        taken
+    }
+
+    /// Fraction of a step left in the accumulator, for interpolating rendering.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.timestep as f64) as f32
+    }
+
+    /// Advances every body by exactly one timestep and clears applied forces.
+    pub fn step(&mut self) {
+        let dt = self.timestep;
+        for i in 0..self.bodies.len() {
+            let body = self.bodies[i];
+            let (position, velocity) = self.integrate(&body, dt);
+            let (angle, angular_velocity) = integrate_rotation(&body, self.integrator, dt);
+
+            let body = &mut self.bodies[i];
+            body.position = position;
+            body.velocity = velocity;
+            body.angle = angle;
+            body.angular_velocity = angular_velocity;
+            body.clear_forces();
+        }
+        self.resolve_collisions();
+        self.steps += 1;
+    }
+
+    fn resolve_collisions(&mut self) {
+        let (ids, boxes): (Vec<usize>, Vec<_>) = self
+            .colliders
+            .iter()
+            .zip(&self.bodies)
+            .enumerate()
+            .filter_map(|(i, (collider, body))| Some((i, collider.as_ref()?.aabb(body))))
+            .unzip();
+
+        let previous = std::mem::take(&mut self.collisions);
+        for (i, j) in self.broad_phase.pairs(&boxes) {
+            let (a, b) = (ids[i], ids[j]);
+            let (body_a, body_b) = (&self.bodies[a], &self.bodies[b]);
+            if body_a.is_static() && body_b.is_static() {
+                continue;
+            }
+            let (collider_a, collider_b) = (self.collider(a).unwrap(), self.collider(b).unwrap());
+            if let Some(manifold) = collide(collider_a, body_a, collider_b, body_b) {
+                let mut collision = Collision::new(a, b, manifold);
+                // both lists are sorted by pair
+                if let Ok(k) = previous.binary_search_by_key(&(a, b), |c| (c.a, c.b)) {
+                    collision.impulses = matching_impulses(&previous[k], &collision.manifold);
+                }
+                self.collisions.push(collision);
+            }
+        }
+        self.solver.resolve(&mut self.bodies, &mut self.collisions);
+    }
+
+    fn acceleration(&self, body: &Body, position: Vector2D, velocity: Vector2D) -> Vector2D {
+        if body.is_static() {
+            return Vector2D::ZERO;
+        }
+        let force = self.fields.iter().fold(body.force, |sum, field| {
+            sum + field.force(body, position, velocity)
+        });
+        force * body.inverse_mass()
+    }
+
+    fn integrate(&self, body: &Body, dt: f32) -> (Vector2D, Vector2D) {
+        let (x, v) = (body.position, body.velocity);
+        let a = |x, v| self.acceleration(body, x, v);
+
+        match self.integrator {
+            Integrator::ExplicitEuler => (x + v * dt, v + a(x, v) * dt),
+            Integrator::SemiImplicitEuler => {
+                let v1 = v + a(x, v) * dt;
+                (x + v1 * dt, v1)
+            }
+            Integrator::Verlet => {
+                let a0 = a(x, v);
+                let x1 = x + v * dt + a0 * (0.5 * dt * dt);
+                // velocity-dependent forces see a first-order velocity estimate
+                let a1 = a(x1, v + a0 * dt);
+                (x1, v + (a0 + a1) * (0.5 * dt))
+            }
+            Integrator::Rk4 => {
+                let (k1x, k1v) = (v, a(x, v));
+                let (k2x, k2v) = {
+                    let v2 = v + k1v * (dt / 2.0);
+                    (v2, a(x + k1x * (dt / 2.0), v2))
+                };
+                let (k3x, k3v) = {
+                    let v3 = v + k2v * (dt / 2.0);
+                    (v3, a(x + k2x * (dt / 2.0), v3))
+                };
+                let (k4x, k4v) = {
+                    let v4 = v + k3v * dt;
+                    (v4, a(x + k3x * dt, v4))
+                };
+                (
+                    x + (k1x + k2x * 2.0 + k3x * 2.0 + k4x) * (dt / 6.0),
+                    v + (k1v + k2v * 2.0 + k3v * 2.0 + k4v) * (dt / 6.0),
+                )
+            }
+        }
+    }
+
+    pub fn kinetic_energy(&self) -> f32 {
+        self.bodies.iter().map(Body::kinetic_energy).sum()
+    }
+
+    /// Potential energy of the dynamic bodies in the conservative fields.
+    pub fn potential_energy(&self) -> f32 {
+        self.bodies
+            .iter()
+            .filter(|b| !b.is_static())
+            .flat_map(|b| {
+                self.fields
+                    .iter()
+                    .map(move |f| f.potential_energy(b, b.position))
+            })
+            .sum()
+    }
+
+    pub fn energy(&self) -> Energy {
+        Energy {
+            kinetic: self.kinetic_energy(),
+            potential: self.potential_energy(),
+        }
+    }
+
+    pub fn momentum(&self) -> Vector2D {
+        self.bodies.iter().map(Body::momentum).sum()
+    }
+}
+
+fn matching_impulses(previous: &Collision, manifold: &Manifold) -> Vec<(f32, f32)> {
+    manifold
+        .contacts
+        .iter()
+        .map(|contact| {
+            previous
+                .manifold
+                .contacts
+                .iter()
+                .zip(&previous.impulses)
+                .find(|(old, _)| old.point.distance(&contact.point) < CONTACT_MATCH_DISTANCE)
+                .map_or((0.0, 0.0), |(_, impulse)| *impulse)
+        })
+        .collect()
+}
+
+// Torque is constant over a step, so the higher-order schemes are exact
+fn integrate_rotation(body: &Body, integrator: Integrator, dt: f32) -> (f32, f32) {
+    let (angle, omega) = (body.angle, body.angular_velocity);
+    let alpha = body.torque * body.inverse_inertia();
+    match integrator {
+        Integrator::ExplicitEuler => (angle + omega * dt, omega + alpha * dt),
+        Integrator::SemiImplicitEuler => {
+            let omega = omega + alpha * dt;
+            (angle + omega * dt, omega)
+        }
+        Integrator::Verlet | Integrator::Rk4 => (
+            angle + omega * dt + 0.5 * alpha * dt * dt,
+            omega + alpha * dt,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests_world {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::physics::collision::Collider;
+    use crate::physics::forces::{Drag, Gravity, Spring};
+
+    const ALL: [Integrator; 4] = [
+        Integrator::ExplicitEuler,
+        Integrator::SemiImplicitEuler,
+        Integrator::Verlet,
+        Integrator::Rk4,
+    ];
+
+    // unit mass on a unit spring: period 2 pi, energy 0.5
+    fn oscillator(integrator: Integrator, dt: f32) -> World {
+        let mut world = World::new(dt).with_integrator(integrator);
+        world.add_body(Body::new(Vector2D::new(1.0, 0.0), 1.0));
+        world.add_field(Spring {
+            anchor: Vector2D::ZERO,
+            stiffness: 1.0,
+            rest_length: 0.0,
+        });
+        world
+    }
+
+    fn projectile(integrator: Integrator) -> World {
+        let mut world = World::new(0.01).with_integrator(integrator);
+        world.add_body(Body::new(Vector2D::ZERO, 2.0).with_velocity(Vector2D::new(3.0, 10.0)));
+        world.add_field(Gravity(Vector2D::new(0.0, -10.0)));
+        world
+    }
+
+    #[test]
+    fn constant_force_is_exact_for_second_order_schemes() {
+        for integrator in [Integrator::Verlet, Integrator::Rk4] {
+            let mut world = projectile(integrator);
+            for _ in 0..100 {
+                world.step();
+            }
+            // x = v t + g t^2 / 2 at t = 1
+            let body = world.bodies[0];
+            assert_approx_eq!(body.position, Vector2D::new(3.0, 5.0), epsilon = 1e-4);
+            assert_approx_eq!(body.velocity, Vector2D::new(3.0, 0.0), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn first_order_schemes_bracket_the_projectile() {
+        let mut explicit = projectile(Integrator::ExplicitEuler);
+        let mut semi = projectile(Integrator::SemiImplicitEuler);
+        for _ in 0..100 {
+            explicit.step();
+            semi.step();
+        }
+        // O(dt) errors of opposite sign: g t dt / 2 = 0.05
+        assert_approx_eq!(explicit.bodies[0].position.y, 5.05, epsilon = 1e-3);
+        assert_approx_eq!(semi.bodies[0].position.y, 4.95, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn energy_behaviour_on_an_oscillator() {
+        let mut drift = Vec::new();
+        for integrator in ALL {
+            let mut world = oscillator(integrator, 0.05);
+            let initial = world.energy().total();
+            assert_eq!(initial, 0.5);
+            let mut worst: f32 = 0.0;
+            // about 16 periods
+            for _ in 0..2000 {
+                world.step();
+                worst = worst.max((world.energy().total() - initial).abs());
+            }
+            drift.push(worst);
+        }
+
+        let [explicit, semi, verlet, rk4] = drift[..] else {
+            unreachable!()
+        };
+        assert!(
+            explicit > 0.5,
+            "explicit Euler should gain energy, drift {}",
+            explicit
+        );
+        assert!(semi < 0.02, "semi-implicit drift {}", semi);
+        assert!(verlet < 0.001, "Verlet drift {}", verlet);
+        assert!(rk4 < 0.001, "RK4 drift {}", rk4);
+    }
+
+    #[test]
+    fn rk4_tracks_the_analytic_oscillator() {
+        let mut world = oscillator(Integrator::Rk4, 0.01);
+        for _ in 0..314 {
+            world.step();
+        }
+        let t = world.time() as f32;
+        assert_approx_eq!(
+            world.bodies[0].position,
+            Vector2D::new(t.cos(), 0.0),
+            epsilon = 1e-4
+        );
+        assert_approx_eq!(
+            world.bodies[0].velocity,
+            Vector2D::new(-t.sin(), 0.0),
+            epsilon = 1e-4
+        );
+    }
+
+    #[test]
+    fn drag_dissipates_energy() {
+        for integrator in ALL {
+            let mut world = oscillator(integrator, 0.01);
+            world.add_field(Drag(0.5));
+            for _ in 0..1000 {
+                world.step();
+            }
+            assert!(world.energy().total() < 0.01, "{:?}", integrator);
+        }
+    }
+
+    #[test]
+    fn static_bodies_stay_put() {
+        let mut world = projectile(Integrator::Rk4);
+        let ground = world.add_body(Body::fixed(Vector2D::new(0.0, -1.0)));
+        world.bodies[ground].apply_force(Vector2D::new(100.0, 0.0));
+        world.step();
+        assert_eq!(world.bodies[ground].position, Vector2D::new(0.0, -1.0));
+        // the ground contributes no energy, even in the gravity field
+        let mut alone = projectile(Integrator::Rk4);
+        alone.step();
+        assert_eq!(world.energy(), alone.energy());
+    }
+
+    #[test]
+    fn applied_forces_last_one_step() {
+        let mut world = World::new(0.5);
+        world.add_body(Body::new(Vector2D::ZERO, 2.0).with_inertia(1.0));
+        world.bodies[0].apply_force_at(Vector2D::new(4.0, 0.0), Vector2D::new(0.0, -1.0));
+        world.step();
+        assert_eq!(world.bodies[0].velocity, Vector2D::new(1.0, 0.0));
+        assert_eq!(world.bodies[0].angular_velocity, 2.0);
+        assert_eq!(world.bodies[0].force, Vector2D::ZERO);
+
+        world.step();
+        assert_eq!(world.bodies[0].velocity, Vector2D::new(1.0, 0.0));
+        assert_eq!(world.bodies[0].angular_velocity, 2.0);
+        assert_eq!(world.bodies[0].angle, 1.0 + 1.0);
+    }
+
+    #[test]
+    fn fixed_timestep_is_independent_of_frame_length() {
+        let mut coarse = oscillator(Integrator::Verlet, 1.0 / 64.0);
+        let mut fine = oscillator(Integrator::Verlet, 1.0 / 64.0);
+
+        assert_eq!(coarse.advance(0.5), 32);
+        for _ in 0..32 {
+            fine.advance(1.0 / 64.0);
+        }
+        assert_eq!(coarse.steps(), fine.steps());
+        assert_eq!(coarse.bodies, fine.bodies);
+
+        assert_eq!(coarse.advance(1.0 / 128.0), 0);
+        assert_eq!(coarse.alpha(), 0.5);
+        assert_eq!(coarse.advance(1.0 / 128.0), 1);
+        assert_eq!(coarse.alpha(), 0.0);
+    }
+
+    #[test]
+    fn long_stalls_are_capped() {
+        let mut world = oscillator(Integrator::Verlet, 1.0 / 60.0).with_max_steps(10);
+        assert_eq!(world.advance(1e6), 10);
+        assert!((0.0..1.0).contains(&world.alpha()));
+        assert_eq!(world.advance(0.0), 0);
+
+        // a large backlog in f32 would swallow further steps
+        let mut world = oscillator(Integrator::Verlet, 1.0 / 60.0);
+        world.advance(3e5);
+        assert_eq!(world.advance(1.0), 60);
+    }
+
+    #[test]
+    #[should_panic(expected = "elapsed time must be finite and non-negative")]
+    fn nan_elapsed_time_is_rejected() {
+        oscillator(Integrator::Verlet, 0.01).advance(f32::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "elapsed time must be finite and non-negative")]
+    fn negative_elapsed_time_is_rejected() {
+        oscillator(Integrator::Verlet, 0.01).advance(-0.5);
+    }
+
+    #[test]
+    fn runs_are_deterministic() {
+        let run = || {
+            let mut world = World::new(1.0 / 120.0).with_integrator(Integrator::Rk4);
+            for i in 0..10 {
+                world.add_body(
+                    Body::new(Vector2D::new(i as f32, 0.0), 1.0 + i as f32)
+                        .with_velocity(Vector2D::new(0.3 * i as f32, 1.0)),
+                );
+            }
+            world.add_field(Gravity(Vector2D::new(0.0, -9.81)));
+            world.add_field(Spring {
+                anchor: Vector2D::new(5.0, 5.0),
+                stiffness: 3.0,
+                rest_length: 2.0,
+            });
+            world.add_field(Drag(0.1));
+            for _ in 0..1000 {
+                world.step();
+            }
+            world.bodies
+        };
+        let (a, b) = (run(), run());
+        for (a, b) in a.iter().zip(&b) {
+            assert_eq!(a.position.x.to_bits(), b.position.x.to_bits());
+            assert_eq!(a.position.y.to_bits(), b.position.y.to_bits());
+            assert_eq!(a.velocity.x.to_bits(), b.velocity.x.to_bits());
+            assert_eq!(a.velocity.y.to_bits(), b.velocity.y.to_bits());
+        }
+    }
+
+    #[test]
+    fn momentum_is_the_sum_over_bodies() {
+        let mut world = World::new(0.1);
+        world.add_body(Body::new(Vector2D::ZERO, 2.0).with_velocity(Vector2D::new(1.0, 0.0)));
+        world.add_body(Body::new(Vector2D::ZERO, 1.0).with_velocity(Vector2D::new(0.0, 3.0)));
+        world.add_body(Body::fixed(Vector2D::ZERO));
+        assert_eq!(world.momentum(), Vector2D::new(2.0, 3.0));
+    }
+
+    // ground whose top surface is y = 0
+    fn with_ground(timestep: f32) -> World {
+        let mut world = World::new(timestep);
+        world.add_field(Gravity(Vector2D::new(0.0, -10.0)));
+        let ground = world.add_body(Body::fixed(Vector2D::new(0.0, -0.5)));
+        world.set_collider(ground, Collider::rectangle(20.0, 1.0));
+        world
+    }
+
+    #[test]
+    fn boxes_stack_and_come_to_rest() {
+        let mut world = with_ground(1.0 / 60.0);
+        let crate_box = Collider::rectangle(1.0, 1.0);
+        for i in 0..5 {
+            let body = Body::new(Vector2D::new(0.0, 0.5 + 1.05 * i as f32), 1.0)
+                .with_inertia(crate_box.moment_of_inertia(1.0));
+            let id = world.add_body(body);
+            world.set_collider(id, crate_box.clone());
+        }
+
+        for _ in 0..300 {
+            world.step();
+        }
+        for (i, body) in world.bodies[1..].iter().enumerate() {
+            assert!(body.position.x.abs() < 0.01, "box {}: {:?}", i, body);
+            assert!(
+                (body.position.y - (0.5 + i as f32)).abs() < 0.05,
+                "box {}: {:?}",
+                i,
+                body
+            );
+            assert!(body.angle.abs() < 0.01, "box {}: {:?}", i, body);
+            assert!(body.velocity.length() < 0.05, "box {}: {:?}", i, body);
+        }
+        // ground and box, then box on box
+        assert_eq!(world.collisions().len(), 5);
+        assert!(
+            world
+                .collisions()
+                .iter()
+                .all(|c| c.manifold.contacts.len() == 2)
+        );
+    }
+
+    #[test]
+    fn balls_bounce_with_restitution() {
+        let mut world = with_ground(1.0 / 120.0);
+        world.solver.restitution = 0.8;
+        let ball = world.add_body(Body::new(Vector2D::new(0.0, 5.5), 1.0));
+        world.set_collider(ball, Collider::circle(0.5));
+
+        // falls for about a second, then bounces back up
+        let mut bounced = false;
+        let mut peak = 0.0f32;
+        for _ in 0..360 {
+            world.step();
+            let body = world.bodies[ball];
+            bounced |= body.velocity.y > 0.0;
+            if bounced {
+                peak = peak.max(body.position.y - 0.5);
+            }
+        }
+        // 0.8^2 of the 5 m drop
+        assert!(bounced);
+        assert!((peak - 3.2).abs() < 0.2, "peak {}", peak);
+    }
+
+    #[test]
+    fn bodies_without_colliders_pass_through() {
+        let mut world = with_ground(1.0 / 60.0);
+        let ghost = world.add_body(Body::new(Vector2D::new(0.0, 1.0), 1.0));
+        for _ in 0..60 {
+            world.step();
+        }
+        assert!(world.bodies[ghost].position.y < -2.0);
+        assert!(world.collisions().is_empty());
+        assert!(world.collider(ghost).is_none());
+    }
+}
@@ -0,0 +1,322 @@
+use crate::scalar::{Real, Scalar};
+use crate::vector2d::Vector2D;
+
+// Positions as distinct from displacements (an affine space over Vector2D).
+//
+// Only the operations that make sense for positions exist: the difference
+// of two points is the vector between them, a point moved by a vector is a
+// point, and points cannot be added or scaled. Combinations of points go
+// through weights that are normalized to sum to one, so the result does not
+// depend on where the origin is.
+
+/// Position in the plane; see `Vector2D` for displacements.
+///
+/// Adding two points does not compile:
+///
+/// ```compile_fail
+/// use vector::point2d::Point2D;
+///
+/// let p = Point2D::new(1.0, 2.0) + Point2D::new(3.0, 4.0);
+/// ```
+#[derive(Default, Copy, Clone, PartialEq, Debug)]
+pub struct Point2D<T = f32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Scalar> Point2D<T> {
+    pub const ORIGIN: Point2D<T> = Point2D {
+        x: T::ZERO,
+        y: T::ZERO,
+    };
+
+    pub fn new(x: T, y: T) -> Self {
+        Point2D { x, y }
+    }
+
+    /// The point at `v` from the origin.
+    pub fn from_vector(v: Vector2D<T>) -> Self {
+        Point2D { x: v.x, y: v.y }
+    }
+
+    /// The vector from the origin to `self`.
+    pub fn to_vector(self) -> Vector2D<T> {
+        Vector2D::new(self.x, self.y)
+    }
+
+    pub fn distance_squared(&self, other: &Self) -> T {
+        (*other - *self).length_squared()
+    }
+
+    pub fn midpoint(&self, other: &Self) -> Self {
+        *self + (*other - *self) / (T::ONE + T::ONE)
+    }
+
+    /// Linear interpolation: `t = 0` gives `self`, `t = 1` gives `other`.
+    /// `t` is not clamped, so values outside [0, 1] extrapolate.
+    pub fn lerp(&self, other: &Self, t: T) -> Self {
+        *self + (*other - *self) * t
+    }
+
+    /// Weighted average of `points`. The weights are divided by their sum,
+    /// so they need not add up to one; `None` when they sum to zero or the
+    /// slices differ in length.
+    pub fn barycentric(points: &[Self], weights: &[T]) -> Option<Self> {
+        if points.len() != weights.len() {
+            return None;
+        }
+        let total = weights.iter().fold(T::ZERO, |sum, w| sum + *w);
+        if total == T::ZERO {
+            return None;
+        }
+        // offsets from the first point stay small for nearby points far
+        // from the origin
+        let first = points[0];
+        let offset = points
+            .iter()
+            .zip(weights)
+            .map(|(p, w)| (*p - first) * *w)
+            .fold(Vector2D::ZERO, |sum, v| sum + v);
+        Some(first + offset / total)
+    }
+
+    /// Weights `[u, v, w]` with `u * a + v * b + w * c == self` and
+    /// `u + v + w == 1`, `None` for degenerate triangles. All three are
+    /// non-negative exactly when `self` is in the triangle.
+    pub fn barycentric_coordinates(&self, a: &Self, b: &Self, c: &Self) -> Option<[T; 3]> {
+        let (ab, ac, ap) = (*b - *a, *c - *a, *self - *a);
+        let area = ab.perp_dot(&ac);
+        if area == T::ZERO {
+            return None;
+        }
+        let v = ap.perp_dot(&ac) / area;
+        let w = ab.perp_dot(&ap) / area;
+        Some([T::ONE - v - w, v, w])
+    }
+
+    /// Lossless conversion to another scalar type.
+    pub fn cast<U: Scalar + From<T>>(self) -> Point2D<U> {
+        Point2D::from_vector(self.to_vector().cast())
+    }
+
+    /// Conversion through f64 with `as` semantics, see `Vector2D::cast_lossy`.
+    pub fn cast_lossy<U: Scalar>(self) -> Point2D<U> {
+        Point2D::from_vector(self.to_vector().cast_lossy())
+    }
+}
+
+impl<T: Real> Point2D<T> {
+    pub fn distance(&self, other: &Self) -> T {
+        self.distance_squared(other).sqrt()
+    }
+}
+
+impl<T: Scalar> std::ops::Add<Vector2D<T>> for Point2D<T> {
+    type Output = Self;
+
+    fn add(self, v: Vector2D<T>) -> Self::Output {
+        Point2D {
+            x: self.x + v.x,
+            y: self.y + v.y,
+        }
+    }
+}
+
+impl<T: Scalar> std::ops::Sub<Vector2D<T>> for Point2D<T> {
+    type Output = Self;
+
+    fn sub(self, v: Vector2D<T>) -> Self::Output {
+        Point2D {
+            x: self.x - v.x,
+            y: self.y - v.y,
+        }
+    }
+}
+
+// Point - Point is the vector from `other` to `self`
+impl<T: Scalar> std::ops::Sub for Point2D<T> {
+    type Output = Vector2D<T>;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Vector2D::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Scalar> std::ops::AddAssign<Vector2D<T>> for Point2D<T> {
+    fn add_assign(&mut self, v: Vector2D<T>) {
+        self.x += v.x;
+        self.y += v.y;
+    }
+}
+
+impl<T: Scalar> std::ops::SubAssign<Vector2D<T>> for Point2D<T> {
+    fn sub_assign(&mut self, v: Vector2D<T>) {
+        self.x -= v.x;
+        self.y -= v.y;
+    }
+}
+
+impl<T> std::ops::Index<usize> for Point2D<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("index out of range for Point2D: {}", index),
+        }
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for Point2D<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("index out of range for Point2D: {}", index),
+        }
+    }
+}
+
+impl<T> From<Vector2D<T>> for Point2D<T> {
+    fn from(v: Vector2D<T>) -> Self {
+        Point2D { x: v.x, y: v.y }
+    }
+}
+
+impl<T> From<Point2D<T>> for Vector2D<T> {
+    fn from(p: Point2D<T>) -> Self {
+        Vector2D { x: p.x, y: p.y }
+    }
+}
+
+impl<T> From<(T, T)> for Point2D<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2D { x, y }
+    }
+}
+
+impl<T> From<[T; 2]> for Point2D<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Point2D { x, y }
+    }
+}
+
+impl<T> From<Point2D<T>> for (T, T) {
+    fn from(p: Point2D<T>) -> Self {
+        (p.x, p.y)
+    }
+}
+
+impl<T> From<Point2D<T>> for [T; 2] {
+    fn from(p: Point2D<T>) -> Self {
+        [p.x, p.y]
+    }
+}
+
+#[cfg(test)]
+mod tests_point2d {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    fn p(x: f32, y: f32) -> Point2D {
+        Point2D::new(x, y)
+    }
+
+    #[test]
+    fn affine_operations() {
+        let (a, b) = (p(1.0, 2.0), p(4.0, 6.0));
+        let d: Vector2D = b - a;
+        assert_eq!(d, Vector2D::new(3.0, 4.0));
+        assert_eq!(a + d, b);
+        assert_eq!(b - d, a);
+
+        let mut c = a;
+        c += Vector2D::new(1.0, 1.0);
+        assert_eq!(c, p(2.0, 3.0));
+        c -= Vector2D::new(2.0, 3.0);
+        assert_eq!(c, Point2D::ORIGIN);
+        assert_eq!(Point2D::<f32>::default(), Point2D::ORIGIN);
+    }
+
+    #[test]
+    fn distances_and_interpolation() {
+        let (a, b) = (p(1.0, 2.0), p(4.0, 6.0));
+        assert_eq!(a.distance(&b), 5.0);
+        assert_eq!(a.distance_squared(&b), 25.0);
+        assert_eq!(a.midpoint(&b), p(2.5, 4.0));
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 2.0), p(7.0, 10.0));
+
+        // integer points round the midpoint towards the first point
+        let (i, j) = (Point2D::new(0i32, 0), Point2D::new(3i32, -3));
+        assert_eq!(i.midpoint(&j), Point2D::new(1, -1));
+        assert_eq!(i.distance_squared(&j), 18);
+    }
+
+    #[test]
+    fn barycentric_combinations() {
+        let triangle = [p(0.0, 0.0), p(4.0, 0.0), p(0.0, 4.0)];
+        let centroid = Point2D::barycentric(&triangle, &[1.0, 1.0, 1.0]).unwrap();
+        assert_approx_eq!(centroid.x, 4.0 / 3.0);
+        assert_approx_eq!(centroid.y, 4.0 / 3.0);
+        assert_eq!(
+            Point2D::barycentric(&triangle, &[0.0, 2.0, 0.0]),
+            Some(triangle[1])
+        );
+        assert_eq!(Point2D::barycentric(&triangle, &[1.0, -1.0, 0.0]), None);
+        assert_eq!(Point2D::barycentric(&triangle, &[1.0]), None);
+        assert_eq!(Point2D::barycentric(&[], &[]), None::<Point2D>);
+
+        // far from the origin the combination is as accurate as near it
+        let shifted: Vec<Point2D> = triangle
+            .iter()
+            .map(|q| *q + Vector2D::new(1e6, 1e6))
+            .collect();
+        let far = Point2D::barycentric(&shifted, &[1.0, 1.0, 2.0]).unwrap();
+        assert_eq!(far - shifted[0], Vector2D::new(1.0, 2.0));
+
+        let [u, v, w] = centroid
+            .barycentric_coordinates(&triangle[0], &triangle[1], &triangle[2])
+            .unwrap();
+        assert_approx_eq!(u, 1.0 / 3.0);
+        assert_approx_eq!(v, 1.0 / 3.0);
+        assert_approx_eq!(w, 1.0 / 3.0);
+
+        let outside = p(5.0, 1.0);
+        let weights = outside
+            .barycentric_coordinates(&triangle[0], &triangle[1], &triangle[2])
+            .unwrap();
+        assert!(weights.iter().any(|w| *w < 0.0));
+        assert_eq!(Point2D::barycentric(&triangle, &weights), Some(outside));
+
+        let line = [p(0.0, 0.0), p(1.0, 1.0), p(2.0, 2.0)];
+        assert_eq!(
+            outside.barycentric_coordinates(&line[0], &line[1], &line[2]),
+            None
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let q = p(1.5, -2.0);
+        assert_eq!(q.to_vector(), Vector2D::new(1.5, -2.0));
+        assert_eq!(Point2D::from_vector(q.to_vector()), q);
+        assert_eq!(Vector2D::from(q), Vector2D::new(1.5, -2.0));
+        assert_eq!(Point2D::from(Vector2D::new(1.5, -2.0)), q);
+        assert_eq!(q - Point2D::ORIGIN, q.to_vector());
+
+        assert_eq!(Point2D::from((1, 2)), Point2D::new(1, 2));
+        assert_eq!(Point2D::from([1, 2]), Point2D::new(1, 2));
+        assert_eq!(<(i32, i32)>::from(Point2D::new(1, 2)), (1, 2));
+        assert_eq!(<[i32; 2]>::from(Point2D::new(1, 2)), [1, 2]);
+
+        assert_eq!(Point2D::new(3i16, -4).cast::<f32>(), p(3.0, -4.0));
+        assert_eq!(q.cast_lossy::<i32>(), Point2D::new(1, -2));
+        assert_eq!((q[0], q[1]), (1.5, -2.0));
+        let mut r = q;
+        r[1] = 7.0;
+        assert_eq!(r, p(1.5, 7.0));
+    }
+}
@@ -0,0 +1,495 @@
+use crate::geometry::shapes::Aabb;
+use crate::matrix::Mat2;
+use crate::spatial::SpatialIndex;
+use crate::spatial::kdtree::KdTree;
+use crate::vector2d::Vector2D;
+
+// Statistics and clustering for unordered sets of points.
+//
+// Sums run in f64 over offsets from a reference point (the first point or
+// the centroid), so clouds far from the origin are summarized as accurately
+// as clouds around it. Nothing here depends on hash order or the clock:
+// k-means takes an explicit seed, and DBSCAN and outlier removal visit the
+// points in input order, so the same input always gives the same output.
+
+/// Mean of the points, `None` for no points.
+pub fn centroid(points: &[Vector2D]) -> Option<Vector2D> {
+    let first = *points.first()?;
+    let (mut x, mut y) = (0.0f64, 0.0f64);
+    for p in points {
+        x += (p.x - first.x) as f64;
+        y += (p.y - first.y) as f64;
+    }
+    let n = points.len() as f64;
+    Some(first + Vector2D::new((x / n) as f32, (y / n) as f32))
+}
+
+pub fn bounding_box(points: &[Vector2D]) -> Option<Aabb> {
+    Aabb::from_points(points.iter().copied())
+}
+
+/// Population covariance (divided by n, not n - 1) as the symmetric matrix
+/// [[var x, cov xy], [cov xy, var y]], `None` for no points.
+pub fn covariance(points: &[Vector2D]) -> Option<Mat2> {
+    let mean = centroid(points)?;
+    let (mut xx, mut xy, mut yy) = (0.0f64, 0.0f64, 0.0f64);
+    for p in points {
+        let (dx, dy) = ((p.x - mean.x) as f64, (p.y - mean.y) as f64);
+        xx += dx * dx;
+        xy += dx * dy;
+        yy += dy * dy;
+    }
+    let n = points.len() as f64;
+    let (xx, xy, yy) = ((xx / n) as f32, (xy / n) as f32, (yy / n) as f32);
+    Some(Mat2::new(xx, xy, xy, yy))
+}
+
+/// Directions of greatest and least spread of a point cloud.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PrincipalAxes {
+    pub centroid: Vector2D,
+    /// Unit vector along the direction of largest variance, with an angle
+    /// in (-pi/2, pi/2].
+    pub major: Vector2D,
+    /// `major` turned counter-clockwise by a right angle.
+    pub minor: Vector2D,
+    /// Variance along `major` and along `minor`, largest first.
+    pub variances: [f32; 2],
+}
+
+/// Eigen-decomposition of the covariance matrix, `None` for no points.
+/// When the spread is the same in every direction, `major` is the x axis.
+pub fn principal_axes(points: &[Vector2D]) -> Option<PrincipalAxes> {
+    let m = covariance(points)?.m;
+    let (a, b, c) = (m[0][0], m[0][1], m[1][1]);
+    // for a symmetric 2x2 matrix the eigenvalues are mean +- radius and the
+    // first eigenvector lies at half the angle of (a - c, 2b)
+    let mean = (a + c) / 2.0;
+    let radius = ((a - c) / 2.0).hypot(b);
+    let angle = (2.0 * b).atan2(a - c) / 2.0;
+    let major = Vector2D::from_polar(1.0, angle);
+    Some(PrincipalAxes {
+        centroid: centroid(points)?,
+        major,
+        minor: major.perpendicular(),
+        variances: [mean + radius, (mean - radius).max(0.0)],
+    })
+}
+
+/// Statistical outlier removal: keeps the points whose mean distance to
+/// their `k` nearest neighbours is at most `std_ratio` standard deviations
+/// above the average of that mean over all points. Kept points stay in
+/// input order; with fewer than `k + 1` points nothing is removed.
+///
+/// Panics if a point is not finite.
+pub fn remove_outliers(points: &[Vector2D], k: usize, std_ratio: f32) -> Vec<Vector2D> {
+    if k == 0 || points.len() <= k {
+        return points.to_vec();
+    }
+    let tree = KdTree::from_entries(points.iter().map(|p| (*p, ())).collect());
+    let mean_distances: Vec<f64> = points
+        .iter()
+        .map(|p| {
+            // the nearest entry is the point itself at distance zero
+            let nearest = tree.nearest(*p, k + 1);
+            let total: f64 = nearest.iter().map(|(q, _)| p.distance(q) as f64).sum();
+            total / k as f64
+        })
+        .collect();
+
+    let n = points.len() as f64;
+    let mean = mean_distances.iter().sum::<f64>() / n;
+    let variance = mean_distances
+        .iter()
+        .map(|d| (d - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    let threshold = mean + std_ratio as f64 * variance.sqrt();
+    points
+        .iter()
+        .zip(&mean_distances)
+        .filter(|(_, d)| **d <= threshold)
+        .map(|(p, _)| *p)
+        .collect()
+}
+
+/// Result of `kmeans`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct KMeans {
+    pub centroids: Vec<Vector2D>,
+    /// Index into `centroids` for every input point.
+    pub assignments: Vec<usize>,
+    /// Sum of squared distances from the points to their centroids.
+    pub inertia: f32,
+    pub iterations: usize,
+}
+
+const KMEANS_MAX_ITERATIONS: usize = 300;
+
+/// Lloyd's algorithm with k-means++ seeding driven by `seed`. Stops when no
+/// assignment changes, or after 300 iterations. A cluster that ends up
+/// empty takes over the point farthest from its centroid. `None` when `k`
+/// is zero or larger than the number of points.
+pub fn kmeans(points: &[Vector2D], k: usize, seed: u64) -> Option<KMeans> {
+    if k == 0 || k > points.len() {
+        return None;
+    }
+    let mut random = Xorshift::new(seed);
+    let mut centroids = vec![points[random.below(points.len())]];
+    let mut closest: Vec<f64> = points
+        .iter()
+        .map(|p| p.distance_squared(&centroids[0]) as f64)
+        .collect();
+    while centroids.len() < k {
+        // the next centroid is drawn with probability proportional to the
+        // squared distance to the nearest centroid so far
+        let total: f64 = closest.iter().sum();
+        let next = if total > 0.0 {
+            let mut target = random.unit() * total;
+            closest
+                .iter()
+                .position(|d| {
+                    target -= d;
+                    target < 0.0
+                })
+                .unwrap_or_else(|| closest.iter().rposition(|d| *d > 0.0).unwrap())
+        } else {
+            random.below(points.len())
+        };
+        centroids.push(points[next]);
+        for (d, p) in closest.iter_mut().zip(points) {
+            *d = d.min(p.distance_squared(&points[next]) as f64);
+        }
+    }
+
+    let nearest = |centroids: &[Vector2D], p: &Vector2D| {
+        (0..centroids.len())
+            .min_by(|i, j| {
+                p.distance_squared(&centroids[*i])
+                    .total_cmp(&p.distance_squared(&centroids[*j]))
+            })
+            .unwrap()
+    };
+    let mut assignments = vec![usize::MAX; points.len()];
+    let mut iterations = 0;
+    while iterations < KMEANS_MAX_ITERATIONS {
+        iterations += 1;
+        let mut changed = false;
+        for (assignment, p) in assignments.iter_mut().zip(points) {
+            let best = nearest(&centroids, p);
+            changed |= *assignment != best;
+            *assignment = best;
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![(0.0f64, 0.0f64, 0usize); k];
+        for (p, cluster) in points.iter().zip(&assignments) {
+            let sum = &mut sums[*cluster];
+            let offset = *p - centroids[*cluster];
+            sum.0 += offset.x as f64;
+            sum.1 += offset.y as f64;
+            sum.2 += 1;
+        }
+        for (cluster, (x, y, count)) in sums.into_iter().enumerate() {
+            if count > 0 {
+                let n = count as f64;
+                centroids[cluster] += Vector2D::new((x / n) as f32, (y / n) as f32);
+            }
+        }
+        for cluster in 0..k {
+            if assignments.contains(&cluster) {
+                continue;
+            }
+            let farthest = (0..points.len())
+                .max_by(|i, j| {
+                    let di = points[*i].distance_squared(&centroids[assignments[*i]]);
+                    let dj = points[*j].distance_squared(&centroids[assignments[*j]]);
+                    di.total_cmp(&dj)
+                })
+                .unwrap();
+            centroids[cluster] = points[farthest];
+            assignments[farthest] = cluster;
+        }
+    }
+
+    let inertia = points
+        .iter()
+        .zip(&assignments)
+        .map(|(p, cluster)| p.distance_squared(&centroids[*cluster]) as f64)
+        .sum::<f64>() as f32;
+    Some(KMeans {
+        centroids,
+        assignments,
+        inertia,
+        iterations,
+    })
+}
+
+/// Density-based clustering. Points with at least `min_points` points
+/// (themselves included) within `epsilon` are core points; clusters are the
+/// core points connected through such neighbourhoods plus the points within
+/// `epsilon` of them. Returns a cluster index for every point, numbered in
+/// order of discovery, or `None` for noise. A border point within reach of
+/// several clusters joins the first one found. A negative `epsilon` makes
+/// every point noise.
+///
+/// Panics if a point is not finite.
+pub fn dbscan(points: &[Vector2D], epsilon: f32, min_points: usize) -> Vec<Option<usize>> {
+    let tree = KdTree::from_entries(points.iter().copied().zip(0..).collect());
+    let neighbours = |p: Vector2D| {
+        let mut found: Vec<usize> = tree
+            .within_radius(p, epsilon)
+            .into_iter()
+            .map(|(_, i)| *i)
+            .collect();
+        // the tree returns them in no particular order
+        found.sort_unstable();
+        found
+    };
+
+    let mut labels = vec![None; points.len()];
+    let mut visited = vec![false; points.len()];
+    let mut clusters = 0;
+    for start in 0..points.len() {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let around = neighbours(points[start]);
+        if around.len() < min_points {
+            continue;
+        }
+
+        let cluster = clusters;
+        clusters += 1;
+        labels[start] = Some(cluster);
+        // points are marked visited when queued, so each is queued once and
+        // the queue never outgrows the point set
+        let mut queue = Vec::new();
+        let mut reach = |around: Vec<usize>, queue: &mut Vec<usize>| {
+            for j in around {
+                labels[j].get_or_insert(cluster);
+                if !visited[j] {
+                    visited[j] = true;
+                    queue.push(j);
+                }
+            }
+        };
+        reach(around, &mut queue);
+        while let Some(i) = queue.pop() {
+            let around = neighbours(points[i]);
+            if around.len() >= min_points {
+                reach(around, &mut queue);
+            }
+        }
+    }
+    labels
+}
+
+// Small xorshift64 generator; quality is ample for picking seeds
+struct Xorshift {
+    state: u64,
+}
+
+impl Xorshift {
+    fn new(seed: u64) -> Self {
+        // the all-zero state would only ever produce zeros
+        Xorshift { state: seed.max(1) }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Uniform in [0, 1).
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.unit() * n as f64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests_pointcloud {
+    use super::*;
+    use crate::assert_approx_eq;
+    use crate::testing::random_points;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    // `count` points spread over a 5x5 square around `center`
+    fn blob(center: Vector2D, count: usize, seed: u64) -> Vec<Vector2D> {
+        random_points(count, seed)
+            .into_iter()
+            .map(|p| center + (p - v(50.0, 50.0)) * 0.05)
+            .collect()
+    }
+
+    #[test]
+    fn summary_statistics() {
+        let points = [v(0.0, 0.0), v(4.0, 0.0), v(4.0, 2.0), v(0.0, 2.0)];
+        assert_eq!(centroid(&points), Some(v(2.0, 1.0)));
+        assert_eq!(
+            bounding_box(&points),
+            Some(Aabb::new(v(0.0, 0.0), v(4.0, 2.0)))
+        );
+        assert_eq!(covariance(&points), Some(Mat2::new(4.0, 0.0, 0.0, 1.0)));
+
+        let diagonal = [v(0.0, 0.0), v(1.0, 1.0), v(2.0, 2.0)];
+        let m = covariance(&diagonal).unwrap();
+        assert_approx_eq!(m.m[0][0], 2.0 / 3.0);
+        assert_approx_eq!(m.m[0][1], 2.0 / 3.0);
+        assert_eq!(m.m[0][1], m.m[1][0]);
+
+        assert_eq!(centroid(&[]), None);
+        assert_eq!(bounding_box(&[]), None);
+        assert_eq!(covariance(&[]), None);
+        assert_eq!(covariance(&[v(3.0, 4.0)]), Some(Mat2::ZERO));
+    }
+
+    #[test]
+    fn statistics_far_from_origin() {
+        let offset = v(1e6, -1e6);
+        let points: Vec<Vector2D> = random_points(1000, 5)
+            .into_iter()
+            .map(|p| p * 0.01)
+            .collect();
+        let shifted: Vec<Vector2D> = points.iter().map(|p| *p + offset).collect();
+        assert_approx_eq!(
+            centroid(&shifted).unwrap() - offset,
+            centroid(&points).unwrap(),
+            epsilon = 0.07
+        );
+        let (near, far) = (covariance(&points).unwrap(), covariance(&shifted).unwrap());
+        for (row, far_row) in near.m.iter().zip(&far.m) {
+            for (a, b) in row.iter().zip(far_row) {
+                assert_approx_eq!(*a, *b, epsilon = 1e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn principal_axes_follow_the_spread() {
+        let direction = Vector2D::from_polar(1.0, 0.5);
+        let points: Vec<Vector2D> = random_points(500, 9)
+            .into_iter()
+            .map(|p| {
+                v(3.0, 1.0)
+                    + direction * (p.x - 50.0)
+                    + direction.perpendicular() * (p.y - 50.0) * 0.1
+            })
+            .collect();
+        let axes = principal_axes(&points).unwrap();
+        assert_approx_eq!(axes.centroid, centroid(&points).unwrap());
+        assert_approx_eq!(axes.major.dot(&direction).abs(), 1.0, epsilon = 1e-3);
+        assert_approx_eq!(axes.major.length(), 1.0);
+        assert_approx_eq!(axes.major.dot(&axes.minor), 0.0, epsilon = 1e-6);
+        assert!(axes.variances[0] > 50.0 * axes.variances[1]);
+
+        // the variances are the spread measured along the axes
+        let along = |axis: Vector2D| {
+            points
+                .iter()
+                .map(|p| (*p - axes.centroid).dot(&axis).powi(2))
+                .sum::<f32>()
+                / points.len() as f32
+        };
+        assert_approx_eq!(along(axes.major), axes.variances[0], epsilon = 1e-2);
+        assert_approx_eq!(along(axes.minor), axes.variances[1], epsilon = 1e-2);
+
+        let vertical = principal_axes(&[v(0.0, -2.0), v(0.0, 2.0)]).unwrap();
+        assert_approx_eq!(vertical.major, v(0.0, 1.0), epsilon = 1e-6);
+        assert_eq!(vertical.variances, [4.0, 0.0]);
+        let square = [v(0.0, 0.0), v(1.0, 0.0), v(1.0, 1.0), v(0.0, 1.0)];
+        assert_eq!(principal_axes(&square).unwrap().major, v(1.0, 0.0));
+        assert_eq!(principal_axes(&[]), None);
+    }
+
+    #[test]
+    fn outliers_are_removed() {
+        let mut points = blob(v(10.0, 10.0), 200, 3);
+        points.insert(50, v(40.0, 10.0));
+        points.push(v(-30.0, 0.0));
+        let kept = remove_outliers(&points, 8, 2.0);
+        assert_eq!(kept.len(), 200);
+        assert!(!kept.contains(&v(40.0, 10.0)));
+        assert!(!kept.contains(&v(-30.0, 0.0)));
+        assert_eq!(kept[..50], points[..50]);
+
+        assert_eq!(remove_outliers(&points[..5], 8, 2.0), points[..5]);
+        assert_eq!(remove_outliers(&points, 0, 2.0), points);
+    }
+
+    #[test]
+    fn kmeans_separates_blobs() {
+        let centers = [v(0.0, 0.0), v(20.0, 0.0), v(10.0, 15.0)];
+        let points: Vec<Vector2D> = centers
+            .iter()
+            .zip(1..)
+            .flat_map(|(c, seed)| blob(*c, 100, seed))
+            .collect();
+
+        for seed in 1..6 {
+            let result = kmeans(&points, 3, seed).unwrap();
+            assert_eq!(result.assignments.len(), 300);
+            for (i, center) in centers.iter().enumerate() {
+                let cluster = result.assignments[i * 100];
+                assert!(
+                    result.assignments[i * 100..(i + 1) * 100]
+                        .iter()
+                        .all(|a| *a == cluster)
+                );
+                assert!(
+                    result.centroids[cluster].distance(center) < 0.5,
+                    "seed {seed}"
+                );
+            }
+            assert!(result.iterations < KMEANS_MAX_ITERATIONS);
+            assert_eq!(kmeans(&points, 3, seed), Some(result));
+        }
+
+        let exact = kmeans(&centers, 3, 7).unwrap();
+        assert_eq!(exact.inertia, 0.0);
+        let mut found = exact.centroids.clone();
+        found.sort_by(|a, b| a.x.total_cmp(&b.x));
+        assert_eq!(found, [centers[0], centers[2], centers[1]]);
+
+        let one = kmeans(&points, 1, 1).unwrap();
+        assert_approx_eq!(one.centroids[0], centroid(&points).unwrap(), epsilon = 1e-4);
+        assert_eq!(kmeans(&points, 0, 1), None);
+        assert_eq!(kmeans(&centers, 4, 1), None);
+    }
+
+    #[test]
+    fn dbscan_finds_dense_regions() {
+        let mut points = blob(v(0.0, 0.0), 80, 4);
+        points.extend(blob(v(20.0, 0.0), 80, 5));
+        points.push(v(10.0, 10.0));
+        // a border point: close to one core point but too sparse itself
+        points.push(v(-3.5, 0.0));
+
+        let labels = dbscan(&points, 1.5, 4);
+        assert_eq!(labels[0], Some(0));
+        assert!(labels[..80].iter().all(|l| *l == Some(0)));
+        assert!(labels[80..160].iter().all(|l| *l == Some(1)));
+        assert_eq!(labels[160], None);
+        assert_eq!(labels[161], Some(0));
+        assert!(dbscan(&points, -1.5, 1).iter().all(Option::is_none));
+        assert_eq!(dbscan(&points, 1.5, 4), labels);
+
+        // with a single required point every point is a core point
+        let pairs = [v(0.0, 0.0), v(5.0, 0.0), v(0.5, 0.0)];
+        assert_eq!(dbscan(&pairs, 1.0, 1), [Some(0), Some(1), Some(0)]);
+        assert_eq!(dbscan(&pairs, 1.0, 3), [None, None, None]);
+        assert!(dbscan(&[], 1.0, 2).is_empty());
+    }
+}
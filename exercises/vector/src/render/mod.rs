@@ -0,0 +1,35 @@
+pub mod png;
+pub mod raster;
+pub mod svg;
+
+/// 8-bit RGBA colour, not premultiplied.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const RED: Color = Color::rgb(220, 40, 40);
+    pub const GREEN: Color = Color::rgb(40, 160, 60);
+    pub const BLUE: Color = Color::rgb(40, 90, 220);
+    pub const GRAY: Color = Color::rgb(128, 128, 128);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b, a: 255 }
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// The same colour with alpha `a`.
+    pub const fn with_alpha(self, a: u8) -> Self {
+        Color { a, ..self }
+    }
+}
@@ -0,0 +1,226 @@
+use std::io;
+
+// Minimal PNG encoder: 8-bit RGBA, no filtering, and a zlib stream made of
+// stored (uncompressed) deflate blocks. Files come out larger than those of
+// a real compressor but are valid PNGs that any viewer reads, and the
+// encoder needs nothing beyond the two checksums.
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+// Largest payload of a stored deflate block
+const MAX_STORED: usize = 65535;
+
+/// Writes `rgba` (4 bytes per pixel, rows top to bottom) as a PNG image.
+/// Fails with `InvalidInput` if either dimension is zero, which PNG does
+/// not allow.
+///
+/// Panics if `rgba` does not hold `width * height` pixels.
+pub fn write_png(out: &mut impl io::Write, width: u32, height: u32, rgba: &[u8]) -> io::Result<()> {
+    if width == 0 || height == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "PNG images cannot be empty",
+        ));
+    }
+    assert_eq!(
+        rgba.len(),
+        width as usize * height as usize * 4,
+        "pixel data does not match the image size"
+    );
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // bit depth 8, colour type RGBA, deflate, adaptive filtering, no interlace
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    // every row starts with its filter type, 0 for none
+    let mut raw = Vec::with_capacity(rgba.len() + height as usize);
+    for row in rgba.chunks(width as usize * 4) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    out.write_all(&SIGNATURE)?;
+    write_chunk(out, b"IHDR", &header)?;
+    write_chunk(out, b"IDAT", &zlib_stored(&raw))?;
+    write_chunk(out, b"IEND", &[])
+}
+
+fn write_chunk(out: &mut impl io::Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32_update(crc32_update(!0, kind), data);
+    out.write_all(&(!crc).to_be_bytes())
+}
+
+// `data` is never empty, since every row holds at least its filter byte
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let blocks = data.len().div_ceil(MAX_STORED);
+    let mut out = Vec::with_capacity(data.len() + 5 * blocks + 6);
+    // deflate with a 32K window, no preset dictionary, check bits filled in
+    out.extend_from_slice(&[0x78, 0x01]);
+    let mut chunks = data.chunks(MAX_STORED).peekable();
+    while let Some(chunk) = chunks.next() {
+        let last = chunks.peek().is_none();
+        let length = chunk.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&length.to_le_bytes());
+        out.extend_from_slice(&(!length).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+// CRC-32 as used by PNG and zlib (reflected, polynomial 0xedb88320); start
+// with !0 and invert the final value
+fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut n = 0;
+        while n < 256 {
+            let mut c = n as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 {
+                    0xedb8_8320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                k += 1;
+            }
+            table[n] = c;
+            n += 1;
+        }
+        table
+    };
+    for &byte in data {
+        crc = TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MODULUS: u32 = 65521;
+    // 5552 bytes is the longest run that cannot overflow before reducing
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MODULUS;
+        b %= MODULUS;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests_png {
+    use super::*;
+
+    fn crc32(data: &[u8]) -> u32 {
+        !crc32_update(!0, data)
+    }
+
+    // Splits a PNG into its chunks, checking signature and checksums
+    fn chunks(png: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+        assert_eq!(png[..8], SIGNATURE);
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            let kind: [u8; 4] = rest[4..8].try_into().unwrap();
+            let data = rest[8..8 + length].to_vec();
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc, crc32(&rest[4..8 + length]));
+            chunks.push((kind, data));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    // Inverse of `zlib_stored`, for stored blocks only
+    fn inflate_stored(zlib: &[u8]) -> Vec<u8> {
+        assert_eq!(u16::from_be_bytes([zlib[0], zlib[1]]) % 31, 0);
+        let mut data = Vec::new();
+        let mut at = 2;
+        loop {
+            let last = zlib[at] & 1 == 1;
+            assert_eq!(zlib[at] >> 1, 0, "not a stored block");
+            let length = u16::from_le_bytes([zlib[at + 1], zlib[at + 2]]);
+            assert_eq!(!length, u16::from_le_bytes([zlib[at + 3], zlib[at + 4]]));
+            data.extend_from_slice(&zlib[at + 5..at + 5 + length as usize]);
+            at += 5 + length as usize;
+            if last {
+                break;
+            }
+        }
+        assert_eq!(zlib[at..], adler32(&data).to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn checksums_match_reference_values() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b""), 1);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        // long runs of 0xff exercise the overflow bound
+        let ones = vec![0xff; 100_000];
+        let (mut a, mut b) = (1u64, 0u64);
+        for &byte in &ones {
+            a = (a + byte as u64) % 65521;
+            b = (b + a) % 65521;
+        }
+        assert_eq!(adler32(&ones), ((b << 16) | a) as u32);
+    }
+
+    #[test]
+    fn writes_header_and_rows() {
+        let rgba = [
+            255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, //
+            0, 0, 0, 0, 10, 20, 30, 40, 255, 255, 255, 255,
+        ];
+        let mut png = Vec::new();
+        write_png(&mut png, 3, 2, &rgba).unwrap();
+
+        let chunks = chunks(&png);
+        let kinds: Vec<_> = chunks.iter().map(|(kind, _)| kind).collect();
+        assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 3, 0, 0, 0, 2, 8, 6, 0, 0, 0]);
+
+        let raw = inflate_stored(&chunks[1].1);
+        assert_eq!(raw.len(), 2 * (1 + 3 * 4));
+        assert_eq!(raw[0], 0);
+        assert_eq!(raw[1..13], rgba[..12]);
+        assert_eq!(raw[13], 0);
+        assert_eq!(raw[14..], rgba[12..]);
+    }
+
+    #[test]
+    fn large_images_span_several_blocks() {
+        let (width, height) = (300, 200);
+        let rgba: Vec<u8> = (0..width * height * 4).map(|i| (i % 251) as u8).collect();
+        let mut png = Vec::new();
+        write_png(&mut png, width as u32, height as u32, &rgba).unwrap();
+
+        let raw = inflate_stored(&chunks(&png)[1].1);
+        assert_eq!(raw.len(), height * (1 + width * 4));
+        for (y, row) in raw.chunks(1 + width * 4).enumerate() {
+            assert_eq!(row[0], 0);
+            assert_eq!(row[1..], rgba[y * width * 4..(y + 1) * width * 4]);
+        }
+    }
+
+    #[test]
+    fn empty_images_are_rejected() {
+        let mut out = Vec::new();
+        let error = write_png(&mut out, 0, 3, &[]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(out.is_empty());
+    }
+}
@@ -0,0 +1,703 @@
+use crate::geometry::clipping::PolygonWithHoles;
+use crate::render::Color;
+use crate::render::png::write_png;
+use crate::vector2d::Vector2D;
+use std::io;
+
+// Software rasterizer drawing into an RGBA framebuffer.
+//
+// Coordinates are in pixels with y pointing down; pixel (x, y) covers the
+// square [x, x + 1) x [y, y + 1), so its centre is at (x + 0.5, y + 0.5).
+// Fills cover the pixels whose centres are inside, and lines are clipped to
+// the framebuffer before they are walked, so far away end points cost
+// nothing. Colours are blended "source over" using their alpha, which makes
+// the same drawing calls always produce the same bytes, as golden-image
+// tests need.
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Marker {
+    Dot,
+    Square,
+    Plus,
+    Cross,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+}
+
+// `color` over `below` with the given fraction of the pixel covered
+fn blend(below: Color, color: Color, coverage: f32) -> Color {
+    let alpha = color.a as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+    if alpha <= 0.0 {
+        return below;
+    }
+    let below_alpha = below.a as f32 / 255.0 * (1.0 - alpha);
+    let out_alpha = alpha + below_alpha;
+    let channel = |top: u8, bottom: u8| {
+        ((top as f32 * alpha + bottom as f32 * below_alpha) / out_alpha).round() as u8
+    };
+    Color::rgba(
+        channel(color.r, below.r),
+        channel(color.g, below.g),
+        channel(color.b, below.b),
+        (out_alpha * 255.0).round() as u8,
+    )
+}
+
+// Liang-Barsky: the part of the segment inside the box. Computed in f64
+// so that far away end points still give accurate clipped ones.
+fn clip_segment(
+    a: Vector2D,
+    b: Vector2D,
+    min: Vector2D,
+    max: Vector2D,
+) -> Option<(Vector2D, Vector2D)> {
+    let (ax, ay) = (a.x as f64, a.y as f64);
+    let (dx, dy) = (b.x as f64 - ax, b.y as f64 - ay);
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [
+        (-dx, ax - min.x as f64),
+        (dx, max.x as f64 - ax),
+        (-dy, ay - min.y as f64),
+        (dy, max.y as f64 - ay),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    let at = |t: f64| Vector2D::new((ax + dx * t) as f32, (ay + dy * t) as f32);
+    (t0 <= t1).then(|| (at(t0), at(t1)))
+}
+
+impl Framebuffer {
+    pub fn new(width: u32, height: u32, background: Color) -> Self {
+        Framebuffer {
+            width,
+            height,
+            pixels: vec![background; width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pixels row by row, top to bottom.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: i64, y: i64) -> Option<Color> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    pub fn clear(&mut self, color: Color) {
+        self.pixels.fill(color);
+    }
+
+    fn index(&self, x: i64, y: i64) -> Option<usize> {
+        let inside = (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y);
+        inside.then(|| y as usize * self.width as usize + x as usize)
+    }
+
+    /// Blends `color` into one pixel, `coverage` scaling its alpha. Pixels
+    /// outside the framebuffer are ignored.
+    pub fn plot(&mut self, x: i64, y: i64, color: Color, coverage: f32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = blend(self.pixels[i], color, coverage);
+        }
+    }
+
+    // The segment clipped to a pixel beyond the framebuffer on every side
+    fn clip(&self, a: Vector2D, b: Vector2D) -> Option<(Vector2D, Vector2D)> {
+        let max = Vector2D::new(self.width as f32 + 1.0, self.height as f32 + 1.0);
+        clip_segment(a, b, Vector2D::new(-1.0, -1.0), max)
+    }
+
+    /// Bresenham line through the pixels containing `a` and `b`, both included.
+    pub fn line(&mut self, a: Vector2D, b: Vector2D, color: Color) {
+        let Some((a, b)) = self.clip(a, b) else {
+            return;
+        };
+        let (mut x, mut y) = (a.x.floor() as i64, a.y.floor() as i64);
+        let (x1, y1) = (b.x.floor() as i64, b.y.floor() as i64);
+        let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+        let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+        let mut error = dx + dy;
+        loop {
+            self.plot(x, y, color, 1.0);
+            if x == x1 && y == y1 {
+                break;
+            }
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Anti-aliased line (Xiaolin Wu): every step along the major axis
+    /// splits full coverage between the two pixels nearest to the line.
+    pub fn line_aa(&mut self, a: Vector2D, b: Vector2D, color: Color) {
+        let Some((a, b)) = self.clip(a, b) else {
+            return;
+        };
+        // Wu's algorithm puts pixel centres on integer coordinates
+        let (mut a, mut b) = (a - Vector2D::new(0.5, 0.5), b - Vector2D::new(0.5, 0.5));
+        let steep = (b.y - a.y).abs() > (b.x - a.x).abs();
+        if steep {
+            a = Vector2D::new(a.y, a.x);
+            b = Vector2D::new(b.y, b.x);
+        }
+        if a.x > b.x {
+            std::mem::swap(&mut a, &mut b);
+        }
+        let d = b - a;
+        let gradient = if d.x == 0.0 { 1.0 } else { d.y / d.x };
+        let mut plot = |major: i64, minor: i64, coverage: f32| {
+            if steep {
+                self.plot(minor, major, color, coverage);
+            } else {
+                self.plot(major, minor, color, coverage);
+            }
+        };
+        let fraction = |v: f32| v - v.floor();
+
+        // end points get the coverage of the part of their pixel they span
+        let mut end = |p: Vector2D, gap: f32| {
+            let x = p.x.round();
+            let y = p.y + gradient * (x - p.x);
+            plot(x as i64, y.floor() as i64, (1.0 - fraction(y)) * gap);
+            plot(x as i64, y.floor() as i64 + 1, fraction(y) * gap);
+            (x as i64, y)
+        };
+        let (first, y) = end(a, 1.0 - fraction(a.x + 0.5));
+        let (last, _) = end(b, fraction(b.x + 0.5));
+
+        let mut y = y + gradient;
+        for x in first + 1..last {
+            plot(x, y.floor() as i64, 1.0 - fraction(y));
+            plot(x, y.floor() as i64 + 1, fraction(y));
+            y += gradient;
+        }
+    }
+
+    pub fn polyline(&mut self, points: &[Vector2D], color: Color) {
+        for w in points.windows(2) {
+            self.line(w[0], w[1], color);
+        }
+    }
+
+    /// Scanline fill by the even-odd rule, so rings inside rings are holes.
+    fn fill_rings(&mut self, rings: &[&[Vector2D]], color: Color) {
+        let (min_y, max_y) = rings
+            .iter()
+            .flat_map(|ring| ring.iter())
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p.y), hi.max(p.y))
+            });
+        if min_y > max_y {
+            return;
+        }
+        let first_row = ((min_y - 0.5).ceil() as i64).max(0);
+        let last_row = ((max_y - 0.5).floor() as i64).min(self.height as i64 - 1);
+
+        let mut crossings = Vec::new();
+        for row in first_row..=last_row {
+            let center = row as f32 + 0.5;
+            crossings.clear();
+            for ring in rings {
+                for i in 0..ring.len() {
+                    let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                    // half-open, so a vertex on the scanline counts once
+                    if (a.y <= center) != (b.y <= center) {
+                        crossings.push(a.x + (center - a.y) / (b.y - a.y) * (b.x - a.x));
+                    }
+                }
+            }
+            crossings.sort_by(f32::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                let from = ((span[0] - 0.5).ceil() as i64).max(0);
+                let to = ((span[1] - 0.5).ceil() as i64).min(self.width as i64);
+                for x in from..to {
+                    self.plot(x, row, color, 1.0);
+                }
+            }
+        }
+    }
+
+    pub fn fill_polygon(&mut self, vertices: &[Vector2D], color: Color) {
+        self.fill_rings(&[vertices], color);
+    }
+
+    pub fn fill_polygon_with_holes(&mut self, polygon: &PolygonWithHoles, color: Color) {
+        let rings: Vec<&[Vector2D]> = std::iter::once(polygon.outer.as_slice())
+            .chain(polygon.holes.iter().map(Vec::as_slice))
+            .collect();
+        self.fill_rings(&rings, color);
+    }
+
+    /// Midpoint circle around the pixel containing `center`, with the radius
+    /// rounded to whole pixels. Every pixel is drawn once, and the cost
+    /// depends on the size of the framebuffer, not on the radius. Radii of
+    /// 2^63 pixels or more draw nothing.
+    pub fn circle(&mut self, center: Vector2D, radius: f32, color: Color) {
+        let (cx, cy) = (center.x.floor() as f64, center.y.floor() as f64);
+        let r = radius.round() as f64;
+        // outline pixels are within half a pixel of the rounded radius
+        let (right, bottom) = (self.width as f64 - 1.0, self.height as f64 - 1.0);
+        let nearest = (cx - cx.clamp(0.0, right)).hypot(cy - cy.clamp(0.0, bottom));
+        let farthest = cx
+            .abs()
+            .max((cx - right).abs())
+            .hypot(cy.abs().max((cy - bottom).abs()));
+        if nearest > r + 1.0 || farthest < r - 1.0 || r >= 2f64.powi(63) {
+            return;
+        }
+
+        // Stepping from (r, 0) while x >= y, the midpoint algorithm settles
+        // at each y on the smallest x with x (x + 1) >= r^2 - y^2. Solving
+        // for x directly visits only the rows and columns of the
+        // framebuffer, however long the arc outside it is.
+        let (cx, cy, r) = (cx as i128, cy as i128, r as i128);
+        let octant_x = |y: i128| {
+            let d = (r * r - y * y).max(0) as u128;
+            let x = ((4 * d + 1).isqrt() - 1) / 2;
+            let x = if x * (x + 1) == d { x } else { x + 1 } as i128;
+            (x >= y).then_some(x)
+        };
+        let (width, height) = (self.width as i128, self.height as i128);
+        let mut pixels = Vec::new();
+        for row in 0..height {
+            if let Some(x) = octant_x((row - cy).abs()) {
+                pixels.extend([(cx - x, row), (cx + x, row)]);
+            }
+        }
+        for column in 0..width {
+            if let Some(x) = octant_x((column - cx).abs()) {
+                pixels.extend([(column, cy - x), (column, cy + x)]);
+            }
+        }
+        pixels.retain(|&(x, y)| (0..width).contains(&x) && (0..height).contains(&y));
+        pixels.sort_unstable();
+        pixels.dedup();
+        for (x, y) in pixels {
+            self.plot(x as i64, y as i64, color, 1.0);
+        }
+    }
+
+    /// Fills the pixels whose centres are within `radius` of `center`.
+    pub fn fill_circle(&mut self, center: Vector2D, radius: f32, color: Color) {
+        let first_row = ((center.y - radius - 0.5).ceil() as i64).max(0);
+        let last_row = ((center.y + radius - 0.5).floor() as i64).min(self.height as i64 - 1);
+        for row in first_row..=last_row {
+            let dy = row as f32 + 0.5 - center.y;
+            let half = (radius * radius - dy * dy).max(0.0).sqrt();
+            let from = ((center.x - half - 0.5).ceil() as i64).max(0);
+            let to = ((center.x + half - 0.5).floor() as i64).min(self.width as i64 - 1);
+            for x in from..=to {
+                self.plot(x, row, color, 1.0);
+            }
+        }
+    }
+
+    /// Marker centred on `p` reaching `size` pixels from it.
+    pub fn marker(&mut self, p: Vector2D, marker: Marker, size: f32, color: Color) {
+        let (dx, dy) = (Vector2D::new(size, 0.0), Vector2D::new(0.0, size));
+        match marker {
+            Marker::Dot => self.fill_circle(p, size, color),
+            Marker::Square => {
+                self.fill_polygon(&[p - dx - dy, p + dx - dy, p + dx + dy, p - dx + dy], color)
+            }
+            Marker::Plus => {
+                self.line(p - dx, p + dx, color);
+                // the centre pixel is already drawn
+                self.line(p - dy, p - Vector2D::new(0.0, 1.0), color);
+                self.line(p + Vector2D::new(0.0, 1.0), p + dy, color);
+            }
+            Marker::Cross => {
+                self.line(p - dx - dy, p + dx + dy, color);
+                self.line(p - dx + dy, p - Vector2D::new(1.0, -1.0), color);
+                self.line(p + Vector2D::new(1.0, -1.0), p + dx - dy, color);
+            }
+        }
+    }
+
+    /// Pixels as RGBA bytes, row by row.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|c| [c.r, c.g, c.b, c.a])
+            .collect()
+    }
+
+    /// Binary PPM (P6). The format has no alpha channel, which is dropped.
+    pub fn write_ppm(&self, out: &mut impl io::Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let rgb: Vec<u8> = self.pixels.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+        out.write_all(&rgb)
+    }
+
+    /// Fails with `InvalidInput` if the framebuffer has no pixels, which PNG
+    /// cannot store.
+    pub fn write_png(&self, out: &mut impl io::Write) -> io::Result<()> {
+        write_png(out, self.width, self.height, &self.to_rgba())
+    }
+}
+
+#[cfg(test)]
+mod tests_raster {
+    use super::*;
+    use crate::assert_approx_eq;
+
+    const INK: Color = Color::BLACK;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    fn canvas(width: u32, height: u32) -> Framebuffer {
+        Framebuffer::new(width, height, Color::WHITE)
+    }
+
+    // Golden image as text: `#` for ink, `.` for background
+    fn ascii(framebuffer: &Framebuffer) -> String {
+        framebuffer
+            .pixels()
+            .chunks(framebuffer.width() as usize)
+            .flat_map(|row| {
+                row.iter()
+                    .map(|c| match *c {
+                        Color::WHITE => '.',
+                        INK => '#',
+                        _ => '?',
+                    })
+                    .chain(['\n'])
+            })
+            .collect()
+    }
+
+    // Fraction of full ink in a pixel drawn onto white
+    fn coverage(framebuffer: &Framebuffer, x: i64, y: i64) -> f32 {
+        1.0 - framebuffer.pixel(x, y).unwrap().r as f32 / 255.0
+    }
+
+    #[test]
+    fn bresenham_lines() {
+        let mut image = canvas(8, 5);
+        image.line(v(0.5, 0.5), v(7.5, 3.5), INK);
+        image.line(v(0.5, 4.5), v(0.5, 4.5), INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+##......
+..##....
+....##..
+......##
+#.......
+"
+        );
+
+        let mut image = canvas(5, 5);
+        image.line(v(4.9, 0.1), v(0.2, 4.8), INK);
+        image.line(v(2.5, 0.0), v(2.5, 4.0), INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+..#.#
+..##.
+..#..
+.##..
+#.#..
+"
+        );
+    }
+
+    #[test]
+    fn lines_are_clipped() {
+        let mut image = canvas(4, 3);
+        image.line(v(-1e9, 1.5), v(1e9, 1.5), INK);
+        image.line_aa(v(2.5, -1e9), v(2.5, 1e9), INK);
+        image.line(v(-5.0, -5.0), v(-1.0, 10.0), INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+..#.
+####
+..#.
+"
+        );
+    }
+
+    #[test]
+    fn wu_lines_share_coverage_between_neighbours() {
+        // through pixel centres: a crisp line
+        let mut image = canvas(10, 5);
+        image.line_aa(v(0.5, 2.5), v(9.5, 2.5), INK);
+        for x in 1..9 {
+            assert_approx_eq!(coverage(&image, x, 2), 1.0, epsilon = 0.01);
+            assert_eq!(coverage(&image, x, 1), 0.0);
+        }
+        // the ends stop at pixel centres and cover half of their pixels
+        assert_approx_eq!(coverage(&image, 0, 2), 0.5, epsilon = 0.01);
+        assert_approx_eq!(coverage(&image, 9, 2), 0.5, epsilon = 0.01);
+
+        // on the boundary between two rows: half each
+        let mut image = canvas(10, 5);
+        image.line_aa(v(0.5, 2.0), v(9.5, 2.0), INK);
+        for x in 1..9 {
+            assert_approx_eq!(coverage(&image, x, 1), 0.5, epsilon = 0.01);
+            assert_approx_eq!(coverage(&image, x, 2), 0.5, epsilon = 0.01);
+        }
+
+        // steep and shallow lines put one pixel's worth in every step
+        for (a, b) in [(v(0.5, 0.5), v(9.5, 3.7)), (v(1.3, 9.5), v(4.1, 0.5))] {
+            let mut image = canvas(10, 10);
+            image.line_aa(a, b, INK);
+            let steep = (b.y - a.y).abs() > (b.x - a.x).abs();
+            for major in 2..8 {
+                let total: f32 = (0..10)
+                    .map(|minor| {
+                        if steep {
+                            coverage(&image, minor, major)
+                        } else {
+                            coverage(&image, major, minor)
+                        }
+                    })
+                    .sum();
+                assert_approx_eq!(total, 1.0, epsilon = 0.02);
+            }
+        }
+    }
+
+    #[test]
+    fn scanline_fills() {
+        let mut image = canvas(7, 6);
+        image.fill_polygon(&[v(0.0, 0.0), v(7.0, 0.0), v(0.0, 6.0)], INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+######.
+#####..
+####...
+###....
+##.....
+#......
+"
+        );
+
+        // edges on pixel boundaries cover exactly the pixels inside
+        let mut image = canvas(6, 5);
+        let square = vec![v(1.0, 1.0), v(5.0, 1.0), v(5.0, 4.0), v(1.0, 4.0)];
+        let hole = vec![v(2.0, 2.0), v(2.0, 3.0), v(4.0, 3.0), v(4.0, 2.0)];
+        image.fill_polygon_with_holes(&PolygonWithHoles::new(square, vec![hole]), INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+......
+.####.
+.#..#.
+.####.
+......
+"
+        );
+
+        // shapes partly outside are clipped
+        let mut image = canvas(3, 3);
+        image.fill_polygon(&[v(-10.0, -10.0), v(1.5, -10.0), v(1.5, 20.0)], INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+#..
+#..
+#..
+"
+        );
+    }
+
+    #[test]
+    fn circles_and_markers() {
+        let mut image = canvas(9, 9);
+        image.circle(v(4.5, 4.5), 3.0, INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+.........
+...###...
+..#...#..
+.#.....#.
+.#.....#.
+.#.....#.
+..#...#..
+...###...
+.........
+"
+        );
+
+        // only the visible arc is drawn, and it matches the full outline
+        let mut full = canvas(120, 120);
+        full.circle(v(104.5, 4.5), 100.0, INK);
+        let mut corner = canvas(9, 9);
+        corner.circle(v(104.5, 4.5), 100.0, INK);
+        for (y, row) in corner.pixels().chunks(9).enumerate() {
+            assert_eq!(row, &full.pixels()[y * 120..y * 120 + 9]);
+        }
+        assert!(corner.pixels().contains(&INK));
+
+        // outlines enclosing or missing the framebuffer draw nothing
+        for (center, radius) in [
+            (v(4.5, 4.5), 1e8),
+            (v(-1e9, 3e9), 1e8),
+            (v(30.0, 4.5), 20.0),
+        ] {
+            let mut image = canvas(9, 9);
+            image.circle(center, radius, INK);
+            assert!(!image.pixels().contains(&INK));
+        }
+
+        // the same pixels as stepping along the octant
+        for radius in 0..40 {
+            let mut image = canvas(90, 90);
+            image.circle(v(45.5, 40.5), radius as f32, INK);
+            let mut walked = canvas(90, 90);
+            let (mut x, mut y, mut error) = (radius, 0, 1 - radius);
+            while x >= y {
+                for (dx, dy) in [(x, y), (y, x), (-y, x), (-x, y)] {
+                    walked.plot(45 + dx, 40 + dy, INK, 1.0);
+                    walked.plot(45 - dx, 40 - dy, INK, 1.0);
+                }
+                y += 1;
+                if error < 0 {
+                    error += 2 * y + 1;
+                } else {
+                    x -= 1;
+                    error += 2 * (y - x) + 1;
+                }
+            }
+            assert_eq!(image, walked);
+        }
+
+        // a huge outline crossing the framebuffer is nearly a straight line
+        let mut image = canvas(10, 10);
+        image.circle(v(3e9 + 5.5, 5.5), 3e9, INK);
+        assert_eq!(
+            ascii(&image).lines().filter(|l| l.starts_with('#')).count(),
+            10
+        );
+        assert!(!ascii(&image).contains(".#"));
+
+        let mut image = canvas(9, 9);
+        image.fill_circle(v(4.5, 4.5), 3.0, INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+.........
+....#....
+..#####..
+..#####..
+.#######.
+..#####..
+..#####..
+....#....
+.........
+"
+        );
+        let mut big = canvas(100, 100);
+        big.fill_circle(v(50.0, 50.0), 40.0, INK);
+        let filled = big.pixels().iter().filter(|c| **c == INK).count() as f32;
+        assert_approx_eq!(filled, std::f32::consts::PI * 1600.0, epsilon = 20.0);
+
+        let mut image = canvas(11, 5);
+        image.marker(v(2.5, 2.5), Marker::Plus, 2.0, INK);
+        image.marker(v(8.5, 2.5), Marker::Cross, 2.0, INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+..#...#...#
+..#....#.#.
+#####...#..
+..#....#.#.
+..#...#...#
+"
+        );
+
+        let mut image = canvas(5, 4);
+        image.marker(v(2.5, 2.0), Marker::Square, 1.5, INK);
+        assert_eq!(
+            ascii(&image),
+            "\
+.###.
+.###.
+.###.
+.....
+"
+        );
+    }
+
+    #[test]
+    fn translucent_colors_blend() {
+        let mut image = canvas(2, 1);
+        image.plot(0, 0, Color::RED.with_alpha(128), 1.0);
+        image.plot(1, 0, Color::BLACK, 0.25);
+        assert_eq!(image.pixel(0, 0), Some(Color::rgb(237, 147, 147)));
+        assert_eq!(image.pixel(1, 0), Some(Color::rgb(191, 191, 191)));
+
+        // translucent circle outlines do not darken where octants meet
+        let mut image = canvas(9, 9);
+        image.circle(v(4.5, 4.5), 3.0, INK.with_alpha(128));
+        let shades: Vec<Color> = image
+            .pixels()
+            .iter()
+            .copied()
+            .filter(|c| *c != Color::WHITE)
+            .collect();
+        assert!(shades.iter().all(|c| *c == shades[0]));
+
+        let mut transparent = Framebuffer::new(1, 1, Color::TRANSPARENT);
+        transparent.plot(0, 0, Color::BLUE.with_alpha(51), 1.0);
+        assert_eq!(transparent.pixel(0, 0), Some(Color::BLUE.with_alpha(51)));
+        assert_eq!(transparent.pixel(1, 0), None);
+    }
+
+    #[test]
+    fn image_files() {
+        let mut image = canvas(2, 2);
+        image.plot(1, 0, Color::RED, 1.0);
+        image.plot(0, 1, INK.with_alpha(0), 1.0);
+
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend_from_slice(&[255, 255, 255, 220, 40, 40, 255, 255, 255, 255, 255, 255]);
+        assert_eq!(ppm, expected);
+
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        assert_eq!(png[1..4], *b"PNG");
+        let empty = Framebuffer::new(0, 0, Color::WHITE);
+        assert!(empty.write_png(&mut Vec::new()).is_err());
+        assert_eq!(image.to_rgba()[4..8], [220, 40, 40, 255]);
+    }
+}
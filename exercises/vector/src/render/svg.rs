@@ -0,0 +1,555 @@
+use crate::geometry::curves::Curve;
+use crate::geometry::shapes::{Aabb, Circle, Segment};
+use crate::render::Color;
+use crate::vector2d::Vector2D;
+use std::io;
+
+// SVG output for looking at geometry.
+//
+// A scene collects primitives in scene coordinates. Writing it maps them
+// into the image with one uniform scale, so that the scene's bounding box
+// (or a given region) fits inside the margins, centred, with y pointing up
+// unless flipping is turned off. Coordinates are transformed before they are
+// written rather than through an SVG transform, so stroke widths and marker
+// sizes stay in pixels whatever the scale.
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Style {
+    pub stroke: Option<Color>,
+    pub fill: Option<Color>,
+    /// Stroke width in pixels.
+    pub width: f32,
+    /// Dash length in pixels, solid when `None`.
+    pub dash: Option<f32>,
+    /// Radius of point markers and half the length of arrow heads, in pixels.
+    pub marker_size: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            stroke: Some(Color::BLACK),
+            fill: None,
+            width: 1.0,
+            dash: None,
+            marker_size: 3.0,
+        }
+    }
+}
+
+impl Style {
+    pub fn stroked(color: Color) -> Self {
+        Style {
+            stroke: Some(color),
+            ..Style::default()
+        }
+    }
+
+    pub fn filled(color: Color) -> Self {
+        Style {
+            stroke: None,
+            fill: Some(color),
+            ..Style::default()
+        }
+    }
+
+    pub fn with_stroke(self, color: Color) -> Self {
+        Style {
+            stroke: Some(color),
+            ..self
+        }
+    }
+
+    pub fn with_fill(self, color: Color) -> Self {
+        Style {
+            fill: Some(color),
+            ..self
+        }
+    }
+
+    pub fn with_width(self, width: f32) -> Self {
+        Style { width, ..self }
+    }
+
+    pub fn with_dash(self, dash: f32) -> Self {
+        Style {
+            dash: Some(dash),
+            ..self
+        }
+    }
+
+    pub fn with_marker_size(self, marker_size: f32) -> Self {
+        Style {
+            marker_size,
+            ..self
+        }
+    }
+}
+
+/// Image size and the mapping from scene to image coordinates.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct View {
+    pub width: u32,
+    pub height: u32,
+    /// Pixels kept free around the fitted region.
+    pub margin: f32,
+    /// Region to fit into the image, the scene's bounds when `None`.
+    pub bounds: Option<Aabb>,
+    /// Makes y point up instead of down.
+    pub flip_y: bool,
+    pub background: Option<Color>,
+}
+
+impl View {
+    pub fn new(width: u32, height: u32) -> Self {
+        View {
+            width,
+            height,
+            margin: 10.0,
+            bounds: None,
+            flip_y: true,
+            background: Some(Color::WHITE),
+        }
+    }
+
+    pub fn with_margin(self, margin: f32) -> Self {
+        View { margin, ..self }
+    }
+
+    pub fn with_bounds(self, bounds: Aabb) -> Self {
+        View {
+            bounds: Some(bounds),
+            ..self
+        }
+    }
+
+    pub fn with_flip_y(self, flip_y: bool) -> Self {
+        View { flip_y, ..self }
+    }
+
+    pub fn with_background(self, background: Option<Color>) -> Self {
+        View { background, ..self }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Item {
+    Point(Vector2D),
+    Arrow(Vector2D, Vector2D),
+    Polyline(Vec<Vector2D>),
+    Polygon(Vec<Vector2D>),
+    Circle(Circle),
+}
+
+impl Item {
+    fn aabb(&self) -> Option<Aabb> {
+        match self {
+            Item::Point(p) => Some(Aabb::new(*p, *p)),
+            Item::Arrow(from, to) => Some(Aabb::new(*from, *to)),
+            Item::Polyline(points) | Item::Polygon(points) => {
+                Aabb::from_points(points.iter().copied())
+            }
+            Item::Circle(circle) => Some(circle.aabb()),
+        }
+    }
+}
+
+/// Primitives to draw, each with its style, in drawing order.
+#[derive(Clone, Default, Debug)]
+pub struct Scene {
+    items: Vec<(Item, Style)>,
+}
+
+// Scene to image coordinates
+struct Transform {
+    scale: f32,
+    scene_center: Vector2D,
+    image_center: Vector2D,
+    flip_y: bool,
+}
+
+impl Transform {
+    fn fit(view: &View, bounds: Aabb) -> Self {
+        let available = Vector2D::new(
+            (view.width as f32 - 2.0 * view.margin).max(0.0),
+            (view.height as f32 - 2.0 * view.margin).max(0.0),
+        );
+        let size = bounds.size();
+        // a zero extent puts no limit on the scale
+        let scale = (available.x / size.x).min(available.y / size.y);
+        Transform {
+            scale: if scale.is_finite() { scale } else { 1.0 },
+            scene_center: bounds.center(),
+            image_center: Vector2D::new(view.width as f32, view.height as f32) / 2.0,
+            flip_y: view.flip_y,
+        }
+    }
+
+    fn apply(&self, p: Vector2D) -> Vector2D {
+        let d = (p - self.scene_center) * self.scale;
+        let d = if self.flip_y {
+            Vector2D::new(d.x, -d.y)
+        } else {
+            d
+        };
+        self.image_center + d
+    }
+}
+
+// Two decimals without trailing zeros
+fn number(x: f32) -> String {
+    let text = format!("{x:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn points(points: impl IntoIterator<Item = Vector2D>) -> String {
+    points
+        .into_iter()
+        .map(|p| format!("{},{}", number(p.x), number(p.y)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn paint(attribute: &str, color: Color) -> String {
+    let mut text = format!(
+        r##" {attribute}="#{:02x}{:02x}{:02x}""##,
+        color.r, color.g, color.b
+    );
+    if color.a < 255 {
+        text += &format!(
+            r#" {attribute}-opacity="{}""#,
+            number(color.a as f32 / 255.0)
+        );
+    }
+    text
+}
+
+fn attributes(style: &Style) -> String {
+    let mut text = match style.fill {
+        Some(color) => paint("fill", color),
+        None => r#" fill="none""#.to_string(),
+    };
+    if let Some(color) = style.stroke {
+        text += &paint("stroke", color);
+        text += &format!(r#" stroke-width="{}""#, number(style.width));
+        if let Some(dash) = style.dash {
+            text += &format!(r#" stroke-dasharray="{}""#, number(dash));
+        }
+    }
+    text
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Scene::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// A dot of radius `marker_size`, filled with the fill colour or, if
+    /// there is none, the stroke colour.
+    pub fn point(&mut self, p: Vector2D, style: Style) -> &mut Self {
+        self.items.push((Item::Point(p), style));
+        self
+    }
+
+    /// `vector` drawn as an arrow starting at `origin`.
+    pub fn arrow(&mut self, origin: Vector2D, vector: Vector2D, style: Style) -> &mut Self {
+        self.items
+            .push((Item::Arrow(origin, origin + vector), style));
+        self
+    }
+
+    pub fn segment(&mut self, segment: &Segment, style: Style) -> &mut Self {
+        self.polyline(&[segment.start, segment.end], style)
+    }
+
+    pub fn polyline(&mut self, points: &[Vector2D], style: Style) -> &mut Self {
+        self.items.push((Item::Polyline(points.to_vec()), style));
+        self
+    }
+
+    pub fn polygon(&mut self, vertices: &[Vector2D], style: Style) -> &mut Self {
+        self.items.push((Item::Polygon(vertices.to_vec()), style));
+        self
+    }
+
+    pub fn circle(&mut self, circle: &Circle, style: Style) -> &mut Self {
+        self.items.push((Item::Circle(*circle), style));
+        self
+    }
+
+    /// The curve flattened to within a thousandth of its size.
+    pub fn curve(&mut self, curve: &dyn Curve, style: Style) -> &mut Self {
+        let size = curve.aabb().size().length();
+        let tolerance = (size * 1e-3).max(f32::MIN_POSITIVE);
+        self.polyline(&curve.flatten(tolerance), style)
+    }
+
+    /// Bounds of all primitives, ignoring marker and stroke sizes.
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.items
+            .iter()
+            .filter_map(|(item, _)| item.aabb())
+            .reduce(|a, b| a.union(&b))
+    }
+
+    /// Writes the scene as a standalone SVG document.
+    pub fn write(&self, out: &mut impl io::Write, view: &View) -> io::Result<()> {
+        let bounds = view
+            .bounds
+            .or_else(|| self.bounds())
+            .unwrap_or(Aabb::new(Vector2D::ZERO, Vector2D::new(1.0, 1.0)));
+        let transform = Transform::fit(view, bounds);
+
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            view.width, view.height
+        )?;
+        if let Some(color) = view.background {
+            writeln!(
+                out,
+                r#"<rect width="100%" height="100%"{}/>"#,
+                paint("fill", color)
+            )?;
+        }
+        for (item, style) in &self.items {
+            write_item(out, &transform, item, style)?;
+        }
+        writeln!(out, "</svg>")
+    }
+
+    pub fn to_svg(&self, view: &View) -> String {
+        let mut out = Vec::new();
+        self.write(&mut out, view)
+            .expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("the SVG is ASCII")
+    }
+}
+
+fn write_item(
+    out: &mut impl io::Write,
+    transform: &Transform,
+    item: &Item,
+    style: &Style,
+) -> io::Result<()> {
+    match item {
+        Item::Point(p) => {
+            let p = transform.apply(*p);
+            let Some(color) = style.fill.or(style.stroke) else {
+                return Ok(());
+            };
+            writeln!(
+                out,
+                r#"<circle cx="{}" cy="{}" r="{}"{}/>"#,
+                number(p.x),
+                number(p.y),
+                number(style.marker_size),
+                paint("fill", color)
+            )
+        }
+        Item::Arrow(from, to) => {
+            let (from, tip) = (transform.apply(*from), transform.apply(*to));
+            let Some(color) = style.stroke else {
+                return Ok(());
+            };
+            let Some(direction) = (tip - from).normalize() else {
+                return Ok(());
+            };
+            // the shaft stops at the head so that the line cap stays hidden
+            let length = (2.0 * style.marker_size).min(tip.distance(&from));
+            let base = tip - direction * length;
+            let side = direction.perpendicular() * (0.5 * length);
+            writeln!(
+                out,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+                number(from.x),
+                number(from.y),
+                number(base.x),
+                number(base.y),
+                attributes(&Style {
+                    fill: None,
+                    ..*style
+                })
+            )?;
+            writeln!(
+                out,
+                r#"<polygon points="{}"{}/>"#,
+                points([tip, base + side, base - side]),
+                paint("fill", color)
+            )
+        }
+        Item::Polyline(vertices) => writeln!(
+            out,
+            r#"<polyline points="{}"{}/>"#,
+            points(vertices.iter().map(|p| transform.apply(*p))),
+            attributes(style)
+        ),
+        Item::Polygon(vertices) => writeln!(
+            out,
+            r#"<polygon points="{}"{}/>"#,
+            points(vertices.iter().map(|p| transform.apply(*p))),
+            attributes(style)
+        ),
+        Item::Circle(circle) => {
+            let center = transform.apply(circle.center);
+            writeln!(
+                out,
+                r#"<circle cx="{}" cy="{}" r="{}"{}/>"#,
+                number(center.x),
+                number(center.y),
+                number(circle.radius * transform.scale),
+                attributes(style)
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_svg {
+    use super::*;
+    use crate::geometry::curves::CubicBezier;
+
+    fn v(x: f32, y: f32) -> Vector2D {
+        Vector2D::new(x, y)
+    }
+
+    fn body(svg: &str) -> Vec<&str> {
+        svg.lines()
+            .filter(|line| {
+                !line.starts_with("<svg")
+                    && !line.starts_with("</svg")
+                    && !line.starts_with("<rect")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn writes_a_complete_document() {
+        let mut scene = Scene::new();
+        scene
+            .polygon(
+                &[v(0.0, 0.0), v(4.0, 0.0), v(4.0, 2.0)],
+                Style::stroked(Color::BLUE)
+                    .with_fill(Color::RED.with_alpha(128))
+                    .with_dash(4.0),
+            )
+            .point(v(2.0, 1.0), Style::default().with_marker_size(2.5));
+
+        let view = View::new(100, 60).with_margin(10.0);
+        assert_eq!(
+            scene.to_svg(&view),
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="60" viewBox="0 0 100 60">"#,
+                "\n",
+                r##"<rect width="100%" height="100%" fill="#ffffff"/>"##,
+                "\n",
+                r##"<polygon points="10,50 90,50 90,10" fill="#dc2828" fill-opacity="0.5" stroke="#285adc" stroke-width="1" stroke-dasharray="4"/>"##,
+                "\n",
+                r##"<circle cx="50" cy="30" r="2.5" fill="#000000"/>"##,
+                "\n",
+                "</svg>\n",
+            )
+        );
+    }
+
+    #[test]
+    fn fits_the_bounds_into_the_view() {
+        let mut scene = Scene::new();
+        scene.segment(&Segment::new(v(0.0, 0.0), v(10.0, 5.0)), Style::default());
+
+        // the wider extent decides the scale, the other one is centred
+        let view = View::new(220, 220).with_background(None);
+        assert_eq!(
+            body(&scene.to_svg(&view)),
+            [
+                r##"<polyline points="10,160 210,60" fill="none" stroke="#000000" stroke-width="1"/>"##
+            ]
+        );
+
+        let view = view.with_flip_y(false);
+        assert_eq!(
+            body(&scene.to_svg(&view)),
+            [
+                r##"<polyline points="10,60 210,160" fill="none" stroke="#000000" stroke-width="1"/>"##
+            ]
+        );
+
+        let view = View::new(120, 120)
+            .with_margin(0.0)
+            .with_bounds(Aabb::new(v(-10.0, -10.0), v(10.0, 10.0)));
+        assert_eq!(
+            body(&scene.to_svg(&view)),
+            [
+                r##"<polyline points="60,60 120,30" fill="none" stroke="#000000" stroke-width="1"/>"##
+            ]
+        );
+    }
+
+    #[test]
+    fn circles_scale_and_arrows_get_heads() {
+        let mut scene = Scene::new();
+        scene
+            .circle(&Circle::new(v(0.0, 0.0), 1.0), Style::filled(Color::GREEN))
+            .arrow(
+                v(0.0, 0.0),
+                v(1.0, 0.0),
+                Style::stroked(Color::BLACK).with_width(2.0),
+            );
+
+        let view = View::new(40, 40).with_margin(0.0).with_background(None);
+        assert_eq!(
+            body(&scene.to_svg(&view)),
+            [
+                r##"<circle cx="20" cy="20" r="20" fill="#28a03c"/>"##,
+                r##"<line x1="20" y1="20" x2="34" y2="20" fill="none" stroke="#000000" stroke-width="2"/>"##,
+                r##"<polygon points="40,20 34,23 34,17" fill="#000000"/>"##,
+            ]
+        );
+
+        // zero vectors have no direction to draw
+        let mut scene = Scene::new();
+        scene.arrow(v(1.0, 1.0), Vector2D::ZERO, Style::default());
+        assert!(body(&scene.to_svg(&view)).is_empty());
+    }
+
+    #[test]
+    fn curves_are_flattened() {
+        let curve = CubicBezier::new(v(0.0, 0.0), v(1.0, 2.0), v(3.0, 2.0), v(4.0, 0.0));
+        let mut scene = Scene::new();
+        scene.curve(&curve, Style::default());
+        assert_eq!(scene.len(), 1);
+
+        let svg = scene.to_svg(&View::new(400, 400));
+        let lines = body(&svg);
+        let vertices = lines[0].split('"').nth(1).unwrap().split(' ').count();
+        assert!(vertices > 10, "{vertices}");
+        assert!(lines[0].starts_with(r#"<polyline points="10,"#));
+    }
+
+    #[test]
+    fn degenerate_scenes() {
+        let view = View::new(50, 50);
+        assert_eq!(body(&Scene::new().to_svg(&view)), Vec::<&str>::new());
+
+        let mut scene = Scene::new();
+        scene.point(v(3.0, -7.0), Style::default());
+        assert_eq!(scene.bounds(), Some(Aabb::new(v(3.0, -7.0), v(3.0, -7.0))));
+        assert_eq!(
+            body(&scene.to_svg(&view)),
+            [r##"<circle cx="25" cy="25" r="3" fill="#000000"/>"##]
+        );
+    }
+}
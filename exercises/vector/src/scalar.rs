@@ -0,0 +1,382 @@
+use std::fmt::{self, Debug};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// Numeric types usable as vector components
+pub trait Scalar:
+    Copy
+    + Default
+    + PartialEq
+    + PartialOrd
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    // Lossy round trip through f64 with `as` semantics, used by `cast_lossy`
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+// Scalars with a meaningful square root (lengths, normalization)
+pub trait Real: Scalar {
+    fn sqrt(self) -> Self;
+}
+
+// Floating-point scalars: trigonometry, NaN and infinity handling
+pub trait Float: Real {
+    const EPSILON: Self;
+    const PI: Self;
+
+    fn abs(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn atan2(self, x: Self) -> Self;
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! impl_float_scalar {
+    ($($t:ident),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0.0;
+                const ONE: Self = 1.0;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value as $t
+                }
+            }
+
+            impl Real for $t {
+                fn sqrt(self) -> Self {
+                    <$t>::sqrt(self)
+                }
+            }
+
+            impl Float for $t {
+                const EPSILON: Self = <$t>::EPSILON;
+                const PI: Self = std::$t::consts::PI;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn sin(self) -> Self {
+                    <$t>::sin(self)
+                }
+
+                fn cos(self) -> Self {
+                    <$t>::cos(self)
+                }
+
+                fn atan2(self, x: Self) -> Self {
+                    <$t>::atan2(self, x)
+                }
+
+                fn is_nan(self) -> bool {
+                    <$t>::is_nan(self)
+                }
+
+                fn is_finite(self) -> bool {
+                    <$t>::is_finite(self)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_int_scalar {
+    ($($t:ty),*) => {
+        $(
+            impl Scalar for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_float_scalar!(f32, f64);
+impl_int_scalar!(i8, i16, i32, i64, i128, isize);
+
+/////////////////////////////////////////////////////////
+// Fixed-point number in Q16.16 format: 16 integer bits (with sign)
+// and 16 fractional bits stored in an i32.
+//
+// All arithmetic saturates at MIN and MAX, like `from_f64`, in debug and
+// release builds alike. Products round towards negative infinity and
+// quotients towards zero before saturating; division by zero panics.
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    pub const FRACTIONAL_BITS: u32 = 16;
+    pub const MIN: Fixed = Fixed(i32::MIN);
+    pub const MAX: Fixed = Fixed(i32::MAX);
+    pub const EPSILON: Fixed = Fixed(1);
+
+    pub const fn from_bits(bits: i32) -> Self {
+        Fixed(bits)
+    }
+
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    pub const fn from_int(value: i16) -> Self {
+        Fixed((value as i32) << Self::FRACTIONAL_BITS)
+    }
+
+    /// Rounds to the nearest representable value, saturating outside the range.
+    pub fn from_f64(value: f64) -> Self {
+        Fixed((value * (1 << Self::FRACTIONAL_BITS) as f64).round() as i32)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / (1 << Self::FRACTIONAL_BITS) as f64
+    }
+
+    fn saturate(bits: i64) -> Self {
+        Fixed(bits.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+
+impl Debug for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fixed({})", self.to_f64())
+    }
+}
+
+impl fmt::Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Fixed(self.0.saturating_add(other.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Fixed(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Fixed::saturate((self.0 as i64 * other.0 as i64) >> Self::FRACTIONAL_BITS)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self::Output {
+        Fixed::saturate(((self.0 as i64) << Self::FRACTIONAL_BITS) / other.0 as i64)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Fixed {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for Fixed {
+    fn div_assign(&mut self, other: Self) {
+        *self = *self / other;
+    }
+}
+
+impl Scalar for Fixed {
+    const ZERO: Self = Fixed(0);
+    const ONE: Self = Fixed(1 << Fixed::FRACTIONAL_BITS);
+
+    fn to_f64(self) -> f64 {
+        Fixed::to_f64(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        Fixed::from_f64(value)
+    }
+}
+
+impl Real for Fixed {
+    /// Panics for negative values, like the integer `isqrt`.
+    fn sqrt(self) -> Self {
+        assert!(self.0 >= 0, "square root of negative fixed-point number");
+        Fixed(((self.0 as u64) << Self::FRACTIONAL_BITS).isqrt() as i32)
+    }
+}
+
+// Lossless conversions only: every i8/i16 fits in the integer part and
+// every Q16.16 value is exactly representable as f64.
+impl From<i8> for Fixed {
+    fn from(value: i8) -> Self {
+        Fixed::from_int(value as i16)
+    }
+}
+
+impl From<i16> for Fixed {
+    fn from(value: i16) -> Self {
+        Fixed::from_int(value)
+    }
+}
+
+impl From<Fixed> for f64 {
+    fn from(value: Fixed) -> Self {
+        value.to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests_scalar {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(<f32 as Scalar>::ONE, 1.0);
+        assert_eq!(<i32 as Scalar>::ZERO, 0);
+        assert_eq!(Fixed::ONE.to_f64(), 1.0);
+        assert_eq!(Fixed::EPSILON.to_f64(), 1.0 / 65536.0);
+    }
+
+    #[test]
+    fn fixed_conversions() {
+        assert_eq!(Fixed::from_int(-3).to_f64(), -3.0);
+        assert_eq!(Fixed::from_f64(1.25).to_bits(), 0x0001_4000);
+        assert_eq!(Fixed::from_f64(1e9), Fixed::MAX);
+        assert_eq!(Fixed::from_f64(-1e9), Fixed::MIN);
+        assert_eq!(f64::from(Fixed::from(-7i8)), -7.0);
+    }
+
+    #[test]
+    fn fixed_arithmetic() {
+        let a = Fixed::from_f64(2.5);
+        let b = Fixed::from_f64(-0.5);
+
+        assert_eq!(a + b, Fixed::from_int(2));
+        assert_eq!(a - b, Fixed::from_int(3));
+        assert_eq!(a * b, Fixed::from_f64(-1.25));
+        assert_eq!(a / b, Fixed::from_int(-5));
+        assert_eq!(-a, Fixed::from_f64(-2.5));
+
+        let mut c = a;
+        c *= Fixed::from_int(2);
+        c -= Fixed::ONE;
+        c /= Fixed::from_int(4);
+        c += Fixed::from_f64(0.5);
+        assert_eq!(c, Fixed::from_f64(1.5));
+    }
+
+    #[test]
+    fn fixed_arithmetic_saturates() {
+        let big = Fixed::from_int(30_000);
+        assert_eq!(Fixed::MAX + Fixed::EPSILON, Fixed::MAX);
+        assert_eq!(Fixed::MIN - Fixed::EPSILON, Fixed::MIN);
+        assert_eq!(big + big, Fixed::MAX);
+        assert_eq!(-big - big, Fixed::MIN);
+        assert_eq!(-Fixed::MIN, Fixed::MAX);
+        assert_eq!(-Fixed::MAX, Fixed::from_bits(-i32::MAX));
+
+        assert_eq!(big * big, Fixed::MAX);
+        assert_eq!(big * -big, Fixed::MIN);
+        assert_eq!(Fixed::MAX * Fixed::ONE, Fixed::MAX);
+        assert_eq!(Fixed::MIN * -Fixed::ONE, Fixed::MAX);
+        assert_eq!(big / Fixed::EPSILON, Fixed::MAX);
+        assert_eq!(-big / Fixed::EPSILON, Fixed::MIN);
+        assert_eq!(Fixed::MIN / -Fixed::ONE, Fixed::MAX);
+
+        let mut c = Fixed::MAX;
+        c += Fixed::ONE;
+        c *= Fixed::from_int(2);
+        assert_eq!(c, Fixed::MAX);
+        // values just inside the range are unaffected
+        assert_eq!(Fixed::MAX - Fixed::EPSILON + Fixed::EPSILON, Fixed::MAX);
+        assert_eq!(Fixed::from_int(-32_768) * Fixed::ONE, Fixed::MIN);
+    }
+
+    #[test]
+    #[should_panic]
+    fn fixed_division_by_zero() {
+        let _ = Fixed::ONE / Fixed::ZERO;
+    }
+
+    #[test]
+    fn fixed_sqrt() {
+        assert_eq!(Fixed::from_int(16).sqrt(), Fixed::from_int(4));
+        assert_eq!(Fixed::from_f64(0.25).sqrt(), Fixed::from_f64(0.5));
+        assert!((Fixed::from_int(2).sqrt().to_f64() - 2f64.sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    #[should_panic(expected = "negative")]
+    fn fixed_sqrt_of_negative() {
+        Fixed::from_int(-1).sqrt();
+    }
+
+    #[test]
+    fn fixed_formatting() {
+        assert_eq!(format!("{:?}", Fixed::from_f64(1.5)), "Fixed(1.5)");
+        assert_eq!(format!("{}", Fixed::from_int(-2)), "-2");
+    }
+
+    #[test]
+    fn lossy_round_trip() {
+        assert_eq!(<i32 as Scalar>::from_f64(-2.9), -2);
+        assert_eq!(<i8 as Scalar>::from_f64(300.0), i8::MAX);
+        assert_eq!(<f32 as Scalar>::from_f64(0.5), 0.5);
+        assert_eq!(Scalar::to_f64(7i64), 7.0);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::geometry::shapes::Aabb;
+use crate::spatial::{Nearest, SpatialIndex, assert_finite, in_rect};
+use crate::vector2d::Vector2D;
+
+// 2-d tree stored in an arena. Each node splits on x or y, alternating with
+// depth; smaller coordinates go left, equal or larger ones right (after a
+// rebuild equal coordinates may end up on both sides, so searches visit both).
+//
+// Removal leaves a tombstone; once tombstones outnumber the live entries the
+// tree is rebuilt balanced, which also repairs skew from sorted insertions.
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    point: Vector2D,
+    data: Option<T>,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct KdTree<T> {
+    nodes: Vec<Node<T>>,
+    root: Option<usize>,
+    len: usize,
+}
+
+impl<T> KdTree<T> {
+    pub fn new() -> Self {
+        KdTree {
+            nodes: Vec::new(),
+            root: None,
+            len: 0,
+        }
+    }
+
+    /// Balanced tree built by median splits, O(n log n).
+    ///
+    /// Panics if a point is not finite.
+    pub fn from_entries(entries: Vec<(Vector2D, T)>) -> Self {
+        entries.iter().for_each(|(p, _)| assert_finite(*p));
+        let mut tree = KdTree::new();
+        let mut entries: Vec<Option<(Vector2D, T)>> = entries.into_iter().map(Some).collect();
+        tree.len = entries.len();
+        tree.root = tree.build(&mut entries, 0);
+        tree
+    }
+
+    fn build(&mut self, entries: &mut [Option<(Vector2D, T)>], depth: usize) -> Option<usize> {
+        if entries.is_empty() {
+            return None;
+        }
+        let axis = depth % 2;
+        let mid = entries.len() / 2;
+        entries.select_nth_unstable_by(mid, |a, b| {
+            let (a, b) = (a.as_ref().unwrap(), b.as_ref().unwrap());
+            a.0[axis].total_cmp(&b.0[axis])
+        });
+
+        let (point, data) = entries[mid].take().unwrap();
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            point,
+            data: Some(data),
+            axis,
+            left: None,
+            right: None,
+        });
+        let (left, right) = entries.split_at_mut(mid);
+        self.nodes[id].left = self.build(left, depth + 1);
+        self.nodes[id].right = self.build(&mut right[1..], depth + 1);
+        Some(id)
+    }
+
+    fn rebuild(&mut self) {
+        let entries: Vec<(Vector2D, T)> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .filter_map(|n| n.data.map(|d| (n.point, d)))
+            .collect();
+        *self = KdTree::from_entries(entries);
+    }
+
+    /// Depth of the deepest node, 0 for an empty tree.
+    pub fn depth(&self) -> usize {
+        fn depth_of<T>(nodes: &[Node<T>], node: Option<usize>) -> usize {
+            node.map_or(0, |i| {
+                1 + depth_of(nodes, nodes[i].left).max(depth_of(nodes, nodes[i].right))
+            })
+        }
+        depth_of(&self.nodes, self.root)
+    }
+
+    fn nearest_in<'a>(
+        &'a self,
+        node: Option<usize>,
+        target: Vector2D,
+        nearest: &mut Nearest<'a, T>,
+    ) {
+        let Some(i) = node else { return };
+        let n = &self.nodes[i];
+        if let Some(data) = &n.data {
+            nearest.offer(target, n.point, data);
+        }
+
+        let diff = target[n.axis] - n.point[n.axis];
+        let (near, far) = if diff < 0.0 {
+            (n.left, n.right)
+        } else {
+            (n.right, n.left)
+        };
+        self.nearest_in(near, target, nearest);
+        if diff * diff < nearest.worst() {
+            self.nearest_in(far, target, nearest);
+        }
+    }
+
+    // Visits the subtrees whose half-planes overlap [low, high] along the node's axis
+    fn collect<'a>(
+        &'a self,
+        node: Option<usize>,
+        range: &impl Fn(usize) -> (f32, f32),
+        accept: &impl Fn(Vector2D) -> bool,
+        found: &mut Vec<(Vector2D, &'a T)>,
+    ) {
+        let Some(i) = node else { return };
+        let n = &self.nodes[i];
+        if let Some(data) = &n.data
+            && accept(n.point)
+        {
+            found.push((n.point, data));
+        }
+
+        let (low, high) = range(n.axis);
+        let split = n.point[n.axis];
+        if low <= split {
+            self.collect(n.left, range, accept, found);
+        }
+        if high >= split {
+            self.collect(n.right, range, accept, found);
+        }
+    }
+}
+
+impl<T> Default for KdTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq> SpatialIndex<T> for KdTree<T> {
+    fn insert(&mut self, point: Vector2D, data: T) {
+        assert_finite(point);
+        let id = self.nodes.len();
+        let mut parent = None;
+        let mut node = self.root;
+        let mut depth = 0;
+        while let Some(i) = node {
+            let n = &self.nodes[i];
+            let go_left = point[n.axis] < n.point[n.axis];
+            parent = Some((i, go_left));
+            node = if go_left { n.left } else { n.right };
+            depth += 1;
+        }
+
+        self.nodes.push(Node {
+            point,
+            data: Some(data),
+            axis: depth % 2,
+            left: None,
+            right: None,
+        });
+        match parent {
+            None => self.root = Some(id),
+            Some((i, true)) => self.nodes[i].left = Some(id),
+            Some((i, false)) => self.nodes[i].right = Some(id),
+        }
+        self.len += 1;
+    }
+
+    fn remove(&mut self, point: Vector2D, data: &T) -> bool {
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(i) = stack.pop() {
+            let n = &self.nodes[i];
+            if n.point == point && n.data.as_ref() == Some(data) {
+                self.nodes[i].data = None;
+                self.len -= 1;
+                if self.nodes.len() > 16 && self.len < self.nodes.len() / 2 {
+                    self.rebuild();
+                }
+                return true;
+            }
+            let (coordinate, split) = (point[n.axis], n.point[n.axis]);
+            if coordinate <= split {
+                stack.extend(n.left);
+            }
+            if coordinate >= split {
+                stack.extend(n.right);
+            }
+        }
+        false
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn nearest(&self, target: Vector2D, k: usize) -> Vec<(Vector2D, &T)> {
+        let mut nearest = Nearest::new(k);
+        self.nearest_in(self.root, target, &mut nearest);
+        nearest.into_sorted()
+    }
+
+    fn within_radius(&self, center: Vector2D, radius: f32) -> Vec<(Vector2D, &T)> {
+        let mut found = Vec::new();
+        if radius.is_nan() || radius < 0.0 {
+            return found;
+        }
+        self.collect(
+            self.root,
+            &|axis| (center[axis] - radius, center[axis] + radius),
+            &|p| p.distance_squared(&center) <= radius * radius,
+            &mut found,
+        );
+        found
+    }
+
+    fn within_rect(&self, rect: &Aabb) -> Vec<(Vector2D, &T)> {
+        let mut found = Vec::new();
+        self.collect(
+            self.root,
+            &|axis| (rect.min[axis], rect.max[axis]),
+            &|p| in_rect(rect, p),
+            &mut found,
+        );
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests_kdtree {
+    use super::*;
+    use crate::spatial::tests_spatial::check_against_brute_force;
+    use crate::testing::random_points;
+
+    #[test]
+    fn matches_brute_force() {
+        check_against_brute_force(KdTree::new());
+    }
+
+    #[test]
+    fn matches_brute_force_when_bulk_built() {
+        let mut tree = KdTree::from_entries(vec![(Vector2D::new(50.0, 50.0), usize::MAX)]);
+        tree.insert(Vector2D::new(10.0, 10.0), usize::MAX - 1);
+        assert!(tree.remove(Vector2D::new(50.0, 50.0), &usize::MAX));
+        assert!(tree.remove(Vector2D::new(10.0, 10.0), &(usize::MAX - 1)));
+        check_against_brute_force(tree);
+    }
+
+    #[test]
+    fn bulk_build_is_balanced() {
+        let entries: Vec<(Vector2D, usize)> = random_points(1023, 5)
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| (p, i))
+            .collect();
+        let tree = KdTree::from_entries(entries);
+        assert_eq!(tree.len(), 1023);
+        assert_eq!(tree.depth(), 10);
+    }
+
+    #[test]
+    fn sorted_insertions_are_rebalanced_by_removal() {
+        let mut tree = KdTree::new();
+        for i in 0..200 {
+            tree.insert(Vector2D::new(i as f32, i as f32), i);
+        }
+        assert_eq!(tree.depth(), 200);
+        for i in 0..101 {
+            assert!(tree.remove(Vector2D::new(i as f32, i as f32), &i));
+        }
+        assert_eq!(tree.len(), 99);
+        assert!(tree.depth() <= 7);
+        assert_eq!(
+            tree.nearest(Vector2D::new(0.0, 0.0), 1),
+            vec![(Vector2D::new(101.0, 101.0), &101)]
+        );
+    }
+
+    #[test]
+    fn identical_points() {
+        let mut tree = KdTree::new();
+        for i in 0..50 {
+            tree.insert(Vector2D::new(1.0, 1.0), i);
+        }
+        assert_eq!(tree.within_radius(Vector2D::new(1.0, 1.0), 0.0).len(), 50);
+        assert!(tree.remove(Vector2D::new(1.0, 1.0), &25));
+        assert_eq!(tree.nearest(Vector2D::ZERO, 100).len(), 49);
+    }
+
+    #[test]
+    #[should_panic(expected = "point must be finite")]
+    fn rejects_non_finite_points() {
+        KdTree::from_entries(vec![
+            (Vector2D::new(1.0, 1.0), 0),
+            (Vector2D::new(f32::INFINITY, 0.0), 1),
+        ]);
+    }
+}
@@ -0,0 +1,323 @@
+use crate::geometry::shapes::Aabb;
+use crate::vector2d::Vector2D;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+pub mod kdtree;
+pub mod quadtree;
+
+/// Point index answering nearest-neighbour and range queries.
+///
+/// Every entry is a point with attached data; several entries may share a
+/// point. Points must be finite: inserting a NaN or infinite coordinate
+/// panics in every implementation. Range queries are closed (points on the
+/// circle or the box edge are included) and return entries in no particular
+/// order.
+pub trait SpatialIndex<T: PartialEq> {
+    /// Panics if `point` is not finite.
+    fn insert(&mut self, point: Vector2D, data: T);
+
+    /// Removes one entry with this point and data, returns whether one was found.
+    fn remove(&mut self, point: Vector2D, data: &T) -> bool;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Up to `k` entries closest to `target`, nearest first.
+    fn nearest(&self, target: Vector2D, k: usize) -> Vec<(Vector2D, &T)>;
+
+    /// Empty for a negative or NaN radius.
+    fn within_radius(&self, center: Vector2D, radius: f32) -> Vec<(Vector2D, &T)>;
+
+    fn within_rect(&self, rect: &Aabb) -> Vec<(Vector2D, &T)>;
+}
+
+/// Linear scan over all entries; the reference the trees are tested against.
+#[derive(Clone, Debug)]
+pub struct BruteForce<T> {
+    entries: Vec<(Vector2D, T)>,
+}
+
+impl<T> BruteForce<T> {
+    pub fn new() -> Self {
+        BruteForce {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> Default for BruteForce<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PartialEq> SpatialIndex<T> for BruteForce<T> {
+    fn insert(&mut self, point: Vector2D, data: T) {
+        assert_finite(point);
+        self.entries.push((point, data));
+    }
+
+    fn remove(&mut self, point: Vector2D, data: &T) -> bool {
+        match self
+            .entries
+            .iter()
+            .position(|(p, d)| *p == point && d == data)
+        {
+            Some(i) => {
+                self.entries.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn nearest(&self, target: Vector2D, k: usize) -> Vec<(Vector2D, &T)> {
+        let mut nearest = Nearest::new(k);
+        for (point, data) in &self.entries {
+            nearest.offer(target, *point, data);
+        }
+        nearest.into_sorted()
+    }
+
+    fn within_radius(&self, center: Vector2D, radius: f32) -> Vec<(Vector2D, &T)> {
+        if radius.is_nan() || radius < 0.0 {
+            return Vec::new();
+        }
+        self.entries
+            .iter()
+            .filter(|(p, _)| p.distance_squared(&center) <= radius * radius)
+            .map(|(p, d)| (*p, d))
+            .collect()
+    }
+
+    fn within_rect(&self, rect: &Aabb) -> Vec<(Vector2D, &T)> {
+        self.entries
+            .iter()
+            .filter(|(p, _)| in_rect(rect, *p))
+            .map(|(p, d)| (*p, d))
+            .collect()
+    }
+}
+
+pub(crate) fn assert_finite(point: Vector2D) {
+    assert!(
+        point.x.is_finite() && point.y.is_finite(),
+        "point must be finite"
+    );
+}
+
+// Exact closed test, unlike `Shape::contains_point` which allows a tolerance
+pub(crate) fn in_rect(rect: &Aabb, p: Vector2D) -> bool {
+    p.x >= rect.min.x && p.x <= rect.max.x && p.y >= rect.min.y && p.y <= rect.max.y
+}
+
+pub(crate) fn rect_distance_squared(rect: &Aabb, p: Vector2D) -> f32 {
+    let dx = (rect.min.x - p.x).max(0.0).max(p.x - rect.max.x);
+    let dy = (rect.min.y - p.y).max(0.0).max(p.y - rect.max.y);
+    dx * dx + dy * dy
+}
+
+struct Candidate<'a, T> {
+    distance_squared: f32,
+    point: Vector2D,
+    data: &'a T,
+}
+
+impl<T> PartialEq for Candidate<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Candidate<'_, T> {}
+
+impl<T> PartialOrd for Candidate<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Candidate<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance_squared.total_cmp(&other.distance_squared)
+    }
+}
+
+/// Keeps the `k` best candidates seen so far in a max-heap.
+pub(crate) struct Nearest<'a, T> {
+    k: usize,
+    heap: BinaryHeap<Candidate<'a, T>>,
+}
+
+impl<'a, T> Nearest<'a, T> {
+    pub(crate) fn new(k: usize) -> Self {
+        Nearest {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    pub(crate) fn offer(&mut self, target: Vector2D, point: Vector2D, data: &'a T) {
+        let distance_squared = point.distance_squared(&target);
+        if distance_squared < self.worst() {
+            self.heap.push(Candidate {
+                distance_squared,
+                point,
+                data,
+            });
+            if self.heap.len() > self.k {
+                self.heap.pop();
+            }
+        }
+    }
+
+    /// Squared distance a candidate has to beat to be kept.
+    pub(crate) fn worst(&self) -> f32 {
+        if self.k == 0 {
+            f32::NEG_INFINITY
+        } else if self.heap.len() < self.k {
+            f32::INFINITY
+        } else {
+            self.heap.peek().unwrap().distance_squared
+        }
+    }
+
+    pub(crate) fn into_sorted(self) -> Vec<(Vector2D, &'a T)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|c| (c.point, c.data))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests_spatial {
+    use super::*;
+    use crate::testing::random_points;
+
+    fn sorted(mut entries: Vec<(Vector2D, &usize)>) -> Vec<usize> {
+        entries.sort_by_key(|(_, d)| **d);
+        entries.into_iter().map(|(_, d)| *d).collect()
+    }
+
+    /// Runs a random workload against `index` and a brute-force reference.
+    pub(crate) fn check_against_brute_force(mut index: impl SpatialIndex<usize>) {
+        let mut reference = BruteForce::new();
+        let points = random_points(600, 3);
+
+        for (i, &p) in points.iter().enumerate() {
+            index.insert(p, i);
+            reference.insert(p, i);
+        }
+        // duplicate points with distinct data
+        for (i, &p) in points.iter().take(20).enumerate() {
+            index.insert(p, 1000 + i);
+            reference.insert(p, 1000 + i);
+        }
+        for i in (0..points.len()).step_by(3) {
+            assert!(index.remove(points[i], &i));
+            assert!(reference.remove(points[i], &i));
+        }
+        assert!(!index.remove(points[0], &0));
+        assert!(!index.remove(Vector2D::new(-1.0, -1.0), &1));
+        assert_eq!(index.len(), reference.len());
+
+        for (q, target) in random_points(50, 11).into_iter().enumerate() {
+            for k in [0, 1, 5, 40] {
+                let found: Vec<f32> = index
+                    .nearest(target, k)
+                    .iter()
+                    .map(|(p, _)| p.distance_squared(&target))
+                    .collect();
+                let expected: Vec<f32> = reference
+                    .nearest(target, k)
+                    .iter()
+                    .map(|(p, _)| p.distance_squared(&target))
+                    .collect();
+                assert_eq!(found, expected, "query {} k {}", q, k);
+            }
+
+            let radius = (q % 7) as f32 * 3.0;
+            assert_eq!(
+                sorted(index.within_radius(target, radius)),
+                sorted(reference.within_radius(target, radius))
+            );
+            // squaring would turn these into positive radii
+            assert!(index.within_radius(target, -radius - 1.0).is_empty());
+            assert!(index.within_radius(target, f32::NAN).is_empty());
+
+            let rect = Aabb::new(target, target + Vector2D::new(15.0, (q % 5) as f32 * 6.0));
+            assert_eq!(
+                sorted(index.within_rect(&rect)),
+                sorted(reference.within_rect(&rect))
+            );
+        }
+
+        // points far outside the initial extent
+        let far = Vector2D::new(-5000.0, 12000.0);
+        index.insert(far, 9999);
+        assert_eq!(
+            index.nearest(Vector2D::new(-4000.0, 10000.0), 1)[0],
+            (far, &9999)
+        );
+
+        // emptying the index
+        for (p, d) in reference.entries.clone() {
+            assert!(index.remove(p, &d));
+        }
+        assert!(index.remove(far, &9999));
+        assert!(index.is_empty());
+        assert!(index.nearest(Vector2D::ZERO, 3).is_empty());
+    }
+
+    #[test]
+    fn brute_force_queries() {
+        let mut index = BruteForce::new();
+        index.insert(Vector2D::new(0.0, 0.0), 'a');
+        index.insert(Vector2D::new(3.0, 0.0), 'b');
+        index.insert(Vector2D::new(0.0, 5.0), 'c');
+
+        let nearest = index.nearest(Vector2D::new(2.0, 0.0), 2);
+        assert_eq!(
+            nearest,
+            vec![
+                (Vector2D::new(3.0, 0.0), &'b'),
+                (Vector2D::new(0.0, 0.0), &'a')
+            ]
+        );
+        assert_eq!(index.within_radius(Vector2D::ZERO, 3.0).len(), 2);
+        assert_eq!(
+            index.within_rect(&Aabb::new(
+                Vector2D::new(-1.0, 1.0),
+                Vector2D::new(1.0, 5.0)
+            )),
+            vec![(Vector2D::new(0.0, 5.0), &'c')]
+        );
+        assert!(index.remove(Vector2D::new(3.0, 0.0), &'b'));
+        assert!(!index.remove(Vector2D::new(3.0, 0.0), &'b'));
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "point must be finite")]
+    fn brute_force_rejects_non_finite_points() {
+        BruteForce::new().insert(Vector2D::new(0.0, f32::NAN), 0);
+    }
+
+    #[test]
+    fn nearest_with_k_zero_or_larger_than_len() {
+        let mut index = BruteForce::new();
+        index.insert(Vector2D::new(1.0, 1.0), 1);
+        assert!(index.nearest(Vector2D::ZERO, 0).is_empty());
+        assert_eq!(index.nearest(Vector2D::ZERO, 10).len(), 1);
+    }
+}
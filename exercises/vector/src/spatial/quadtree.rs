@@ -0,0 +1,390 @@
+use crate::geometry::shapes::Aabb;
+use crate::spatial::{Nearest, SpatialIndex, assert_finite, in_rect, rect_distance_squared};
+use crate::vector2d::Vector2D;
+
+// Region quadtree. Leaves hold up to `capacity` entries and split into four
+// quadrants when they overflow; points on a dividing line belong to the upper
+// or right quadrant. Splitting stops at `MAX_DEPTH`, so many identical points
+// simply share one large leaf.
+//
+// Inserting outside the bounds doubles the root towards the point until it
+// fits, keeping the old root as one quadrant of the new one. Non-finite
+// points could never fit and are rejected.
+//
+// Branches keep the number of entries below them, so removal can decide
+// whether to collapse a branch without walking its subtree.
+
+const MAX_DEPTH: usize = 24;
+const DEFAULT_CAPACITY: usize = 8;
+
+#[derive(Clone, Debug)]
+enum Node<T> {
+    Leaf(Vec<(Vector2D, T)>),
+    Branch(Box<[Node<T>; 4]>, usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct QuadTree<T> {
+    root: Node<T>,
+    bounds: Aabb,
+    capacity: usize,
+    len: usize,
+}
+
+fn quadrant(bounds: &Aabb, p: Vector2D) -> usize {
+    let mid = bounds.center();
+    (p.x >= mid.x) as usize + 2 * (p.y >= mid.y) as usize
+}
+
+fn child_bounds(bounds: &Aabb, quadrant: usize) -> Aabb {
+    let mid = bounds.center();
+    let (min_x, max_x) = if quadrant & 1 == 0 {
+        (bounds.min.x, mid.x)
+    } else {
+        (mid.x, bounds.max.x)
+    };
+    let (min_y, max_y) = if quadrant & 2 == 0 {
+        (bounds.min.y, mid.y)
+    } else {
+        (mid.y, bounds.max.y)
+    };
+    Aabb {
+        min: Vector2D::new(min_x, min_y),
+        max: Vector2D::new(max_x, max_y),
+    }
+}
+
+impl<T> Node<T> {
+    fn insert(&mut self, bounds: &Aabb, point: Vector2D, data: T, depth: usize, capacity: usize) {
+        match self {
+            Node::Branch(children, count) => {
+                *count += 1;
+                let q = quadrant(bounds, point);
+                children[q].insert(&child_bounds(bounds, q), point, data, depth + 1, capacity);
+            }
+            Node::Leaf(entries) => {
+                entries.push((point, data));
+                if entries.len() > capacity && depth < MAX_DEPTH {
+                    let mut children: [Node<T>; 4] =
+                        std::array::from_fn(|_| Node::Leaf(Vec::new()));
+                    let count = entries.len();
+                    for (p, d) in entries.drain(..) {
+                        let q = quadrant(bounds, p);
+                        children[q].insert(&child_bounds(bounds, q), p, d, depth + 1, capacity);
+                    }
+                    *self = Node::Branch(Box::new(children), count);
+                }
+            }
+        }
+    }
+
+    fn into_entries(self, out: &mut Vec<(Vector2D, T)>) {
+        match self {
+            Node::Leaf(entries) => out.extend(entries),
+            Node::Branch(children, _) => {
+                for child in *children {
+                    child.into_entries(out);
+                }
+            }
+        }
+    }
+}
+
+impl<T: PartialEq> Node<T> {
+    fn remove(&mut self, bounds: &Aabb, point: Vector2D, data: &T, capacity: usize) -> bool {
+        match self {
+            Node::Leaf(entries) => {
+                match entries.iter().position(|(p, d)| *p == point && d == data) {
+                    Some(i) => {
+                        entries.swap_remove(i);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Node::Branch(children, count) => {
+                let q = quadrant(bounds, point);
+                if !children[q].remove(&child_bounds(bounds, q), point, data, capacity) {
+                    return false;
+                }
+                *count -= 1;
+                // collapse branches that no longer need splitting
+                if *count <= capacity {
+                    let mut entries = Vec::new();
+                    std::mem::replace(self, Node::Leaf(Vec::new())).into_entries(&mut entries);
+                    *self = Node::Leaf(entries);
+                }
+                true
+            }
+        }
+    }
+}
+
+impl<T> QuadTree<T> {
+    pub fn new(bounds: Aabb) -> Self {
+        Self::with_capacity(bounds, DEFAULT_CAPACITY)
+    }
+
+    /// `capacity` is the number of entries a leaf holds before splitting.
+    pub fn with_capacity(bounds: Aabb, capacity: usize) -> Self {
+        // a degenerate box could never be split or grown
+        let size = bounds.size();
+        let bounds = Aabb::new(
+            bounds.min,
+            bounds.min + Vector2D::new(size.x.max(1.0), size.y.max(1.0)),
+        );
+        QuadTree {
+            root: Node::Leaf(Vec::new()),
+            bounds,
+            capacity: capacity.max(1),
+            len: 0,
+        }
+    }
+
+    pub fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    fn grow_towards(&mut self, point: Vector2D) {
+        let size = self.bounds.size();
+        let (min, max) = (self.bounds.min, self.bounds.max);
+        let (min_x, max_x, old_right) = if point.x < min.x {
+            (min.x - size.x, max.x, true)
+        } else {
+            (min.x, max.x + size.x, false)
+        };
+        let (min_y, max_y, old_top) = if point.y < min.y {
+            (min.y - size.y, max.y, true)
+        } else {
+            (min.y, max.y + size.y, false)
+        };
+        self.bounds = Aabb {
+            min: Vector2D::new(min_x, min_y),
+            max: Vector2D::new(max_x, max_y),
+        };
+
+        if let Node::Branch(..) = self.root {
+            let old = std::mem::replace(&mut self.root, Node::Leaf(Vec::new()));
+            let mut children: [Node<T>; 4] = std::array::from_fn(|_| Node::Leaf(Vec::new()));
+            children[old_right as usize + 2 * old_top as usize] = old;
+            self.root = Node::Branch(Box::new(children), self.len);
+        }
+    }
+
+    fn nearest_in<'a>(
+        node: &'a Node<T>,
+        bounds: &Aabb,
+        target: Vector2D,
+        nearest: &mut Nearest<'a, T>,
+    ) {
+        match node {
+            Node::Leaf(entries) => {
+                for (p, d) in entries {
+                    nearest.offer(target, *p, d);
+                }
+            }
+            Node::Branch(children, _) => {
+                // closest quadrants first so the pruning bound tightens quickly
+                let mut order: Vec<(f32, usize)> = (0..4)
+                    .map(|q| (rect_distance_squared(&child_bounds(bounds, q), target), q))
+                    .collect();
+                order.sort_by(|a, b| a.0.total_cmp(&b.0));
+                for (distance_squared, q) in order {
+                    if distance_squared < nearest.worst() {
+                        Self::nearest_in(&children[q], &child_bounds(bounds, q), target, nearest);
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect<'a>(
+        node: &'a Node<T>,
+        bounds: &Aabb,
+        visit: &impl Fn(&Aabb) -> bool,
+        accept: &impl Fn(Vector2D) -> bool,
+        found: &mut Vec<(Vector2D, &'a T)>,
+    ) {
+        match node {
+            Node::Leaf(entries) => {
+                found.extend(
+                    entries
+                        .iter()
+                        .filter(|(p, _)| accept(*p))
+                        .map(|(p, d)| (*p, d)),
+                );
+            }
+            Node::Branch(children, _) => {
+                for (q, child) in children.iter().enumerate() {
+                    let child_bounds = child_bounds(bounds, q);
+                    if visit(&child_bounds) {
+                        Self::collect(child, &child_bounds, visit, accept, found);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: PartialEq> SpatialIndex<T> for QuadTree<T> {
+    fn insert(&mut self, point: Vector2D, data: T) {
+        assert_finite(point);
+        while !in_rect(&self.bounds, point) {
+            self.grow_towards(point);
+        }
+        self.root
+            .insert(&self.bounds, point, data, 0, self.capacity);
+        self.len += 1;
+    }
+
+    fn remove(&mut self, point: Vector2D, data: &T) -> bool {
+        if !in_rect(&self.bounds, point)
+            || !self.root.remove(&self.bounds, point, data, self.capacity)
+        {
+            return false;
+        }
+        self.len -= 1;
+        true
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn nearest(&self, target: Vector2D, k: usize) -> Vec<(Vector2D, &T)> {
+        let mut nearest = Nearest::new(k);
+        Self::nearest_in(&self.root, &self.bounds, target, &mut nearest);
+        nearest.into_sorted()
+    }
+
+    fn within_radius(&self, center: Vector2D, radius: f32) -> Vec<(Vector2D, &T)> {
+        let mut found = Vec::new();
+        if radius.is_nan() || radius < 0.0 {
+            return found;
+        }
+        Self::collect(
+            &self.root,
+            &self.bounds,
+            &|b| rect_distance_squared(b, center) <= radius * radius,
+            &|p| p.distance_squared(&center) <= radius * radius,
+            &mut found,
+        );
+        found
+    }
+
+    fn within_rect(&self, rect: &Aabb) -> Vec<(Vector2D, &T)> {
+        let mut found = Vec::new();
+        Self::collect(
+            &self.root,
+            &self.bounds,
+            &|b| {
+                b.min.x <= rect.max.x
+                    && b.max.x >= rect.min.x
+                    && b.min.y <= rect.max.y
+                    && b.max.y >= rect.min.y
+            },
+            &|p| in_rect(rect, p),
+            &mut found,
+        );
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests_quadtree {
+    use super::*;
+    use crate::spatial::tests_spatial::check_against_brute_force;
+
+    fn unit_bounds() -> Aabb {
+        Aabb::new(Vector2D::new(0.0, 0.0), Vector2D::new(100.0, 100.0))
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        check_against_brute_force(QuadTree::new(unit_bounds()));
+    }
+
+    #[test]
+    fn matches_brute_force_with_small_leaves_and_tiny_bounds() {
+        check_against_brute_force(QuadTree::with_capacity(
+            Aabb::new(Vector2D::new(40.0, 40.0), Vector2D::new(40.0, 40.0)),
+            1,
+        ));
+    }
+
+    #[test]
+    fn grows_to_fit_points_outside_bounds() {
+        let mut tree = QuadTree::with_capacity(unit_bounds(), 2);
+        for i in 0..10 {
+            tree.insert(Vector2D::new(i as f32 * 10.0, 5.0), i);
+        }
+        tree.insert(Vector2D::new(-250.0, 430.0), 100);
+        tree.insert(Vector2D::new(1000.0, -1000.0), 101);
+
+        let bounds = tree.bounds();
+        assert!(in_rect(&bounds, Vector2D::new(-250.0, 430.0)));
+        assert!(in_rect(&bounds, Vector2D::new(1000.0, -1000.0)));
+        assert_eq!(tree.len(), 12);
+        assert_eq!(tree.within_rect(&bounds).len(), 12);
+        assert_eq!(tree.nearest(Vector2D::new(35.0, 5.0), 2).len(), 2);
+    }
+
+    #[test]
+    fn removal_collapses_branches() {
+        let mut tree = QuadTree::with_capacity(unit_bounds(), 2);
+        for i in 0..20 {
+            tree.insert(Vector2D::new(i as f32 * 5.0, i as f32 * 5.0), i);
+        }
+        assert!(matches!(tree.root, Node::Branch(_, 20)));
+        for i in 0..18 {
+            assert!(tree.remove(Vector2D::new(i as f32 * 5.0, i as f32 * 5.0), &i));
+        }
+        assert!(matches!(tree.root, Node::Leaf(_)));
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn identical_points_stop_splitting() {
+        let mut tree = QuadTree::with_capacity(unit_bounds(), 1);
+        for i in 0..100 {
+            tree.insert(Vector2D::new(33.0, 33.0), i);
+        }
+        assert_eq!(
+            tree.within_radius(Vector2D::new(33.0, 33.0), 0.0).len(),
+            100
+        );
+        assert!(tree.remove(Vector2D::new(33.0, 33.0), &99));
+        assert_eq!(tree.len(), 99);
+    }
+
+    #[test]
+    fn branches_track_their_entry_count() {
+        let mut tree = QuadTree::with_capacity(unit_bounds(), 2);
+        for (i, p) in crate::testing::random_points(200, 5)
+            .into_iter()
+            .enumerate()
+        {
+            tree.insert(p, i);
+        }
+        // growing wraps the root in a new branch
+        tree.insert(Vector2D::new(-300.0, 250.0), 200);
+
+        fn check(node: &Node<usize>) -> usize {
+            match node {
+                Node::Leaf(entries) => entries.len(),
+                Node::Branch(children, count) => {
+                    assert_eq!(children.iter().map(check).sum::<usize>(), *count);
+                    *count
+                }
+            }
+        }
+        assert_eq!(check(&tree.root), 201);
+    }
+
+    #[test]
+    #[should_panic(expected = "point must be finite")]
+    fn rejects_non_finite_points() {
+        let mut tree = QuadTree::new(unit_bounds());
+        tree.insert(Vector2D::new(f32::INFINITY, 0.0), 0);
+    }
+}
@@ -0,0 +1,15 @@
+use crate::vector2d::Vector2D;
+
+// Helpers shared by the test modules.
+
+/// Reproducible pseudo-random points in [0, 100) x [0, 100) (xorshift64).
+pub fn random_points(count: usize, seed: u64) -> Vec<Vector2D> {
+    let mut state = seed.max(1);
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 40) as f32 / (1u64 << 24) as f32 * 100.0
+    };
+    (0..count).map(|_| Vector2D::new(next(), next())).collect()
+}
@@ -1,27 +1,44 @@
+use crate::scalar::{Fixed, Float, Real, Scalar};
+use crate::vectorn::Vector;
+
+// Vector2D is a thin wrapper over `Vector<T, 2>`: it keeps the named `x`
+// and `y` fields (struct literals, field access and the `Debug` output all
+// rely on them), while the arithmetic shared by every dimension is done by
+// converting to `Vector<T, 2>`, which is free for two `Copy` components.
+// Only what is specific to the plane (perp_dot, angles, rotation, ...) is
+// implemented here.
+
+// Component type defaults to f32, so `Vector2D` alone means `Vector2D<f32>`
 #[derive(Default, Copy, Clone, PartialEq, Debug)]
-pub struct Vector2D {
-    pub x: f32,
-    pub y: f32,
+pub struct Vector2D<T = f32> {
+    pub x: T,
+    pub y: T,
 }
 
-impl Vector2D {
-    pub const ZERO: Vector2D = Vector2D { x: 0.0, y: 0.0 };
-    pub const UNIT_X: Vector2D = Vector2D { x: 1.0, y: 0.0 };
-    pub const UNIT_Y: Vector2D = Vector2D { x: 0.0, y: 1.0 };
-
-    pub fn new(x: f32, y: f32) -> Self {
+impl<T: Scalar> Vector2D<T> {
+    pub const ZERO: Vector2D<T> = Vector2D {
+        x: T::ZERO,
+        y: T::ZERO,
+    };
+    pub const UNIT_X: Vector2D<T> = Vector2D {
+        x: T::ONE,
+        y: T::ZERO,
+    };
+    pub const UNIT_Y: Vector2D<T> = Vector2D {
+        x: T::ZERO,
+        y: T::ONE,
+    };
+
+    pub fn new(x: T, y: T) -> Self {
         Vector2D { x, y }
     }
 
-    pub fn length(&self) -> f32 {
-        ((self.x * self.x) + (self.y * self.y)).sqrt()
+    pub fn length_squared(&self) -> T {
+        Vector::from(*self).length_squared()
     }
 
-    pub fn scale(&self, scale: f32) -> Self {
-        Vector2D {
-            x: self.x * scale,
-            y: self.y * scale,
-        }
+    pub fn scale(&self, scale: T) -> Self {
+        Vector::from(*self).scale(scale).into()
     }
 
     // self passed by value
@@ -31,41 +48,290 @@ impl Vector2D {
     //         y: self.y * value,
     //     }
     // }
+
+    pub fn dot(&self, other: &Self) -> T {
+        Vector::from(*self).dot(&Vector::from(*other))
+    }
+
+    /// z component of the 3D cross product; positive when `other` is
+    /// counter-clockwise from `self`.
+    pub fn perp_dot(&self, other: &Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// `self` rotated by 90 degrees counter-clockwise.
+    pub fn perpendicular(&self) -> Self {
+        Vector2D {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
+    pub fn distance_squared(&self, other: &Self) -> T {
+        (*other - *self).length_squared()
+    }
+
+    /// Linear interpolation: `t = 0` gives `self`, `t = 1` gives `other`.
+    /// `t` is not clamped, so values outside [0, 1] extrapolate.
+    pub fn lerp(&self, other: &Self, t: T) -> Self {
+        *self + (*other - *self).scale(t)
+    }
+
+    /// Lossless conversion to another scalar type, e.g. `Vector2D<i16>` -> `Vector2D<f32>`.
+    pub fn cast<U: Scalar + From<T>>(self) -> Vector2D<U> {
+        Vector2D {
+            x: U::from(self.x),
+            y: U::from(self.y),
+        }
+    }
+
+    /// Conversion through f64 with `as` semantics: floats are truncated
+    /// towards zero and saturated when converted to integers.
+    pub fn cast_lossy<U: Scalar>(self) -> Vector2D<U> {
+        Vector2D {
+            x: U::from_f64(self.x.to_f64()),
+            y: U::from_f64(self.y.to_f64()),
+        }
+    }
 }
 
-impl std::ops::Neg for Vector2D {
-    type Output = Self;
+impl<T: Real> Vector2D<T> {
+    pub fn length(&self) -> T {
+        self.length_squared().sqrt()
+    }
 
-    fn neg(self) -> Self::Output {
+    pub fn distance(&self, other: &Self) -> T {
+        self.distance_squared(other).sqrt()
+    }
+}
+
+impl<T: Float> Vector2D<T> {
+    pub fn from_polar(radius: T, angle: T) -> Self {
         Vector2D {
-            x: -self.x,
-            y: -self.y,
+            x: radius * angle.cos(),
+            y: radius * angle.sin(),
+        }
+    }
+
+    /// (length, angle) with the angle in (-pi, pi].
+    pub fn to_polar(&self) -> (T, T) {
+        (self.length(), self.angle())
+    }
+
+    /// Unit vector in the same direction, `None` for zero, infinite or NaN vectors.
+    pub fn normalize(&self) -> Option<Self> {
+        // dividing by the largest component first keeps length_squared from
+        // underflowing to zero for tiny vectors
+        let (ax, ay) = (self.x.abs(), self.y.abs());
+        let largest = if ax > ay { ax } else { ay };
+        if largest == T::ZERO || !largest.is_finite() || ax.is_nan() || ay.is_nan() {
+            return None;
+        }
+        let scaled = *self / largest;
+        Some(scaled / scaled.length())
+    }
+
+    /// Angle from the positive x axis in (-pi, pi].
+    pub fn angle(&self) -> T {
+        self.y.atan2(self.x)
+    }
+
+    /// Unsigned angle between the vectors in [0, pi]; zero if either is the zero vector.
+    pub fn angle_between(&self, other: &Self) -> T {
+        self.perp_dot(other).abs().atan2(self.dot(other))
+    }
+
+    /// Counter-clockwise rotation by `angle` radians.
+    pub fn rotate(&self, angle: T) -> Self {
+        let (sin, cos) = (angle.sin(), angle.cos());
+        Vector2D {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+
+    /// Component of `self` parallel to `onto`; `None` when `onto` is the zero vector.
+    pub fn project_onto(&self, onto: &Self) -> Option<Self> {
+        let denominator = onto.length_squared();
+        if denominator == T::ZERO || !denominator.is_finite() {
+            return None;
+        }
+        Some(onto.scale(self.dot(onto) / denominator))
+    }
+
+    /// Mirror image of `self` in the line perpendicular to `normal`, i.e. the
+    /// velocity after bouncing off a surface with that normal. The normal does
+    /// not have to be of unit length; a zero normal leaves `self` unchanged.
+    pub fn reflect(&self, normal: &Self) -> Self {
+        match self.project_onto(normal) {
+            Some(projection) => *self - projection.scale(T::ONE + T::ONE),
+            None => *self,
+        }
+    }
+
+    /// Same direction with the length limited to [min, max]; the zero vector stays zero.
+    pub fn clamp_length(&self, min: T, max: T) -> Self {
+        let length = self.length();
+        if length == T::ZERO {
+            *self
+        } else if length < min {
+            self.scale(min / length)
+        } else if length > max {
+            self.scale(max / length)
+        } else {
+            *self
         }
     }
 }
 
-impl std::ops::Add for Vector2D {
+impl<T: Scalar> std::ops::Neg for Vector2D<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        (-Vector::from(self)).into()
+    }
+}
+
+impl<T: Scalar> std::ops::Add for Vector2D<T> {
     type Output = Self;
 
     fn add(self, other: Self) -> Self::Output {
-        Vector2D {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
+        (Vector::from(self) + Vector::from(other)).into()
     }
 }
 
-impl std::ops::Sub for Vector2D {
+impl<T: Scalar> std::ops::Sub for Vector2D<T> {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self::Output {
-        Vector2D {
-            x: self.x - other.x,
-            y: self.y - other.y,
+        (Vector::from(self) - Vector::from(other)).into()
+    }
+}
+
+impl<T: Scalar> std::ops::Mul<T> for Vector2D<T> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self::Output {
+        self.scale(scalar)
+    }
+}
+
+// scalar * Vector2D has to be implemented for each concrete scalar type
+macro_rules! impl_scalar_mul_vector {
+    ($($t:ty),*) => {
+        $(
+            impl std::ops::Mul<Vector2D<$t>> for $t {
+                type Output = Vector2D<$t>;
+
+                fn mul(self, v: Vector2D<$t>) -> Self::Output {
+                    v.scale(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_mul_vector!(f32, f64, i8, i16, i32, i64, i128, isize, Fixed);
+
+// Vector * Vector is the dot (scalar) product
+impl<T: Scalar> std::ops::Mul for Vector2D<T> {
+    type Output = T;
+
+    fn mul(self, other: Self) -> Self::Output {
+        self.dot(&other)
+    }
+}
+
+impl<T: Scalar> std::ops::Div<T> for Vector2D<T> {
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self::Output {
+        (Vector::from(self) / scalar).into()
+    }
+}
+
+impl<T: Scalar> std::ops::AddAssign for Vector2D<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> std::ops::SubAssign for Vector2D<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Scalar> std::ops::MulAssign<T> for Vector2D<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        *self = self.scale(scalar);
+    }
+}
+
+impl<T: Scalar> std::ops::DivAssign<T> for Vector2D<T> {
+    fn div_assign(&mut self, scalar: T) {
+        *self = *self / scalar;
+    }
+}
+
+impl<T: Scalar> std::iter::Sum for Vector2D<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.map(Vector::from).sum::<Vector<T, 2>>().into()
+    }
+}
+
+impl<'a, T: Scalar> std::iter::Sum<&'a Vector2D<T>> for Vector2D<T> {
+    fn sum<I: Iterator<Item = &'a Vector2D<T>>>(iter: I) -> Self {
+        iter.map(|v| Vector::from(*v)).sum::<Vector<T, 2>>().into()
+    }
+}
+
+impl<T> std::ops::Index<usize> for Vector2D<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match index {
+            0 => &self.x,
+            1 => &self.y,
+            _ => panic!("index out of range for Vector2D: {}", index),
         }
     }
 }
 
+impl<T> std::ops::IndexMut<usize> for Vector2D<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        match index {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            _ => panic!("index out of range for Vector2D: {}", index),
+        }
+    }
+}
+
+impl<T> From<(T, T)> for Vector2D<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Vector2D { x, y }
+    }
+}
+
+impl<T> From<[T; 2]> for Vector2D<T> {
+    fn from([x, y]: [T; 2]) -> Self {
+        Vector2D { x, y }
+    }
+}
+
+impl<T> From<Vector2D<T>> for (T, T) {
+    fn from(v: Vector2D<T>) -> Self {
+        (v.x, v.y)
+    }
+}
+
+impl<T> From<Vector2D<T>> for [T; 2] {
+    fn from(v: Vector2D<T>) -> Self {
+        [v.x, v.y]
+    }
+}
+
 #[cfg(test)]
 mod tests_vector2d {
     use super::*;
@@ -86,7 +352,7 @@ mod tests_vector2d {
 
     #[test]
     fn default_trait() {
-        let v = Vector2D::default();
+        let v: Vector2D = Vector2D::default();
         assert_eq!(v.x, 0.0);
         assert_eq!(v.y, 0.0);
     }
@@ -99,6 +365,7 @@ mod tests_vector2d {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn is_clonable() {
         let v = Vector2D::new(1.0, 2.0);
         let v2 = v.clone();
@@ -166,51 +433,358 @@ mod tests_vector2d {
         assert_eq!(v3, Vector2D::new(4.0, 6.0));
     }
 
-    //     #[test]
-    //     fn subtraction() {
-    //         let v1 = Vector2D::new(1.0, 2.0);
-    //         let v2 = Vector2D::new(3.0, 4.5);
-    //         let v3 = v1 - v2;
+    #[test]
+    fn subtraction() {
+        let v1 = Vector2D::new(1.0, 2.0);
+        let v2 = Vector2D::new(3.0, 4.5);
+        let v3 = v1 - v2;
 
-    //         assert_eq!(v3, Vector2D::new(-2.0, -2.5));
-    //     }
+        assert_eq!(v3, Vector2D::new(-2.0, -2.5));
+    }
 
-    //     #[test]
-    //     fn scalar_product() {
-    //         let v1 = Vector2D::new(1.0, 2.0);
-    //         let v2: Vector2D = Vector2D::new(2.0, 3.0);
-    //         let product = v1 * v2;
-    //         assert_eq!(product, 8.0);
-    //     }
+    #[test]
+    fn scalar_product() {
+        let v1 = Vector2D::new(1.0, 2.0);
+        let v2: Vector2D = Vector2D::new(2.0, 3.0);
+        let product = v1 * v2;
+        assert_eq!(product, 8.0);
+    }
 
-    //     #[test]
-    //     fn multiplication_with_scalar() {
-    //         let v = Vector2D::new(1.0, 2.0);
-    //         let v_scaled = v * 2.0;
-    //         assert_eq!(v_scaled, Vector2D::new(2.0, 4.0));
-    //     }
+    #[test]
+    fn multiplication_with_scalar() {
+        let v = Vector2D::new(1.0, 2.0);
+        let v_scaled = v * 2.0;
+        assert_eq!(v_scaled, Vector2D::new(2.0, 4.0));
+    }
 
-    //     #[test]
-    //     #[should_panic]
-    //     fn index() {
-    //         let v = Vector2D::new(1.0, 2.0);
-    //         assert_eq!(v[0], 1.0);
-    //         assert_eq!(v[1], 2.0);
-    //         let _ = v[2];
-    //     }
+    #[test]
+    fn scalar_multiplied_by_vector() {
+        let v = Vector2D::new(1.0, 2.0);
+        let v_scaled = 2.0 * v;
+        assert_eq!(v_scaled, Vector2D::new(2.0, 4.0));
+    }
 
-    //     #[test]
-    //     fn index_mut() {
-    //         let mut v = Vector2D::new(1.0, 2.0);
-    //         v[0] = 3.0;
-    //         v[1] = 4.0;
-    //         assert_eq!(v, Vector2D::new(3.0, 4.0));
-    //     }
+    #[test]
+    fn division_by_scalar() {
+        let v = Vector2D::new(2.0, 5.0);
+        assert_eq!(v / 2.0, Vector2D::new(1.0, 2.5));
+    }
 
-    //     #[test]
-    //     fn convert_from_tuple() {
-    //         let v_tuple = (1.0, 2.0);
-    //         let v = Vector2D::from(v_tuple);
-    //         assert_eq!(v, Vector2D::new(1.0, 2.0));
-    //     }
+    #[test]
+    fn compound_assignment() {
+        let mut v = Vector2D::new(1.0, 2.0);
+
+        v += Vector2D::new(1.0, 1.0);
+        assert_eq!(v, Vector2D::new(2.0, 3.0));
+
+        v -= Vector2D::new(0.5, 1.0);
+        assert_eq!(v, Vector2D::new(1.5, 2.0));
+
+        v *= 2.0;
+        assert_eq!(v, Vector2D::new(3.0, 4.0));
+
+        v /= 4.0;
+        assert_eq!(v, Vector2D::new(0.75, 1.0));
+    }
+
+    #[test]
+    fn sum() {
+        let vectors = vec![
+            Vector2D::new(1.0, 2.0),
+            Vector2D::new(3.0, 4.0),
+            Vector2D::new(-1.0, 0.5),
+        ];
+
+        let total: Vector2D = vectors.iter().sum();
+        assert_eq!(total, Vector2D::new(3.0, 6.5));
+
+        let total: Vector2D = vectors.into_iter().sum();
+        assert_eq!(total, Vector2D::new(3.0, 6.5));
+
+        let empty: Vector2D = std::iter::empty::<Vector2D>().sum();
+        assert_eq!(empty, Vector2D::ZERO);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index() {
+        let v = Vector2D::new(1.0, 2.0);
+        assert_eq!(v[0], 1.0);
+        assert_eq!(v[1], 2.0);
+        let _ = v[2];
+    }
+
+    #[test]
+    fn index_mut() {
+        let mut v = Vector2D::new(1.0, 2.0);
+        v[0] = 3.0;
+        v[1] = 4.0;
+        assert_eq!(v, Vector2D::new(3.0, 4.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of range")]
+    fn index_mut_out_of_range() {
+        let mut v = Vector2D::new(1.0, 2.0);
+        v[2] = 3.0;
+    }
+
+    #[test]
+    fn convert_from_tuple() {
+        let v_tuple = (1.0, 2.0);
+        let v = Vector2D::from(v_tuple);
+        assert_eq!(v, Vector2D::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn convert_from_array() {
+        let v: Vector2D = [1.0, 2.0].into();
+        assert_eq!(v, Vector2D::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn convert_into_tuple_and_array() {
+        let v = Vector2D::new(1.0, 2.0);
+
+        let t: (f32, f32) = v.into();
+        assert_eq!(t, (1.0, 2.0));
+
+        let a: [f32; 2] = v.into();
+        assert_eq!(a, [1.0, 2.0]);
+    }
+
+    /*****************************************************/
+    /* Generic scalar types                              */
+    /*****************************************************/
+
+    #[test]
+    fn default_scalar_is_f32() {
+        let v: Vector2D = Vector2D::new(1.0, 2.0);
+        let x: f32 = v.x;
+        assert_eq!(x, 1.0);
+    }
+
+    #[test]
+    fn f64_components() {
+        let v = Vector2D::<f64>::new(1e-10, 1.0);
+        assert_eq!(v.scale(1e10), Vector2D::new(1.0, 1e10));
+        assert_eq!(Vector2D::<f64>::new(3.0, 4.0).length(), 5.0);
+    }
+
+    #[test]
+    fn integer_components() {
+        let a = Vector2D::<i32>::new(3, -4);
+        let b = Vector2D::new(1, 2);
+
+        assert_eq!(a + b, Vector2D::new(4, -2));
+        assert_eq!(a - b, Vector2D::new(2, -6));
+        assert_eq!(-a, Vector2D::new(-3, 4));
+        assert_eq!(a * b, -5);
+        assert_eq!(a * 2, Vector2D::new(6, -8));
+        assert_eq!(2 * a, Vector2D::new(6, -8));
+        assert_eq!(a / 2, Vector2D::new(1, -2));
+        assert_eq!(a.length_squared(), 25);
+        assert_eq!(Vector2D::<i64>::UNIT_Y, Vector2D::new(0, 1));
+    }
+
+    #[test]
+    fn fixed_point_components() {
+        let v = Vector2D::new(Fixed::from_int(3), Fixed::from_int(4));
+
+        assert_eq!(v.length(), Fixed::from_int(5));
+        assert_eq!(v * v, Fixed::from_int(25));
+        assert_eq!(
+            v.scale(Fixed::from_f64(0.5)),
+            Vector2D::new(Fixed::from_f64(1.5), Fixed::from_int(2))
+        );
+    }
+
+    #[test]
+    fn lossless_cast() {
+        let grid = Vector2D::<i16>::new(-3, 7);
+
+        assert_eq!(grid.cast::<i32>(), Vector2D::new(-3, 7));
+        assert_eq!(grid.cast::<f32>(), Vector2D::new(-3.0, 7.0));
+        assert_eq!(
+            grid.cast::<Fixed>(),
+            Vector2D::new(Fixed::from_int(-3), Fixed::from_int(7))
+        );
+
+        let v = Vector2D::<f32>::new(0.1, 2.5);
+        assert_eq!(v.cast::<f64>(), Vector2D::new(0.1f32 as f64, 2.5));
+
+        let fixed = Vector2D::new(Fixed::from_f64(0.25), Fixed::from_int(-2));
+        assert_eq!(fixed.cast::<f64>(), Vector2D::new(0.25, -2.0));
+    }
+
+    #[test]
+    fn lossy_cast() {
+        let v = Vector2D::<f64>::new(2.7, -2.7);
+        assert_eq!(v.cast_lossy::<i32>(), Vector2D::new(2, -2));
+        assert_eq!(v.cast_lossy::<f32>(), Vector2D::new(2.7f32, -2.7f32));
+
+        let big = Vector2D::<f64>::new(1e20, -1e20);
+        assert_eq!(big.cast_lossy::<i16>(), Vector2D::new(i16::MAX, i16::MIN));
+
+        let grid = Vector2D::<i64>::new(5, -1);
+        assert_eq!(grid.cast_lossy::<f32>(), Vector2D::new(5.0, -1.0));
+        assert_eq!(
+            grid.cast_lossy::<Fixed>(),
+            Vector2D::new(Fixed::from_int(5), Fixed::from_int(-1))
+        );
+    }
+
+    /*****************************************************/
+    /* Geometry                                          */
+    /*****************************************************/
+
+    use crate::assert_approx_eq;
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    const EPS: f32 = 1e-6;
+
+    #[test]
+    fn normalize() {
+        let n = Vector2D::new(3.0f32, 4.0).normalize().unwrap();
+        assert_approx_eq!(n, Vector2D::new(0.6, 0.8));
+        assert_approx_eq!(n.length(), 1.0);
+    }
+
+    #[test]
+    fn normalize_degenerate_vectors() {
+        assert_eq!(Vector2D::<f32>::ZERO.normalize(), None);
+        assert_eq!(Vector2D::new(f32::NAN, 1.0).normalize(), None);
+        assert_eq!(Vector2D::new(f32::INFINITY, 1.0).normalize(), None);
+        assert!(Vector2D::new(1e-30f32, 0.0).normalize().is_some());
+    }
+
+    #[test]
+    fn dot_and_perp_dot() {
+        let a = Vector2D::new(1.0f32, 2.0);
+        let b = Vector2D::new(3.0, -1.0);
+        assert_eq!(a.dot(&b), 1.0);
+        assert_eq!(a.dot(&b), a * b);
+        assert_eq!(a.perp_dot(&b), -7.0);
+        assert_eq!(Vector2D::<f32>::UNIT_X.perp_dot(&Vector2D::UNIT_Y), 1.0);
+        assert_eq!(Vector2D::new(2, 3).perp_dot(&Vector2D::new(4, 6)), 0);
+    }
+
+    #[test]
+    fn perpendicular() {
+        let v = Vector2D::new(2.0f32, 1.0);
+        assert_eq!(v.perpendicular(), Vector2D::new(-1.0, 2.0));
+        assert_eq!(v.dot(&v.perpendicular()), 0.0);
+        assert_eq!(Vector2D::<f32>::UNIT_X.perpendicular(), Vector2D::UNIT_Y);
+    }
+
+    #[test]
+    fn angle() {
+        assert_eq!(Vector2D::<f32>::UNIT_X.angle(), 0.0);
+        assert_approx_eq!(Vector2D::<f32>::UNIT_Y.angle(), FRAC_PI_2);
+        assert_approx_eq!(Vector2D::new(-1.0f32, 0.0).angle(), PI);
+        assert_approx_eq!(Vector2D::new(1.0f32, -1.0).angle(), -FRAC_PI_4);
+        assert_eq!(Vector2D::<f32>::ZERO.angle(), 0.0);
+        assert!(Vector2D::new(f32::NAN, 0.0).angle().is_nan());
+    }
+
+    #[test]
+    fn angle_between() {
+        let x = Vector2D::<f32>::UNIT_X;
+        assert_approx_eq!(x.angle_between(&Vector2D::UNIT_Y), FRAC_PI_2);
+        assert_approx_eq!(Vector2D::UNIT_Y.angle_between(&x), FRAC_PI_2);
+        assert_approx_eq!(x.angle_between(&Vector2D::new(-2.0, 0.0)), PI);
+        assert_approx_eq!(x.angle_between(&Vector2D::new(5.0, 5.0)), FRAC_PI_4);
+        assert_eq!(x.angle_between(&x), 0.0);
+        assert_eq!(x.angle_between(&Vector2D::ZERO), 0.0);
+    }
+
+    #[test]
+    fn rotate() {
+        let v = Vector2D::new(1.0f32, 0.0);
+        assert_approx_eq!(v.rotate(FRAC_PI_2), Vector2D::UNIT_Y, epsilon = EPS);
+        assert_approx_eq!(v.rotate(PI), -Vector2D::UNIT_X, epsilon = EPS);
+        assert_approx_eq!(v.rotate(-FRAC_PI_2), -Vector2D::UNIT_Y, epsilon = EPS);
+
+        let w = Vector2D::new(3.0f32, -2.0);
+        assert_approx_eq!(w.rotate(1.234).length(), w.length());
+        assert_eq!(Vector2D::<f32>::ZERO.rotate(1.0), Vector2D::ZERO);
+    }
+
+    #[test]
+    fn project_onto() {
+        let v = Vector2D::new(2.0f32, 3.0);
+        assert_eq!(
+            v.project_onto(&Vector2D::new(5.0, 0.0)),
+            Some(Vector2D::new(2.0, 0.0))
+        );
+        assert_approx_eq!(
+            v.project_onto(&Vector2D::new(1.0, 1.0)),
+            Some(Vector2D::new(2.5, 2.5))
+        );
+        assert_eq!(v.project_onto(&Vector2D::ZERO), None);
+        assert_eq!(v.project_onto(&Vector2D::new(f32::NAN, 0.0)), None);
+    }
+
+    #[test]
+    fn reflect() {
+        let v = Vector2D::new(1.0f32, -1.0);
+        assert_eq!(v.reflect(&Vector2D::UNIT_Y), Vector2D::new(1.0, 1.0));
+        assert_eq!(
+            v.reflect(&Vector2D::new(0.0, 10.0)),
+            Vector2D::new(1.0, 1.0)
+        );
+        assert_approx_eq!(v.reflect(&Vector2D::new(1.0, 1.0)), v);
+        assert_eq!(v.reflect(&Vector2D::ZERO), v);
+    }
+
+    #[test]
+    fn lerp() {
+        let a = Vector2D::new(0.0f32, 10.0);
+        let b = Vector2D::new(10.0, 20.0);
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 0.25), Vector2D::new(2.5, 12.5));
+        assert_eq!(a.lerp(&b, 2.0), Vector2D::new(20.0, 30.0));
+        assert_eq!(
+            Vector2D::new(0, 0).lerp(&Vector2D::new(4, 8), 2),
+            Vector2D::new(8, 16)
+        );
+        assert!(a.lerp(&b, f32::NAN).x.is_nan());
+    }
+
+    #[test]
+    fn distance() {
+        let a = Vector2D::new(1.0f32, 1.0);
+        let b = Vector2D::new(4.0, 5.0);
+        assert_eq!(a.distance(&b), 5.0);
+        assert_eq!(b.distance(&a), 5.0);
+        assert_eq!(a.distance_squared(&b), 25.0);
+        assert_eq!(a.distance(&a), 0.0);
+        assert!(a.distance(&Vector2D::new(f32::NAN, 0.0)).is_nan());
+    }
+
+    #[test]
+    fn clamp_length() {
+        let v = Vector2D::new(3.0f32, 4.0);
+        assert_eq!(v.clamp_length(0.0, 10.0), v);
+        assert_approx_eq!(v.clamp_length(0.0, 1.0), Vector2D::new(0.6, 0.8));
+        assert_approx_eq!(v.clamp_length(10.0, 20.0), Vector2D::new(6.0, 8.0));
+        assert_eq!(Vector2D::<f32>::ZERO.clamp_length(1.0, 2.0), Vector2D::ZERO);
+    }
+
+    #[test]
+    fn polar_conversion() {
+        let v = Vector2D::<f32>::from_polar(2.0, FRAC_PI_2);
+        assert_approx_eq!(v, Vector2D::new(0.0, 2.0), epsilon = EPS);
+
+        let (r, theta) = Vector2D::new(-3.0f32, 3.0).to_polar();
+        assert_approx_eq!(r, 18.0f32.sqrt());
+        assert_approx_eq!(theta, 3.0 * FRAC_PI_4);
+
+        let w = Vector2D::new(-1.5f64, -0.5);
+        let (r, theta) = w.to_polar();
+        assert_approx_eq!(Vector2D::from_polar(r, theta), w);
+
+        assert_eq!(Vector2D::<f32>::ZERO.to_polar(), (0.0, 0.0));
+    }
 }
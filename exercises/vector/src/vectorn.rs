@@ -0,0 +1,398 @@
+use crate::scalar::{Real, Scalar};
+use crate::vector2d::Vector2D;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Vector<T, const N: usize>(pub [T; N]);
+
+pub type Vector3D = Vector<f32, 3>;
+pub type Vector4D = Vector<f32, 4>;
+
+impl<T: Scalar, const N: usize> Vector<T, N> {
+    pub const ZERO: Self = Vector([T::ZERO; N]);
+
+    pub fn new(components: [T; N]) -> Self {
+        Vector(components)
+    }
+
+    // Unit vector along `axis`; panics if axis >= N
+    pub fn unit(axis: usize) -> Self {
+        let mut v = Self::ZERO;
+        v[axis] = T::ONE;
+        v
+    }
+
+    pub fn components(&self) -> &[T; N] {
+        &self.0
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(T::ZERO, |acc, (a, b)| acc + *a * *b)
+    }
+
+    pub fn length_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn scale(&self, scale: T) -> Self {
+        Vector(self.0.map(|c| c * scale))
+    }
+}
+
+impl<T: Real, const N: usize> Vector<T, N> {
+    pub fn length(&self) -> T {
+        self.length_squared().sqrt()
+    }
+}
+
+impl<T: Scalar> Vector<T, 2> {
+    pub const UNIT_X: Self = Vector([T::ONE, T::ZERO]);
+    pub const UNIT_Y: Self = Vector([T::ZERO, T::ONE]);
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn extend(&self, z: T) -> Vector<T, 3> {
+        Vector([self.0[0], self.0[1], z])
+    }
+}
+
+impl<T: Scalar> Vector<T, 3> {
+    pub const UNIT_X: Self = Vector([T::ONE, T::ZERO, T::ZERO]);
+    pub const UNIT_Y: Self = Vector([T::ZERO, T::ONE, T::ZERO]);
+    pub const UNIT_Z: Self = Vector([T::ZERO, T::ZERO, T::ONE]);
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        let [ax, ay, az] = self.0;
+        let [bx, by, bz] = other.0;
+        Vector([ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx])
+    }
+
+    pub fn extend(&self, w: T) -> Vector<T, 4> {
+        Vector([self.0[0], self.0[1], self.0[2], w])
+    }
+
+    pub fn truncate(&self) -> Vector<T, 2> {
+        Vector([self.0[0], self.0[1]])
+    }
+}
+
+impl<T: Scalar> Vector<T, 4> {
+    pub const UNIT_X: Self = Vector([T::ONE, T::ZERO, T::ZERO, T::ZERO]);
+    pub const UNIT_Y: Self = Vector([T::ZERO, T::ONE, T::ZERO, T::ZERO]);
+    pub const UNIT_Z: Self = Vector([T::ZERO, T::ZERO, T::ONE, T::ZERO]);
+    pub const UNIT_W: Self = Vector([T::ZERO, T::ZERO, T::ZERO, T::ONE]);
+
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+
+    pub fn w(&self) -> T {
+        self.0[3]
+    }
+
+    pub fn truncate(&self) -> Vector<T, 3> {
+        Vector([self.0[0], self.0[1], self.0[2]])
+    }
+}
+
+impl<T: Scalar, const N: usize> Default for Vector<T, N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::Neg for Vector<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Vector(self.0.map(|c| -c))
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::Add for Vector<T, N> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self += other;
+        self
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::Sub for Vector<T, N> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self::Output {
+        self -= other;
+        self
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::AddAssign for Vector<T, N> {
+    fn add_assign(&mut self, other: Self) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a += b;
+        }
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::SubAssign for Vector<T, N> {
+    fn sub_assign(&mut self, other: Self) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a -= b;
+        }
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::MulAssign<T> for Vector<T, N> {
+    fn mul_assign(&mut self, scalar: T) {
+        self.0.iter_mut().for_each(|c| *c *= scalar);
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::DivAssign<T> for Vector<T, N> {
+    fn div_assign(&mut self, scalar: T) {
+        self.0.iter_mut().for_each(|c| *c /= scalar);
+    }
+}
+
+// Vector * Vector is the dot (scalar) product, as for Vector2D
+impl<T: Scalar, const N: usize> std::ops::Mul for Vector<T, N> {
+    type Output = T;
+
+    fn mul(self, other: Self) -> Self::Output {
+        self.dot(&other)
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::Mul<T> for Vector<T, N> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self::Output {
+        self.scale(scalar)
+    }
+}
+
+impl<T: Scalar, const N: usize> std::ops::Div<T> for Vector<T, N> {
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self::Output {
+        Vector(self.0.map(|c| c / scalar))
+    }
+}
+
+impl<T: Scalar, const N: usize> std::iter::Sum for Vector<T, N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, v| acc + v)
+    }
+}
+
+impl<'a, T: Scalar, const N: usize> std::iter::Sum<&'a Vector<T, N>> for Vector<T, N> {
+    fn sum<I: Iterator<Item = &'a Vector<T, N>>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, v| acc + *v)
+    }
+}
+
+impl<T, const N: usize> std::ops::Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<T, const N: usize> std::ops::IndexMut<usize> for Vector<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for Vector<T, N> {
+    fn from(components: [T; N]) -> Self {
+        Vector(components)
+    }
+}
+
+impl<T, const N: usize> From<Vector<T, N>> for [T; N] {
+    fn from(v: Vector<T, N>) -> Self {
+        v.0
+    }
+}
+
+impl<T> From<(T, T, T)> for Vector<T, 3> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Vector([x, y, z])
+    }
+}
+
+impl<T> From<(T, T, T, T)> for Vector<T, 4> {
+    fn from((x, y, z, w): (T, T, T, T)) -> Self {
+        Vector([x, y, z, w])
+    }
+}
+
+impl<T> From<Vector2D<T>> for Vector<T, 2> {
+    fn from(v: Vector2D<T>) -> Self {
+        Vector([v.x, v.y])
+    }
+}
+
+impl<T> From<Vector<T, 2>> for Vector2D<T> {
+    fn from(Vector([x, y]): Vector<T, 2>) -> Self {
+        Vector2D { x, y }
+    }
+}
+
+#[cfg(test)]
+mod tests_vectorn {
+    use super::*;
+
+    #[test]
+    fn construction_and_accessors() {
+        let v = Vector3D::new([1.0, 2.0, 3.0]);
+        assert_eq!((v.x(), v.y(), v.z()), (1.0, 2.0, 3.0));
+        assert_eq!(v.components(), &[1.0, 2.0, 3.0]);
+
+        let v4 = Vector4D::from((1.0, 2.0, 3.0, 4.0));
+        assert_eq!(v4.w(), 4.0);
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(Vector3D::ZERO, Vector::new([0.0, 0.0, 0.0]));
+        assert_eq!(Vector3D::default(), Vector3D::ZERO);
+        assert_eq!(Vector3D::UNIT_Z, Vector::new([0.0, 0.0, 1.0]));
+        assert_eq!(Vector4D::UNIT_W, Vector4D::unit(3));
+        assert_eq!(Vector::<f64, 5>::unit(4).0, [0.0, 0.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn length() {
+        assert_eq!(Vector3D::new([2.0, 3.0, 6.0]).length(), 7.0);
+        assert_eq!(Vector4D::new([1.0, 1.0, 1.0, 1.0]).length(), 2.0);
+        assert_eq!(Vector::<f64, 3>::new([1.0, 2.0, 2.0]).length_squared(), 9.0);
+    }
+
+    #[test]
+    fn scale() {
+        let v = Vector3D::new([1.0, -2.0, 3.0]);
+        assert_eq!(v.scale(2.0), Vector::new([2.0, -4.0, 6.0]));
+        assert_eq!(v * 2.0, v.scale(2.0));
+        assert_eq!(v / 2.0, Vector::new([0.5, -1.0, 1.5]));
+    }
+
+    #[test]
+    fn operators() {
+        let a = Vector3D::new([1.0, 2.0, 3.0]);
+        let b = Vector3D::new([4.0, 5.0, 6.0]);
+
+        assert_eq!(-a, Vector::new([-1.0, -2.0, -3.0]));
+        assert_eq!(a + b, Vector::new([5.0, 7.0, 9.0]));
+        assert_eq!(b - a, Vector::new([3.0, 3.0, 3.0]));
+        assert_eq!(a * b, 32.0);
+        assert_eq!(a.dot(&b), 32.0);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+
+        let total: Vector3D = vec![a, b, a].into_iter().sum();
+        assert_eq!(total, Vector::new([6.0, 9.0, 12.0]));
+        let total: Vector3D = [a, b, a].iter().sum();
+        assert_eq!(total, Vector::new([6.0, 9.0, 12.0]));
+
+        c *= 2.0;
+        assert_eq!(c, Vector::new([8.0, 10.0, 12.0]));
+        c /= 4.0;
+        assert_eq!(c, Vector::new([2.0, 2.5, 3.0]));
+    }
+
+    #[test]
+    fn cross_product() {
+        assert_eq!(Vector3D::UNIT_X.cross(&Vector3D::UNIT_Y), Vector3D::UNIT_Z);
+        assert_eq!(Vector3D::UNIT_Y.cross(&Vector3D::UNIT_X), -Vector3D::UNIT_Z);
+
+        let a = Vector3D::new([1.0, 2.0, 3.0]);
+        let b = Vector3D::new([4.0, 5.0, 6.0]);
+        let c = a.cross(&b);
+        assert_eq!(c, Vector::new([-3.0, 6.0, -3.0]));
+        assert_eq!(c.dot(&a), 0.0);
+        assert_eq!(c.dot(&b), 0.0);
+    }
+
+    #[test]
+    fn index() {
+        let mut v = Vector4D::new([1.0, 2.0, 3.0, 4.0]);
+        v[3] = 5.0;
+        assert_eq!(v[0], 1.0);
+        assert_eq!(v[3], 5.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_range() {
+        let v = Vector3D::ZERO;
+        let _ = v[3];
+    }
+
+    #[test]
+    fn extend_and_truncate() {
+        let v2 = Vector::new([1.0f32, 2.0]);
+        let v3 = v2.extend(3.0);
+        let v4 = v3.extend(4.0);
+        assert_eq!(v4, Vector4D::new([1.0, 2.0, 3.0, 4.0]));
+        assert_eq!(v4.truncate(), v3);
+        assert_eq!(v3.truncate(), v2);
+    }
+
+    #[test]
+    fn conversions() {
+        let a: [f32; 3] = Vector3D::new([1.0, 2.0, 3.0]).into();
+        assert_eq!(a, [1.0, 2.0, 3.0]);
+        assert_eq!(
+            Vector3D::from([1.0, 2.0, 3.0]),
+            Vector3D::from((1.0, 2.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn vector2d_compatibility() {
+        let v = Vector2D::new(3.0, 4.0);
+        let generic: Vector<f32, 2> = v.into();
+
+        assert_eq!(generic.length(), v.length());
+        assert_eq!(generic.scale(2.0), v.scale(2.0).into());
+        assert_eq!(Vector2D::from(generic), v);
+        assert_eq!(Vector2D::from(Vector::<f32, 2>::UNIT_X), Vector2D::UNIT_X);
+        assert_eq!(Vector2D::from(Vector::<f32, 2>::UNIT_Y), Vector2D::UNIT_Y);
+        assert_eq!(Vector2D::from(Vector::<f32, 2>::ZERO), Vector2D::ZERO);
+    }
+}